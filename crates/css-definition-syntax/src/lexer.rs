@@ -0,0 +1,316 @@
+//! Tokenizer for concrete CSS values, following CSS Syntax Level 3 closely
+//! enough to match values against value definitions.
+//!
+//! Whitespace and comments are dropped.
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CssToken {
+    Ident(String),
+    /// A function name, including the opening parenthesis.
+    Function(String),
+    AtKeyword(String),
+    Hash(String),
+    String(String),
+    Url(String),
+    Number {
+        value: f64,
+        integer: bool,
+    },
+    Percentage(f64),
+    Dimension {
+        value: f64,
+        unit: String,
+    },
+    Delim(char),
+    Comma,
+    Colon,
+    Semicolon,
+    OpenParen,
+    CloseParen,
+    OpenSquare,
+    CloseSquare,
+    OpenCurly,
+    CloseCurly,
+}
+
+impl CssToken {
+    /// The token a single character stands for in a value definition.
+    pub fn from_char(c: char) -> Self {
+        match c {
+            ',' => Self::Comma,
+            ':' => Self::Colon,
+            ';' => Self::Semicolon,
+            '(' => Self::OpenParen,
+            ')' => Self::CloseParen,
+            '[' => Self::OpenSquare,
+            ']' => Self::CloseSquare,
+            '{' => Self::OpenCurly,
+            '}' => Self::CloseCurly,
+            c => Self::Delim(c),
+        }
+    }
+
+    /// The numeric value of number, percentage and dimension tokens.
+    pub fn numeric_value(&self) -> Option<f64> {
+        match self {
+            Self::Number { value, .. }
+            | Self::Percentage(value)
+            | Self::Dimension { value, .. } => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+fn is_name_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_' || !c.is_ascii()
+}
+
+fn is_name_char(c: char) -> bool {
+    is_name_start(c) || c.is_ascii_digit() || c == '-'
+}
+
+struct Lexer {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Lexer {
+    fn at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn starts_ident(&self, offset: usize) -> bool {
+        match self.at(offset) {
+            Some('-') => {
+                self.at(offset + 1)
+                    .is_some_and(|c| is_name_start(c) || c == '-')
+                    || self.at(offset + 1) == Some('\\')
+            }
+            Some('\\') => self.at(offset + 1).is_some_and(|c| c != '\n'),
+            Some(c) => is_name_start(c),
+            None => false,
+        }
+    }
+
+    fn starts_number(&self) -> bool {
+        let digit_at = |offset| self.at(offset).is_some_and(|c: char| c.is_ascii_digit());
+        match self.at(0) {
+            Some('+' | '-') => digit_at(1) || (self.at(1) == Some('.') && digit_at(2)),
+            Some('.') => digit_at(1),
+            Some(c) => c.is_ascii_digit(),
+            None => false,
+        }
+    }
+
+    fn name(&mut self) -> String {
+        let mut name = String::new();
+        while let Some(c) = self.at(0) {
+            if c == '\\' && self.at(1).is_some_and(|c| c != '\n') {
+                name.push(self.at(1).unwrap_or_default());
+                self.pos += 2;
+            } else if is_name_char(c) {
+                name.push(c);
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        name
+    }
+
+    fn number(&mut self) -> (f64, bool) {
+        let start = self.pos;
+        let mut integer = true;
+        if matches!(self.at(0), Some('+' | '-')) {
+            self.pos += 1;
+        }
+        let digits = |lexer: &mut Self| {
+            while lexer.at(0).is_some_and(|c| c.is_ascii_digit()) {
+                lexer.pos += 1;
+            }
+        };
+        digits(self);
+        if self.at(0) == Some('.') && self.at(1).is_some_and(|c| c.is_ascii_digit()) {
+            integer = false;
+            self.pos += 1;
+            digits(self);
+        }
+        if matches!(self.at(0), Some('e' | 'E'))
+            && (self.at(1).is_some_and(|c| c.is_ascii_digit())
+                || (matches!(self.at(1), Some('+' | '-'))
+                    && self.at(2).is_some_and(|c| c.is_ascii_digit())))
+        {
+            integer = false;
+            self.pos += 2;
+            digits(self);
+        }
+        let text = self.chars[start..self.pos].iter().collect::<String>();
+        (text.parse().unwrap_or_default(), integer)
+    }
+
+    fn string(&mut self, quote: char) -> String {
+        self.pos += 1;
+        let mut value = String::new();
+        while let Some(c) = self.at(0) {
+            self.pos += 1;
+            match c {
+                c if c == quote => break,
+                '\\' => {
+                    if let Some(escaped) = self.at(0) {
+                        value.push(escaped);
+                        self.pos += 1;
+                    }
+                }
+                c => value.push(c),
+            }
+        }
+        value
+    }
+
+    fn url(&mut self) -> String {
+        let mut value = String::new();
+        while let Some(c) = self.at(0) {
+            self.pos += 1;
+            if c == ')' {
+                break;
+            }
+            value.push(c);
+        }
+        value.trim().to_string()
+    }
+
+    fn skip_whitespace_and_comments(&mut self) {
+        loop {
+            match (self.at(0), self.at(1)) {
+                (Some(c), _) if c.is_whitespace() => self.pos += 1,
+                (Some('/'), Some('*')) => {
+                    self.pos += 2;
+                    while self.at(0).is_some()
+                        && !(self.at(0) == Some('*') && self.at(1) == Some('/'))
+                    {
+                        self.pos += 1;
+                    }
+                    self.pos = (self.pos + 2).min(self.chars.len());
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn next_token(&mut self) -> Option<CssToken> {
+        self.skip_whitespace_and_comments();
+        let c = self.at(0)?;
+        Some(match c {
+            '"' | '\'' => CssToken::String(self.string(c)),
+            '#' if self.at(1).is_some_and(|c| is_name_char(c) || c == '\\') => {
+                self.pos += 1;
+                CssToken::Hash(self.name())
+            }
+            '@' if self.starts_ident(1) => {
+                self.pos += 1;
+                CssToken::AtKeyword(self.name())
+            }
+            _ if self.starts_number() => {
+                let (value, integer) = self.number();
+                if self.at(0) == Some('%') {
+                    self.pos += 1;
+                    CssToken::Percentage(value)
+                } else if self.starts_ident(0) {
+                    CssToken::Dimension {
+                        value,
+                        unit: self.name(),
+                    }
+                } else {
+                    CssToken::Number { value, integer }
+                }
+            }
+            _ if self.starts_ident(0) => {
+                let name = self.name();
+                if self.at(0) != Some('(') {
+                    return Some(CssToken::Ident(name));
+                }
+                self.pos += 1;
+                let quoted = {
+                    let start = self.pos;
+                    self.skip_whitespace_and_comments();
+                    let quoted = matches!(self.at(0), Some('"' | '\''));
+                    self.pos = start;
+                    quoted
+                };
+                if name.eq_ignore_ascii_case("url") && !quoted {
+                    CssToken::Url(self.url())
+                } else {
+                    CssToken::Function(name)
+                }
+            }
+            c => {
+                self.pos += 1;
+                CssToken::from_char(c)
+            }
+        })
+    }
+}
+
+/// Splits a CSS value into tokens.
+pub fn tokenize(value: &str) -> Vec<CssToken> {
+    let mut lexer = Lexer {
+        chars: value.chars().collect(),
+        pos: 0,
+    };
+    std::iter::from_fn(|| lexer.next_token()).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize("1px solid #fff, calc(50% - 2.5em) /* x */ 'a\\'b' url(foo.png) -3"),
+            vec![
+                CssToken::Dimension {
+                    value: 1.0,
+                    unit: "px".into()
+                },
+                CssToken::Ident("solid".into()),
+                CssToken::Hash("fff".into()),
+                CssToken::Comma,
+                CssToken::Function("calc".into()),
+                CssToken::Percentage(50.0),
+                CssToken::Delim('-'),
+                CssToken::Dimension {
+                    value: 2.5,
+                    unit: "em".into()
+                },
+                CssToken::CloseParen,
+                CssToken::String("a'b".into()),
+                CssToken::Url("foo.png".into()),
+                CssToken::Number {
+                    value: -3.0,
+                    integer: true
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_idents() {
+        assert_eq!(
+            tokenize("--foo -webkit-box @media 1e3 .5"),
+            vec![
+                CssToken::Ident("--foo".into()),
+                CssToken::Ident("-webkit-box".into()),
+                CssToken::AtKeyword("media".into()),
+                CssToken::Number {
+                    value: 1000.0,
+                    integer: false
+                },
+                CssToken::Number {
+                    value: 0.5,
+                    integer: false
+                },
+            ]
+        );
+    }
+}
@@ -1,6 +1,9 @@
 pub mod error;
 pub mod generate;
+pub mod lexer;
+pub mod matcher;
 pub mod parser;
+pub mod railroad;
 pub mod tokenizer;
 pub mod walk;
 
@@ -0,0 +1,862 @@
+//! Matching of concrete CSS values against value definitions, like csstree's
+//! `lexer.match`.
+//!
+//! Matching works on the tokens of the value and tries every way a
+//! definition can consume them, so it backtracks through multipliers,
+//! combinators and optional commas. Referenced types and properties are
+//! resolved through a [`SyntaxResolver`], except for the primitive types
+//! (`<length>`, `<number>`, `<string>`, …) that are matched directly.
+
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::error::SyntaxDefinitionError;
+use crate::lexer::{CssToken, tokenize};
+use crate::parser::{BooleanExpr, CombinatorType, Group, IntI, Multiplier, Node, Range, Type};
+
+/// How deeply type and property references are followed before giving up.
+const MAX_DEPTH: usize = 64;
+
+const LENGTH_UNITS: &[&str] = &[
+    "px", "em", "rem", "ex", "rex", "cap", "rcap", "ch", "rch", "ic", "ric", "lh", "rlh", "vw",
+    "vh", "vi", "vb", "vmin", "vmax", "cqw", "cqh", "cqi", "cqb", "cqmin", "cqmax", "cm", "mm",
+    "q", "in", "pt", "pc",
+];
+const VIEWPORT_UNITS: &[&str] = &["vw", "vh", "vi", "vb", "vmin", "vmax"];
+const ANGLE_UNITS: &[&str] = &["deg", "grad", "rad", "turn"];
+const TIME_UNITS: &[&str] = &["s", "ms"];
+const FREQUENCY_UNITS: &[&str] = &["hz", "khz"];
+const RESOLUTION_UNITS: &[&str] = &["dpi", "dpcm", "dppx", "x"];
+const FLEX_UNITS: &[&str] = &["fr"];
+
+/// Functions that can stand in for any numeric value.
+const MATH_FUNCTIONS: &[&str] = &[
+    "calc",
+    "min",
+    "max",
+    "clamp",
+    "round",
+    "mod",
+    "rem",
+    "sin",
+    "cos",
+    "tan",
+    "asin",
+    "acos",
+    "atan",
+    "atan2",
+    "pow",
+    "sqrt",
+    "hypot",
+    "log",
+    "exp",
+    "abs",
+    "sign",
+    "calc-size",
+];
+
+const NAMED_COLORS: &[&str] = &[
+    "aliceblue",
+    "antiquewhite",
+    "aqua",
+    "aquamarine",
+    "azure",
+    "beige",
+    "bisque",
+    "black",
+    "blanchedalmond",
+    "blue",
+    "blueviolet",
+    "brown",
+    "burlywood",
+    "cadetblue",
+    "chartreuse",
+    "chocolate",
+    "coral",
+    "cornflowerblue",
+    "cornsilk",
+    "crimson",
+    "cyan",
+    "darkblue",
+    "darkcyan",
+    "darkgoldenrod",
+    "darkgray",
+    "darkgreen",
+    "darkgrey",
+    "darkkhaki",
+    "darkmagenta",
+    "darkolivegreen",
+    "darkorange",
+    "darkorchid",
+    "darkred",
+    "darksalmon",
+    "darkseagreen",
+    "darkslateblue",
+    "darkslategray",
+    "darkslategrey",
+    "darkturquoise",
+    "darkviolet",
+    "deeppink",
+    "deepskyblue",
+    "dimgray",
+    "dimgrey",
+    "dodgerblue",
+    "firebrick",
+    "floralwhite",
+    "forestgreen",
+    "fuchsia",
+    "gainsboro",
+    "ghostwhite",
+    "gold",
+    "goldenrod",
+    "gray",
+    "green",
+    "greenyellow",
+    "grey",
+    "honeydew",
+    "hotpink",
+    "indianred",
+    "indigo",
+    "ivory",
+    "khaki",
+    "lavender",
+    "lavenderblush",
+    "lawngreen",
+    "lemonchiffon",
+    "lightblue",
+    "lightcoral",
+    "lightcyan",
+    "lightgoldenrodyellow",
+    "lightgray",
+    "lightgreen",
+    "lightgrey",
+    "lightpink",
+    "lightsalmon",
+    "lightseagreen",
+    "lightskyblue",
+    "lightslategray",
+    "lightslategrey",
+    "lightsteelblue",
+    "lightyellow",
+    "lime",
+    "limegreen",
+    "linen",
+    "magenta",
+    "maroon",
+    "mediumaquamarine",
+    "mediumblue",
+    "mediumorchid",
+    "mediumpurple",
+    "mediumseagreen",
+    "mediumslateblue",
+    "mediumspringgreen",
+    "mediumturquoise",
+    "mediumvioletred",
+    "midnightblue",
+    "mintcream",
+    "mistyrose",
+    "moccasin",
+    "navajowhite",
+    "navy",
+    "oldlace",
+    "olive",
+    "olivedrab",
+    "orange",
+    "orangered",
+    "orchid",
+    "palegoldenrod",
+    "palegreen",
+    "paleturquoise",
+    "palevioletred",
+    "papayawhip",
+    "peachpuff",
+    "peru",
+    "pink",
+    "plum",
+    "powderblue",
+    "purple",
+    "rebeccapurple",
+    "red",
+    "rosybrown",
+    "royalblue",
+    "saddlebrown",
+    "salmon",
+    "sandybrown",
+    "seagreen",
+    "seashell",
+    "sienna",
+    "silver",
+    "skyblue",
+    "slateblue",
+    "slategray",
+    "slategrey",
+    "snow",
+    "springgreen",
+    "steelblue",
+    "tan",
+    "teal",
+    "thistle",
+    "tomato",
+    "turquoise",
+    "violet",
+    "wheat",
+    "white",
+    "whitesmoke",
+    "yellow",
+    "yellowgreen",
+];
+
+const SYSTEM_COLORS: &[&str] = &[
+    "accentcolor",
+    "accentcolortext",
+    "activetext",
+    "buttonborder",
+    "buttonface",
+    "buttontext",
+    "canvas",
+    "canvastext",
+    "field",
+    "fieldtext",
+    "graytext",
+    "highlight",
+    "highlighttext",
+    "linktext",
+    "mark",
+    "marktext",
+    "selecteditem",
+    "selecteditemtext",
+    "visitedtext",
+];
+
+const DEPRECATED_COLORS: &[&str] = &[
+    "activeborder",
+    "activecaption",
+    "appworkspace",
+    "background",
+    "buttonhighlight",
+    "buttonshadow",
+    "captiontext",
+    "inactiveborder",
+    "inactivecaption",
+    "inactivecaptiontext",
+    "infobackground",
+    "infotext",
+    "menu",
+    "menutext",
+    "scrollbar",
+    "threeddarkshadow",
+    "threedface",
+    "threedhighlight",
+    "threedlightshadow",
+    "threedshadow",
+    "window",
+    "windowframe",
+    "windowtext",
+];
+
+/// Looks up the definitions referenced from a value definition.
+pub trait SyntaxResolver {
+    /// The value definition of the property `name` (without `<'…'>`).
+    fn property(&self, name: &str) -> Option<&Node>;
+    /// The value definition of the type `name` (without `<…>`). Function
+    /// types are named with parentheses, e.g. `rgb()`.
+    fn type_syntax(&self, name: &str) -> Option<&Node>;
+}
+
+/// Returns whether `value` matches the value definition `syntax`.
+///
+/// Fails with [`SyntaxDefinitionError::UnknownType`] if a type that has to be
+/// tried can't be resolved.
+pub fn matches(
+    syntax: &Node,
+    value: &str,
+    resolver: &impl SyntaxResolver,
+) -> Result<bool, SyntaxDefinitionError> {
+    let tokens = tokenize(value);
+    let matcher = Matcher {
+        tokens: &tokens,
+        resolver,
+    };
+    Ok(matcher.node(syntax, 0, 0)?.contains(&tokens.len()))
+}
+
+type Ends = BTreeSet<usize>;
+
+struct Matcher<'a, R> {
+    tokens: &'a [CssToken],
+    resolver: &'a R,
+}
+
+impl<R: SyntaxResolver> Matcher<'_, R> {
+    /// All positions after `node` matched the tokens starting at `pos`.
+    fn node(&self, node: &Node, pos: usize, depth: usize) -> Result<Ends, SyntaxDefinitionError> {
+        if depth > MAX_DEPTH {
+            return Ok(Ends::new());
+        }
+        Ok(match node {
+            Node::Group(group) => self.group(group, pos, depth)?,
+            Node::Multiplier(multiplier) => self.multiplier(multiplier, pos, depth)?,
+            Node::Keyword(keyword) => self.single(pos, |token| {
+                matches!(token, CssToken::Ident(ident) if ident.eq_ignore_ascii_case(&keyword.name))
+            }),
+            Node::Function(function) => self.single(pos, |token| {
+                matches!(token, CssToken::Function(name) if name.eq_ignore_ascii_case(&function.name))
+            }),
+            Node::AtKeyword(at_keyword) => self.single(pos, |token| {
+                matches!(token, CssToken::AtKeyword(name) if name.eq_ignore_ascii_case(&at_keyword.name))
+            }),
+            Node::Token(token) => {
+                let expected = CssToken::from_char(token.value);
+                self.single(pos, |token| *token == expected)
+            }
+            Node::String(string) => {
+                let value = string.value.trim_matches(['\'', '"']);
+                let mut chars = value.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => {
+                        let expected = CssToken::from_char(c);
+                        self.single(pos, |token| *token == expected)
+                    }
+                    _ => self.single(pos, |token| {
+                        matches!(token, CssToken::Ident(ident) if ident.eq_ignore_ascii_case(value))
+                    }),
+                }
+            }
+            Node::Comma => self.comma(pos),
+            Node::Property(property) => match self.resolver.property(&property.name) {
+                Some(syntax) => self.node(syntax, pos, depth + 1)?,
+                None => return Err(SyntaxDefinitionError::UnknownType(format!("'{}'", property.name))),
+            },
+            Node::Type(typ) => self.typ(typ, pos, depth)?,
+            Node::BooleanExpr(BooleanExpr { term }) => self.boolean_expr(term, pos, depth)?,
+            Node::Range(_) | Node::Combinator(_) | Node::Spaces(_) => Ends::new(),
+        })
+    }
+
+    fn single(&self, pos: usize, test: impl Fn(&CssToken) -> bool) -> Ends {
+        self.tokens
+            .get(pos)
+            .filter(|token| test(token))
+            .map(|_| pos + 1)
+            .into_iter()
+            .collect()
+    }
+
+    /// Commas are optional at the start and end of a comma-separated
+    /// context, where an optional term next to them is left out.
+    fn comma(&self, pos: usize) -> Ends {
+        let is_context_start = |pos: usize| {
+            pos == 0
+                || matches!(
+                    self.tokens.get(pos - 1),
+                    Some(
+                        CssToken::Comma
+                            | CssToken::Function(_)
+                            | CssToken::OpenParen
+                            | CssToken::OpenSquare
+                    )
+                )
+        };
+        let is_context_end = |pos: usize| {
+            matches!(
+                self.tokens.get(pos),
+                None | Some(CssToken::Comma | CssToken::CloseParen | CssToken::CloseSquare)
+            )
+        };
+        if self.tokens.get(pos) == Some(&CssToken::Comma) {
+            if is_context_start(pos) || is_context_end(pos + 1) {
+                Ends::new()
+            } else {
+                Ends::from([pos + 1])
+            }
+        } else if is_context_start(pos) || is_context_end(pos) {
+            Ends::from([pos])
+        } else {
+            Ends::new()
+        }
+    }
+
+    fn group(
+        &self,
+        group: &Group,
+        pos: usize,
+        depth: usize,
+    ) -> Result<Ends, SyntaxDefinitionError> {
+        let mut ends = match group.combinator {
+            CombinatorType::Space => {
+                let mut ends = Ends::from([pos]);
+                for term in &group.terms {
+                    let mut next = Ends::new();
+                    for end in ends {
+                        next.extend(self.node(term, end, depth)?);
+                    }
+                    ends = next;
+                    if ends.is_empty() {
+                        break;
+                    }
+                }
+                ends
+            }
+            CombinatorType::VerticalLine => {
+                let mut ends = Ends::new();
+                for term in &group.terms {
+                    ends.extend(self.node(term, pos, depth)?);
+                }
+                ends
+            }
+            CombinatorType::DoubleVerticalLine | CombinatorType::DoubleAmpersand => self
+                .any_order(
+                    &group.terms,
+                    pos,
+                    group.combinator == CombinatorType::DoubleAmpersand,
+                    depth,
+                )?,
+        };
+        if group.disallow_empty {
+            ends.remove(&pos);
+        }
+        Ok(ends)
+    }
+
+    /// Matches `terms` in any order, each at most once, and all of them if
+    /// `all`.
+    ///
+    /// Explores the states (terms used so far, position) instead of the
+    /// orders of the terms, so every state and every term at a position is
+    /// only matched once.
+    fn any_order(
+        &self,
+        terms: &[Node],
+        pos: usize,
+        all: bool,
+        depth: usize,
+    ) -> Result<Ends, SyntaxDefinitionError> {
+        let mut ends = Ends::new();
+        let mut term_ends = HashMap::<(usize, usize), Ends>::new();
+        let mut seen = HashSet::new();
+        let mut states = vec![(vec![false; terms.len()], pos)];
+        while let Some((used, pos)) = states.pop() {
+            if seen.contains(&(used.clone(), pos)) {
+                continue;
+            }
+            if (all && used.iter().all(|u| *u)) || (!all && used.iter().any(|u| *u)) {
+                ends.insert(pos);
+            }
+            for (i, term) in terms.iter().enumerate() {
+                if used[i] {
+                    continue;
+                }
+                let term_ends = match term_ends.entry((i, pos)) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => entry.insert(self.node(term, pos, depth)?),
+                };
+                for &end in term_ends.iter() {
+                    let mut next = used.clone();
+                    next[i] = true;
+                    states.push((next, end));
+                }
+            }
+            seen.insert((used, pos));
+        }
+        Ok(ends)
+    }
+
+    fn multiplier(
+        &self,
+        Multiplier {
+            comma,
+            min,
+            max,
+            term,
+        }: &Multiplier,
+        pos: usize,
+        depth: usize,
+    ) -> Result<Ends, SyntaxDefinitionError> {
+        let (min, max) = (
+            *min as usize,
+            if *max == 0 { usize::MAX } else { *max as usize },
+        );
+        let mut ends = Ends::new();
+        if min == 0 {
+            ends.insert(pos);
+        }
+        let mut frontier = Ends::from([pos]);
+        let mut count = 0;
+        while count < max && !frontier.is_empty() {
+            count += 1;
+            let mut next = Ends::new();
+            for start in frontier {
+                let from = if *comma && count > 1 {
+                    match self.tokens.get(start) {
+                        Some(CssToken::Comma) => start + 1,
+                        _ => continue,
+                    }
+                } else {
+                    start
+                };
+                for end in self.node(term, from, depth)? {
+                    // past the minimum, only repetitions that consume
+                    // something make progress
+                    if end > start || count <= min {
+                        next.insert(end);
+                    }
+                }
+            }
+            if count >= min {
+                ends.extend(&next);
+            }
+            frontier = next;
+        }
+        Ok(ends)
+    }
+
+    fn typ(&self, typ: &Type, pos: usize, depth: usize) -> Result<Ends, SyntaxDefinitionError> {
+        let range = match typ.opts.as_deref() {
+            Some(Node::Range(range)) => Some(range),
+            _ => None,
+        };
+        if let Some(ends) = self.primitive(&typ.name, range, pos) {
+            return Ok(ends);
+        }
+        match self.resolver.type_syntax(&typ.name) {
+            Some(syntax) => self.node(syntax, pos, depth + 1),
+            None => Err(SyntaxDefinitionError::UnknownType(typ.name.clone())),
+        }
+    }
+
+    /// Matches the primitive types, or returns `None` for other types.
+    fn primitive(&self, name: &str, range: Option<&Range>, pos: usize) -> Option<Ends> {
+        let token = self.tokens.get(pos);
+        let in_range = |token: &CssToken| match (range, token.numeric_value()) {
+            (Some(Range { min, max, .. }), Some(value)) => {
+                let above_min = match min {
+                    IntI::Finite(min) => value >= f64::from(*min),
+                    IntI::NegativeInfinity => true,
+                    IntI::Infinity => false,
+                };
+                let below_max = match max {
+                    IntI::Finite(max) => value <= f64::from(*max),
+                    IntI::Infinity => true,
+                    IntI::NegativeInfinity => false,
+                };
+                above_min && below_max
+            }
+            _ => true,
+        };
+        let dimension = |units: &[&str]| {
+            self.single(pos, |token| match token {
+                CssToken::Dimension { unit, .. } => {
+                    let unit = unit.to_ascii_lowercase();
+                    in_range(token) && units.contains(&unit.as_str())
+                }
+                _ => false,
+            })
+        };
+        let numeric =
+            |ends: Ends| -> Ends { ends.into_iter().chain(self.math_function(pos)).collect() };
+        let ident_in = |names: &[&str]| {
+            self.single(pos, |token| {
+                matches!(token, CssToken::Ident(ident) if names.contains(&ident.to_ascii_lowercase().as_str()))
+            })
+        };
+        Some(match name {
+            "ident" | "custom-ident" => self.single(pos, |token| matches!(token, CssToken::Ident(_))),
+            "dashed-ident" | "custom-property-name" => self.single(
+                pos,
+                |token| matches!(token, CssToken::Ident(ident) if ident.starts_with("--")),
+            ),
+            "string" => self.single(pos, |token| matches!(token, CssToken::String(_))),
+            "url" => match token {
+                Some(CssToken::Url(_)) => Ends::from([pos + 1]),
+                Some(CssToken::Function(name))
+                    if name.eq_ignore_ascii_case("url") || name.eq_ignore_ascii_case("src") =>
+                {
+                    self.balanced_function(pos).into_iter().collect()
+                }
+                _ => Ends::new(),
+            },
+            "hex-color" => self.single(pos, |token| {
+                matches!(token, CssToken::Hash(hash)
+                    if matches!(hash.len(), 3 | 4 | 6 | 8) && hash.chars().all(|c| c.is_ascii_hexdigit()))
+            }),
+            "named-color" => ident_in(NAMED_COLORS),
+            "system-color" => ident_in(SYSTEM_COLORS),
+            "deprecated-color" => ident_in(DEPRECATED_COLORS),
+            "number" => numeric(self.single(pos, |token| {
+                matches!(token, CssToken::Number { .. }) && in_range(token)
+            })),
+            "integer" => numeric(self.single(pos, |token| {
+                matches!(token, CssToken::Number { integer: true, .. }) && in_range(token)
+            })),
+            "zero" => self.single(pos, |token| matches!(token, CssToken::Number { value, .. } if *value == 0.0)),
+            "percentage" => numeric(self.single(pos, |token| {
+                matches!(token, CssToken::Percentage(_)) && in_range(token)
+            })),
+            "dimension" => numeric(self.single(pos, |token| matches!(token, CssToken::Dimension { .. }))),
+            "length" => numeric(
+                self.single(pos, |token| match token {
+                    CssToken::Dimension { unit, .. } => {
+                        let unit = unit.to_ascii_lowercase();
+                        let viewport = ["s", "l", "d"]
+                            .iter()
+                            .filter_map(|prefix| unit.strip_prefix(prefix))
+                            .any(|unit| VIEWPORT_UNITS.contains(&unit));
+                        in_range(token) && (LENGTH_UNITS.contains(&unit.as_str()) || viewport)
+                    }
+                    CssToken::Number { value, .. } => *value == 0.0,
+                    _ => false,
+                }),
+            ),
+            "angle" => numeric(dimension(ANGLE_UNITS)),
+            "time" => numeric(dimension(TIME_UNITS)),
+            "frequency" => numeric(dimension(FREQUENCY_UNITS)),
+            "resolution" => numeric(dimension(RESOLUTION_UNITS)),
+            "flex" => numeric(dimension(FLEX_UNITS)),
+            "length-percentage" | "angle-percentage" | "time-percentage"
+            | "frequency-percentage" | "number-percentage" => {
+                let base = name.trim_end_matches("-percentage");
+                let mut ends = self.primitive(base, range, pos)?;
+                ends.extend(self.primitive("percentage", range, pos)?);
+                ends
+            }
+            "declaration-value" | "any-value" => self.any_value(pos),
+            _ => return None,
+        })
+    }
+
+    /// The end of a math function call at `pos`, if there is one.
+    fn math_function(&self, pos: usize) -> Option<usize> {
+        match self.tokens.get(pos) {
+            Some(CssToken::Function(name))
+                if MATH_FUNCTIONS.contains(&name.to_ascii_lowercase().as_str()) =>
+            {
+                self.balanced_function(pos)
+            }
+            _ => None,
+        }
+    }
+
+    /// The position after the parenthesis closing the function at `pos`.
+    fn balanced_function(&self, pos: usize) -> Option<usize> {
+        let mut open = 0;
+        for (i, token) in self.tokens.iter().enumerate().skip(pos) {
+            match token {
+                CssToken::Function(_) | CssToken::OpenParen => open += 1,
+                CssToken::CloseParen => {
+                    open -= 1;
+                    if open == 0 {
+                        return Some(i + 1);
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Any non-empty run of tokens with balanced brackets.
+    fn any_value(&self, pos: usize) -> Ends {
+        let mut ends = Ends::new();
+        let mut stack = vec![];
+        for (i, token) in self.tokens.iter().enumerate().skip(pos) {
+            match token {
+                CssToken::Function(_) | CssToken::OpenParen => stack.push(CssToken::CloseParen),
+                CssToken::OpenSquare => stack.push(CssToken::CloseSquare),
+                CssToken::OpenCurly => stack.push(CssToken::CloseCurly),
+                CssToken::CloseParen | CssToken::CloseSquare | CssToken::CloseCurly
+                    if stack.pop().as_ref() != Some(token) =>
+                {
+                    break;
+                }
+                _ => {}
+            }
+            if stack.is_empty() {
+                ends.insert(i + 1);
+            }
+        }
+        ends
+    }
+
+    /// `<boolean-expr[ term ]>`: `not` a single operand, or operands joined
+    /// only by `and` or only by `or`. Operands are `term` or a parenthesized
+    /// expression.
+    fn boolean_expr(
+        &self,
+        term: &Node,
+        pos: usize,
+        depth: usize,
+    ) -> Result<Ends, SyntaxDefinitionError> {
+        let operand = |pos: usize| -> Result<Ends, SyntaxDefinitionError> {
+            let mut ends = self.node(term, pos, depth + 1)?;
+            if self.tokens.get(pos) == Some(&CssToken::OpenParen) {
+                for end in self.boolean_expr(term, pos + 1, depth + 1)? {
+                    if self.tokens.get(end) == Some(&CssToken::CloseParen) {
+                        ends.insert(end + 1);
+                    }
+                }
+            }
+            Ok(ends)
+        };
+        let keyword = |pos: usize, keyword: &str| matches!(self.tokens.get(pos), Some(CssToken::Ident(ident)) if ident.eq_ignore_ascii_case(keyword));
+        if keyword(pos, "not") {
+            return operand(pos + 1);
+        }
+        let mut ends = Ends::new();
+        for first in operand(pos)? {
+            ends.insert(first);
+            for joiner in ["and", "or"] {
+                let mut frontier = Ends::from([first]);
+                while !frontier.is_empty() {
+                    let mut next = Ends::new();
+                    for end in frontier {
+                        if keyword(end, joiner) {
+                            next.extend(operand(end + 1)?);
+                        }
+                    }
+                    ends.extend(&next);
+                    frontier = next;
+                }
+            }
+        }
+        Ok(ends)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::parser::parse;
+
+    struct Definitions {
+        properties: HashMap<&'static str, Node>,
+        types: HashMap<&'static str, Node>,
+    }
+
+    impl SyntaxResolver for Definitions {
+        fn property(&self, name: &str) -> Option<&Node> {
+            self.properties.get(name)
+        }
+
+        fn type_syntax(&self, name: &str) -> Option<&Node> {
+            self.types.get(name)
+        }
+    }
+
+    fn definitions() -> Definitions {
+        let parse_all = |defs: &[(&'static str, &str)]| {
+            defs.iter()
+                .map(|(name, syntax)| (*name, parse(syntax).unwrap()))
+                .collect()
+        };
+        Definitions {
+            properties: parse_all(&[
+                ("margin-top", "<length-percentage> | auto"),
+                ("margin", "<'margin-top'>{1,4}"),
+            ]),
+            types: parse_all(&[
+                (
+                    "color",
+                    "<hex-color> | <named-color> | <rgb()> | currentcolor",
+                ),
+                (
+                    "rgb()",
+                    "rgb( <percentage>{3} [ / <alpha-value> ]? ) | rgb( <number>{3} [ / <alpha-value> ]? )",
+                ),
+                ("alpha-value", "<number> | <percentage>"),
+                ("line-style", "none | hidden | dotted | dashed | solid"),
+            ]),
+        }
+    }
+
+    fn check(syntax: &str, value: &str) -> bool {
+        matches(&parse(syntax).unwrap(), value, &definitions()).unwrap()
+    }
+
+    #[test]
+    fn test_keywords_and_types() {
+        assert!(check("auto | <length>", "AUTO"));
+        assert!(check("auto | <length>", "10px"));
+        assert!(check("auto | <length>", "0"));
+        assert!(!check("auto | <length>", "10"));
+        assert!(!check("auto | <length>", "10deg"));
+        assert!(check("<length>", "calc(100% - 2em)"));
+        assert!(check("<integer [0,∞]>", "3"));
+        assert!(!check("<integer [0,∞]>", "-3"));
+        assert!(!check("<integer>", "1.5"));
+        assert!(check("<'margin'>", "1px auto 10%"));
+        assert!(!check("<'margin'>", "1px auto 10% 0 0"));
+    }
+
+    #[test]
+    fn test_combinators() {
+        let border = "<line-width> || <line-style> || <color>";
+        let definitions = Definitions {
+            types: [(
+                "line-width",
+                parse("<length> | thin | medium | thick").unwrap(),
+            )]
+            .into_iter()
+            .chain(definitions().types)
+            .collect(),
+            ..definitions()
+        };
+        let check = |value| matches(&parse(border).unwrap(), value, &definitions).unwrap();
+        assert!(check("1px solid red"));
+        assert!(check("#fff dashed"));
+        assert!(check("thick"));
+        assert!(!check("solid solid"));
+        assert!(!check(""));
+
+        assert!(self::check("a && b && c", "c a b"));
+        assert!(!self::check("a && b && c", "c a"));
+        assert!(self::check("a [ b | c ]? d", "a d"));
+    }
+
+    #[test]
+    fn test_many_terms_in_any_order() {
+        // Every term can also match nothing, so exploring the orders of the
+        // terms would not finish.
+        let syntax = (0..12)
+            .map(|i| format!("[ k{i} ]?"))
+            .collect::<Vec<_>>()
+            .join(" || ");
+        assert!(check(&syntax, "k11 k3 k0"));
+        assert!(check(&syntax, ""));
+        assert!(!check(&syntax, "k3 k3"));
+        let syntax = (0..12)
+            .map(|i| format!("k{i}?"))
+            .collect::<Vec<_>>()
+            .join(" && ");
+        assert!(check(&syntax, "k5 k1"));
+    }
+
+    #[test]
+    fn test_multipliers_and_commas() {
+        assert!(check("<length>#", "1px, 2px,3px"));
+        assert!(!check("<length>#", "1px 2px"));
+        assert!(!check("<length>#", "1px,"));
+        assert!(check("<length>{2,3}", "1px 2px"));
+        assert!(!check("<length>{2,3}", "1px"));
+        assert!(check("[ <length> | auto ]*", ""));
+        assert!(check("<color>", "rgb(10% 20% 30% / 0.5)"));
+        assert!(!check("<color>", "rgb(10% 20%)"));
+        assert!(check("[ <angle> | to left ]? , <color>#", "red, blue"));
+        assert!(check("[ <angle> | to left ]? , <color>#", "45deg, red"));
+        assert!(!check("[ <angle> | to left ]? , <color>#", ", red"));
+    }
+
+    #[test]
+    fn test_boolean_expr() {
+        let syntax = "<boolean-expr[ <ident> ]>";
+        assert!(check(syntax, "a and (b or c)"));
+        assert!(check(syntax, "not a"));
+        assert!(!check(syntax, "a and b or c"));
+    }
+
+    #[test]
+    fn test_unknown_type() {
+        assert!(matches(&parse("<unknown>").unwrap(), "a", &definitions()).is_err());
+    }
+}
@@ -0,0 +1,479 @@
+//! Railroad diagrams for value definitions, rendered as SVG.
+//!
+//! Keywords, functions and literal tokens are drawn as rounded terminals,
+//! types and properties as rectangular non-terminals that may link to their
+//! definition. Alternatives branch vertically, multipliers loop back (with a
+//! comma on the loop for `#`), and `||`/`&&` groups loop through their
+//! alternatives with a note on the loop.
+
+use std::fmt::Write;
+
+use crate::error::SyntaxDefinitionError;
+use crate::generate::generate;
+use crate::parser::{CombinatorType, Group, Multiplier, Node};
+
+const CHAR_WIDTH: i32 = 8;
+const BOX_PADDING: i32 = 10;
+const BOX_HEIGHT: i32 = 22;
+const ARC_RADIUS: i32 = 10;
+const GAP: i32 = 10;
+const VERTICAL_GAP: i32 = 8;
+const LABEL_HEIGHT: i32 = 14;
+const MARGIN: i32 = 20;
+
+/// The diagram is embedded inline, where a `<style>` inside the SVG applies
+/// to the whole page, so every selector is scoped to the diagram.
+const STYLE: &str = ".railroad-diagram path{fill:none;stroke:currentColor;stroke-width:1.5}\
+.railroad-diagram rect{fill:none;stroke:currentColor;stroke-width:1.5}\
+.railroad-diagram text{font:13px monospace;fill:currentColor;text-anchor:middle}\
+.railroad-diagram text.label{font-size:11px}";
+
+#[derive(Debug)]
+enum Diagram {
+    Skip,
+    Terminal(String),
+    NonTerminal(String, Option<String>),
+    Sequence(Vec<Diagram>),
+    /// Alternatives stacked vertically, with `main` on the line through.
+    Choice {
+        main: usize,
+        branches: Vec<Diagram>,
+    },
+    /// `item` once, then again through the loop below it as often as
+    /// needed.
+    OneOrMore {
+        item: Box<Diagram>,
+        separator: Option<Box<Diagram>>,
+        label: Option<String>,
+    },
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn text_width(text: &str) -> i32 {
+    text.chars().count() as i32 * CHAR_WIDTH
+}
+
+impl Diagram {
+    fn optional(item: Diagram) -> Self {
+        Self::Choice {
+            main: 1,
+            branches: vec![Self::Skip, item],
+        }
+    }
+
+    fn width(&self) -> i32 {
+        match self {
+            Self::Skip => 0,
+            Self::Terminal(text) | Self::NonTerminal(text, _) => text_width(text) + 2 * BOX_PADDING,
+            Self::Sequence(items) => {
+                items.iter().map(Self::width).sum::<i32>()
+                    + GAP * (items.len().saturating_sub(1) as i32)
+            }
+            Self::Choice { branches, .. } => {
+                branches.iter().map(Self::width).max().unwrap_or_default() + 4 * ARC_RADIUS
+            }
+            Self::OneOrMore {
+                item,
+                separator,
+                label,
+            } => {
+                let inner = item
+                    .width()
+                    .max(separator.as_ref().map_or(0, |s| s.width()))
+                    .max(label.as_deref().map_or(0, text_width));
+                inner + 4 * ARC_RADIUS
+            }
+        }
+    }
+
+    /// The extent above the line the diagram is entered and left on.
+    fn up(&self) -> i32 {
+        match self {
+            Self::Skip => 0,
+            Self::Terminal(_) | Self::NonTerminal(..) => BOX_HEIGHT / 2,
+            Self::Sequence(items) => items.iter().map(Self::up).max().unwrap_or_default(),
+            Self::Choice { branches, .. } => self
+                .branch_offsets()
+                .iter()
+                .zip(branches)
+                .map(|(offset, branch)| branch.up() - offset)
+                .max()
+                .unwrap_or_default(),
+            Self::OneOrMore { item, .. } => item.up(),
+        }
+    }
+
+    /// The extent below the line the diagram is entered and left on.
+    fn down(&self) -> i32 {
+        match self {
+            Self::Skip => 0,
+            Self::Terminal(_) | Self::NonTerminal(..) => BOX_HEIGHT / 2,
+            Self::Sequence(items) => items.iter().map(Self::down).max().unwrap_or_default(),
+            Self::Choice { branches, .. } => self
+                .branch_offsets()
+                .iter()
+                .zip(branches)
+                .map(|(offset, branch)| branch.down() + offset)
+                .max()
+                .unwrap_or_default(),
+            Self::OneOrMore {
+                separator, label, ..
+            } => {
+                self.loop_offset()
+                    + separator.as_ref().map_or(0, |s| s.down())
+                    + label.as_ref().map_or(0, |_| LABEL_HEIGHT)
+            }
+        }
+    }
+
+    /// The vertical offsets of the branches of a choice from its line.
+    fn branch_offsets(&self) -> Vec<i32> {
+        let Self::Choice { main, branches } = self else {
+            return vec![];
+        };
+        let mut offsets = vec![0; branches.len()];
+        for i in (0..*main).rev() {
+            offsets[i] =
+                (offsets[i + 1] - branches[i + 1].up() - VERTICAL_GAP - branches[i].down())
+                    .min(offsets[i + 1] - 2 * ARC_RADIUS);
+        }
+        for i in main + 1..branches.len() {
+            offsets[i] =
+                (offsets[i - 1] + branches[i - 1].down() + VERTICAL_GAP + branches[i].up())
+                    .max(offsets[i - 1] + 2 * ARC_RADIUS);
+        }
+        offsets
+    }
+
+    /// The vertical offset of the loop of a one-or-more from its line.
+    fn loop_offset(&self) -> i32 {
+        let Self::OneOrMore {
+            item, separator, ..
+        } = self
+        else {
+            return 0;
+        };
+        (item.down() + VERTICAL_GAP + separator.as_ref().map_or(0, |s| s.up())).max(2 * ARC_RADIUS)
+    }
+
+    /// Draws the diagram entered at `(x, y)`.
+    fn draw(&self, x: i32, y: i32, out: &mut String) -> std::fmt::Result {
+        let r = ARC_RADIUS;
+        match self {
+            Self::Skip => {}
+            Self::Terminal(text) => {
+                let w = self.width();
+                write!(
+                    out,
+                    r#"<g class="terminal"><rect x="{x}" y="{}" width="{w}" height="{BOX_HEIGHT}" rx="{r}"/><text x="{}" y="{}">{}</text></g>"#,
+                    y - BOX_HEIGHT / 2,
+                    x + w / 2,
+                    y + 4,
+                    escape(text)
+                )?;
+            }
+            Self::NonTerminal(text, href) => {
+                let w = self.width();
+                if let Some(href) = href {
+                    write!(out, r#"<a href="{}">"#, escape(href))?;
+                }
+                write!(
+                    out,
+                    r#"<g class="non-terminal"><rect x="{x}" y="{}" width="{w}" height="{BOX_HEIGHT}"/><text x="{}" y="{}">{}</text></g>"#,
+                    y - BOX_HEIGHT / 2,
+                    x + w / 2,
+                    y + 4,
+                    escape(text)
+                )?;
+                if href.is_some() {
+                    out.push_str("</a>");
+                }
+            }
+            Self::Sequence(items) => {
+                let mut x = x;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(out, r#"<path d="M{x} {y}h{GAP}"/>"#)?;
+                        x += GAP;
+                    }
+                    item.draw(x, y, out)?;
+                    x += item.width();
+                }
+            }
+            Self::Choice { branches, .. } => {
+                let w = self.width();
+                for (branch, dy) in branches.iter().zip(self.branch_offsets()) {
+                    let bw = branch.width();
+                    let end = x + w - 2 * r;
+                    match dy {
+                        0 => write!(out, r#"<path d="M{x} {y}h{}"/>"#, 2 * r)?,
+                        dy if dy > 0 => write!(
+                            out,
+                            r#"<path d="M{x} {y}a{r} {r} 0 0 1 {r} {r}V{}a{r} {r} 0 0 0 {r} {r}"/>"#,
+                            y + dy - r
+                        )?,
+                        dy => write!(
+                            out,
+                            r#"<path d="M{x} {y}a{r} {r} 0 0 0 {r} -{r}V{}a{r} {r} 0 0 1 {r} -{r}"/>"#,
+                            y + dy + r
+                        )?,
+                    }
+                    branch.draw(x + 2 * r, y + dy, out)?;
+                    match dy {
+                        0 => write!(out, r#"<path d="M{} {y}H{}"/>"#, x + 2 * r + bw, x + w)?,
+                        dy if dy > 0 => write!(
+                            out,
+                            r#"<path d="M{} {}H{end}a{r} {r} 0 0 0 {r} -{r}V{}a{r} {r} 0 0 1 {r} -{r}"/>"#,
+                            x + 2 * r + bw,
+                            y + dy,
+                            y + r
+                        )?,
+                        dy => write!(
+                            out,
+                            r#"<path d="M{} {}H{end}a{r} {r} 0 0 1 {r} {r}V{}a{r} {r} 0 0 0 {r} {r}"/>"#,
+                            x + 2 * r + bw,
+                            y + dy,
+                            y - r
+                        )?,
+                    }
+                }
+            }
+            Self::OneOrMore {
+                item,
+                separator,
+                label,
+            } => {
+                let w = self.width();
+                let iw = item.width();
+                let ix = x + (w - iw) / 2;
+                let ly = y + self.loop_offset();
+                write!(
+                    out,
+                    r#"<path d="M{x} {y}H{ix}M{} {y}H{}"/>"#,
+                    ix + iw,
+                    x + w
+                )?;
+                item.draw(ix, y, out)?;
+                write!(
+                    out,
+                    r#"<path d="M{} {y}a{r} {r} 0 0 1 {r} {r}V{}a{r} {r} 0 0 1 -{r} {r}H{}a{r} {r} 0 0 1 -{r} -{r}V{}a{r} {r} 0 0 1 {r} -{r}"/>"#,
+                    x + w - 2 * r,
+                    ly - r,
+                    x + 2 * r,
+                    y + r
+                )?;
+                let mut below = ly;
+                if let Some(separator) = separator {
+                    let sw = separator.width();
+                    separator.draw(x + (w - sw) / 2, ly, out)?;
+                    below += separator.down();
+                }
+                if let Some(label) = label {
+                    write!(
+                        out,
+                        r#"<text class="label" x="{}" y="{}">{}</text>"#,
+                        x + w / 2,
+                        below + LABEL_HEIGHT - 2,
+                        escape(label)
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn repetition_label(min: u32, max: u32) -> Option<String> {
+    match (min, max) {
+        (0 | 1, 0) => None,
+        (min, max) if min == max => Some(format!("{min} times")),
+        (min, 0) => Some(format!("{min} or more times")),
+        (min, max) => Some(format!("{min} to {max} times")),
+    }
+}
+
+fn diagram(
+    node: &Node,
+    link: &impl Fn(&Node) -> Option<String>,
+) -> Result<Diagram, SyntaxDefinitionError> {
+    Ok(match node {
+        Node::Keyword(keyword) => Diagram::Terminal(keyword.name.clone()),
+        Node::Function(function) => Diagram::Terminal(format!("{}(", function.name)),
+        Node::Token(token) => Diagram::Terminal(token.value.to_string()),
+        Node::String(string) => Diagram::Terminal(string.value.clone()),
+        Node::Comma => Diagram::Terminal(",".to_string()),
+        Node::AtKeyword(_) => Diagram::Terminal(generate(node, Default::default())?),
+        Node::Type(_) | Node::Property(_) | Node::BooleanExpr(_) => {
+            Diagram::NonTerminal(generate(node, Default::default())?, link(node))
+        }
+        Node::Multiplier(Multiplier {
+            comma,
+            min,
+            max,
+            term,
+        }) => {
+            let item = diagram(term, link)?;
+            if (*min, *max) == (1, 1) {
+                return Ok(item);
+            }
+            if (*min, *max) == (0, 1) {
+                return Ok(Diagram::optional(item));
+            }
+            let repeated = Diagram::OneOrMore {
+                item: Box::new(item),
+                separator: comma.then(|| Box::new(Diagram::Terminal(",".to_string()))),
+                label: repetition_label(*min, *max),
+            };
+            if *min == 0 {
+                Diagram::optional(repeated)
+            } else {
+                repeated
+            }
+        }
+        Node::Group(Group {
+            terms, combinator, ..
+        }) => {
+            let mut items = terms
+                .iter()
+                .map(|term| diagram(term, link))
+                .collect::<Result<Vec<_>, _>>()?;
+            if items.len() == 1 {
+                return Ok(items.remove(0));
+            }
+            let any_order = |items, label: &str| Diagram::OneOrMore {
+                item: Box::new(Diagram::Choice {
+                    main: 0,
+                    branches: items,
+                }),
+                separator: None,
+                label: Some(label.to_string()),
+            };
+            match combinator {
+                CombinatorType::Space => Diagram::Sequence(items),
+                CombinatorType::VerticalLine => Diagram::Choice {
+                    main: 0,
+                    branches: items,
+                },
+                CombinatorType::DoubleVerticalLine => {
+                    any_order(items, "one or more, each once, any order")
+                }
+                CombinatorType::DoubleAmpersand => any_order(items, "all, any order"),
+            }
+        }
+        Node::Range(_) | Node::Combinator(_) | Node::Spaces(_) => Diagram::Skip,
+    })
+}
+
+/// Renders `syntax` as a railroad diagram SVG, titled `title`.
+///
+/// `link` returns the URL a type or property links to, if any.
+pub fn railroad(
+    title: &str,
+    syntax: &Node,
+    link: impl Fn(&Node) -> Option<String>,
+) -> Result<String, SyntaxDefinitionError> {
+    let diagram = diagram(syntax, &link)?;
+    let width = diagram.width() + 2 * MARGIN + 2 * GAP;
+    let height = diagram.up() + diagram.down() + 2 * MARGIN;
+    let y = MARGIN + diagram.up();
+    let mut out = String::new();
+    let mut draw = || -> std::fmt::Result {
+        write!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" class="railroad-diagram" width="{width}" height="{height}" viewBox="0 0 {width} {height}" role="img"><title>{}</title><style>{STYLE}</style>"#,
+            escape(title)
+        )?;
+        let end = width - MARGIN;
+        write!(
+            out,
+            r#"<path d="M{MARGIN} {}v{BOX_HEIGHT}M{MARGIN} {y}h{GAP}"/>"#,
+            y - BOX_HEIGHT / 2
+        )?;
+        diagram.draw(MARGIN + GAP, y, &mut out)?;
+        write!(
+            out,
+            r#"<path d="M{} {y}H{end}M{end} {}v{BOX_HEIGHT}"/></svg>"#,
+            end - GAP,
+            y - BOX_HEIGHT / 2
+        )
+    };
+    draw().expect("writing to a String never fails");
+    Ok(out)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parser::parse;
+
+    fn render(syntax: &str) -> String {
+        railroad("test", &parse(syntax).unwrap(), |node| match node {
+            Node::Type(typ) => Some(format!("/types/{}", typ.name)),
+            _ => None,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_railroad_terms() {
+        let svg = render("auto | <length> && foo( <'width'> )");
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" class="railroad-diagram""#)
+        );
+        assert!(svg.ends_with("</svg>"));
+        assert!(svg.contains(r#"<a href="/types/length"><g class="non-terminal">"#));
+        assert!(svg.contains(">&lt;'width'&gt;</text>"));
+        assert!(svg.contains(">foo(</text>"));
+        assert!(svg.contains(">all, any order</text>"));
+        assert!(svg.contains(r#"<g class="terminal">"#));
+    }
+
+    #[test]
+    fn test_railroad_multipliers() {
+        let svg = render("<length>#{2,4}");
+        assert!(svg.contains(">,</text>"));
+        assert!(svg.contains(">2 to 4 times</text>"));
+        assert!(!render("<length>+").contains(r#"class="label""#));
+    }
+
+    #[test]
+    fn test_railroad_style_is_scoped() {
+        let svg = render("a | b");
+        let style = svg
+            .split_once("<style>")
+            .and_then(|(_, rest)| rest.split_once("</style>"))
+            .map(|(style, _)| style)
+            .unwrap();
+        let rules = style.split_terminator('}').collect::<Vec<_>>();
+        assert!(!rules.is_empty());
+        for rule in rules {
+            let (selectors, _) = rule.split_once('{').unwrap();
+            for selector in selectors.split(',') {
+                assert!(
+                    selector.trim().starts_with(".railroad-diagram "),
+                    "unscoped selector: {selector}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_layout() {
+        let choice = diagram(&parse("a | b | c").unwrap(), &|_| None).unwrap();
+        assert_eq!(choice.up(), BOX_HEIGHT / 2);
+        assert_eq!(choice.branch_offsets(), vec![0, 30, 60]);
+        assert_eq!(choice.down(), 60 + BOX_HEIGHT / 2);
+        let optional = diagram(&parse("a?").unwrap(), &|_| None).unwrap();
+        assert_eq!(optional.branch_offsets(), vec![-2 * ARC_RADIUS, 0]);
+        assert_eq!(
+            optional.width(),
+            text_width("a") + 2 * BOX_PADDING + 4 * ARC_RADIUS
+        );
+    }
+}
@@ -256,3 +256,15 @@ impl std::convert::TryFrom<String> for InterfaceType {
         value.parse()
     }
 }
+
+/// WebIDL definitions from `@webref/idl`.
+///
+/// Definitions are keyed by name. Each entry holds the main definition and
+/// any partial definitions of the same name, in spec order.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct WebrefIdl {
+    pub definitions: std::collections::BTreeMap<String, Vec<IdlFragmentInSpec>>,
+    /// The mixins included by each interface (`A includes B;`).
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub includes: std::collections::BTreeMap<String, Vec<String>>,
+}
@@ -1,3 +1,4 @@
 pub mod error;
+pub mod matcher;
 pub mod syntax;
 pub mod syntax_provider;
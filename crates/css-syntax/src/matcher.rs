@@ -0,0 +1,128 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::LazyLock;
+
+use css_definition_syntax::matcher::{SyntaxResolver, matches};
+use css_definition_syntax::parser::{Node, parse};
+
+use crate::error::SyntaxError;
+use crate::syntax::CSS_REF;
+use crate::syntax_provider::SyntaxProvider;
+
+const CSS_WIDE_KEYWORDS: &[&str] = &["initial", "inherit", "unset", "revert", "revert-layer"];
+
+/// Functions substituted before a value is parsed, so any value containing
+/// them may be valid.
+const SUBSTITUTION_FUNCTIONS: &[&str] = &["var(", "env(", "attr(", "if(", "inherit("];
+
+/// The parsed value definitions of the global scope of the webref data.
+struct WebrefDefinitions {
+    properties: HashMap<&'static str, Node>,
+    types: HashMap<&'static str, Node>,
+}
+
+impl SyntaxResolver for WebrefDefinitions {
+    fn property(&self, name: &str) -> Option<&Node> {
+        self.properties.get(name)
+    }
+
+    fn type_syntax(&self, name: &str) -> Option<&Node> {
+        self.types.get(name)
+    }
+}
+
+fn parse_all<T: SyntaxProvider>(
+    items: Option<&'static BTreeMap<String, T>>,
+) -> impl Iterator<Item = (&'static str, Node)> {
+    items.into_iter().flatten().filter_map(|(name, item)| {
+        let syntax = item.syntax().as_deref()?;
+        Some((name.as_str(), parse(syntax).ok()?))
+    })
+}
+
+static DEFINITIONS: LazyLock<WebrefDefinitions> = LazyLock::new(|| {
+    let global = "__global_scope__";
+    let mut properties: HashMap<_, _> = parse_all(CSS_REF.properties.get(global)).collect();
+    for (name, property) in CSS_REF.properties.get(global).into_iter().flatten() {
+        if let Some(alias_of) = &property.legacy_alias_of
+            && let Some(syntax) = properties.get(alias_of.as_str()).cloned()
+        {
+            properties.entry(name.as_str()).or_insert(syntax);
+        }
+    }
+    let types = parse_all(CSS_REF.types.get(global))
+        .chain(parse_all(CSS_REF.functions.get(global)))
+        .collect();
+    WebrefDefinitions { properties, types }
+});
+
+/// Returns whether `value` is valid for the property `name`, according to
+/// the webref data. A trailing `!important` is ignored.
+///
+/// CSS-wide keywords are always valid, and values using substitution
+/// functions like `var()` are assumed to be valid. Custom properties accept
+/// anything.
+pub fn match_property(name: &str, value: &str) -> Result<bool, SyntaxError> {
+    let value = value.trim();
+    let value = match value.to_ascii_lowercase().rfind("!important") {
+        Some(i) if value[i..].len() == "!important".len() => value[..i].trim_end(),
+        _ => value,
+    };
+    if name.starts_with("--") {
+        return Ok(true);
+    }
+    if CSS_WIDE_KEYWORDS
+        .iter()
+        .any(|keyword| value.eq_ignore_ascii_case(keyword))
+    {
+        return Ok(true);
+    }
+    let lowercase = value.to_ascii_lowercase();
+    if SUBSTITUTION_FUNCTIONS
+        .iter()
+        .any(|function| lowercase.contains(function))
+    {
+        return Ok(true);
+    }
+    let syntax = DEFINITIONS
+        .property(&name.to_ascii_lowercase())
+        .ok_or(SyntaxError::NoSyntaxFound)?;
+    Ok(matches(syntax, value, &*DEFINITIONS)?)
+}
+
+/// Whether webref knows the property `name`.
+pub fn is_known_property(name: &str) -> bool {
+    name.starts_with("--") || DEFINITIONS.property(&name.to_ascii_lowercase()).is_some()
+}
+
+/// Whether webref knows the at-rule `name` (including the `@`).
+pub fn is_known_at_rule(name: &str) -> bool {
+    CSS_REF
+        .atrules
+        .get("__global_scope__")
+        .is_some_and(|atrules| atrules.contains_key(&name.to_ascii_lowercase()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_match_property() -> Result<(), SyntaxError> {
+        assert!(match_property("color", "rebeccapurple")?);
+        assert!(match_property("color", "rgb(0 0 0 / 50%)")?);
+        assert!(!match_property("color", "10px")?);
+        assert!(match_property("border", "1px solid red")?);
+        assert!(!match_property("border", "1px solid solid")?);
+        assert!(match_property("margin", "0 auto !important")?);
+        assert!(match_property("width", "var(--w)")?);
+        assert!(match_property("width", "inherit")?);
+        assert!(match_property("--anything", "{ ; }")?);
+        assert!(matches!(
+            match_property("colour", "red"),
+            Err(SyntaxError::NoSyntaxFound)
+        ));
+        assert!(is_known_at_rule("@container"));
+        assert!(!is_known_at_rule("@meida"));
+        Ok(())
+    }
+}
@@ -7,16 +7,17 @@ use std::sync::LazyLock;
 
 use css_definition_syntax::generate::{self, GenerateOptions};
 use css_definition_syntax::parser::{CombinatorType, Multiplier, Node, Type, parse};
+use css_definition_syntax::railroad::railroad;
 use css_definition_syntax::walk::{WalkOptions, walk};
 use css_syntax_types::{CssValuesItem, SpecLink, WebrefCss};
 #[cfg(all(feature = "rari", not(any(feature = "doctest", test))))]
 use rari_types::globals::data_dir;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::error::SyntaxError;
 use crate::syntax_provider::SyntaxProvider;
 
-static CSS_REF: LazyLock<WebrefCss> = LazyLock::new(|| {
+pub(crate) static CSS_REF: LazyLock<WebrefCss> = LazyLock::new(|| {
     #[cfg(any(feature = "doctest", test))]
     {
         let package_path = std::path::Path::new("package");
@@ -158,6 +159,35 @@ fn skip(name: &str) -> bool {
     name == "color" || name == "gradient"
 }
 
+/// The MDN URL of the property `name` as written in a syntax, e.g.
+/// `<'border-width'>`.
+fn property_url(locale_str: &str, name: &str) -> Option<String> {
+    let slug = name.strip_prefix("<'")?.strip_suffix("'>")?;
+    Some(format!(
+        "/{locale_str}/docs/Web/CSS/Reference/Properties/{slug}"
+    ))
+}
+
+/// The slug of the MDN page of the type `name` as written in a syntax, e.g.
+/// `<length [0,∞]>`, relative to the CSS values reference.
+fn type_slug(name: &str) -> &str {
+    match name {
+        "<color>" => "color_value",
+        "<position>" => "position_value",
+        "<contrast-color()>" => "color_value/contrast-color",
+        "<device-cmyk()>" => "color_value/device-cmyk",
+        "<light-dark()>" => "color_value/light-dark",
+        name if name.starts_with('<') && name.ends_with('>') => {
+            &name[1..name.find(" [").or(name.find('[')).unwrap_or(name.len() - 1)]
+        }
+        name => &name[0..name.find(" [").or(name.find('[')).unwrap_or(name.len())],
+    }
+}
+
+fn type_url(locale_str: &str, slug: &str) -> String {
+    format!("/{locale_str}/docs/Web/CSS/Reference/Values/{slug}")
+}
+
 pub fn get_syntax(typ: CssType, scope: Option<&str>) -> SyntaxLine {
     get_syntax_internal(typ, scope, false)
 }
@@ -334,29 +364,15 @@ impl SyntaxRenderer<'_> {
             Node::Token(_) if name == ")" => r#"<span class="token function">)</span>"#.into(),
             Node::Property(_) => {
                 let encoded = html_escape::encode_safe(name);
-                if name.starts_with("<'") && name.ends_with("'>") {
-                    let slug = &name[2..name.len() - 2];
-                    format!(
-                        r#"<a href="/{}/docs/Web/CSS/Reference/Properties/{slug}"><span class="token property">{encoded}</span></a>"#,
-                        self.locale_str
-                    )
+                if let Some(url) = property_url(self.locale_str, name) {
+                    format!(r#"<a href="{url}"><span class="token property">{encoded}</span></a>"#)
                 } else {
                     format!(r#"<span class="token property">{encoded}</span>"#)
                 }
             }
             Node::Type(typ) => {
                 let encoded = html_escape::encode_safe(name);
-                let slug = match name {
-                    "<color>" => "color_value",
-                    "<position>" => "position_value",
-                    "<contrast-color()>" => "color_value/contrast-color",
-                    "<device-cmyk()>" => "color_value/device-cmyk",
-                    "<light-dark()>" => "color_value/light-dark",
-                    name if name.starts_with('<') && name.ends_with('>') => {
-                        &name[1..name.find(" [").or(name.find('[')).unwrap_or(name.len() - 1)]
-                    }
-                    name => &name[0..name.find(" [").or(name.find('[')).unwrap_or(name.len())],
-                };
+                let slug = type_slug(name);
 
                 if !skip(slug)
                     && (self.constituents.contains(node)
@@ -370,8 +386,8 @@ impl SyntaxRenderer<'_> {
                 } else {
                     // FIXME: this should have the class type but to be compatible we use property
                     format!(
-                        r#"<a href="/{}/docs/Web/CSS/Reference/Values/{slug}"><span class="token property">{encoded}</span></a>"#,
-                        self.locale_str
+                        r#"<a href="{}"><span class="token property">{encoded}</span></a>"#,
+                        type_url(self.locale_str, slug)
                     )
                 }
             }
@@ -484,16 +500,19 @@ impl SyntaxRenderer<'_> {
         )
     }
 
-    fn get_constituent_syntaxes(
+    /// The syntax lines of `syntax` and all its constituents, along with the
+    /// node each constituent line was resolved for (`None` for `syntax`).
+    fn get_constituents(
         &mut self,
         syntax: SyntaxLine,
-    ) -> Result<Vec<SyntaxLine>, SyntaxError> {
+    ) -> Result<(Vec<SyntaxLine>, Vec<Option<Node>>), SyntaxError> {
         let mut all_constituents = vec![];
 
         let mut last_len: usize;
         let mut last_syntax_len = 0;
 
         let mut constituent_syntaxes: Vec<SyntaxLine> = vec![syntax];
+        let mut constituent_nodes: Vec<Option<Node>> = vec![None];
 
         loop {
             last_len = all_constituents.len();
@@ -534,7 +553,8 @@ impl SyntaxRenderer<'_> {
                     && !constituent_syntaxes.contains(&constituent_entry)
                 {
                     constituent.syntax_used = true;
-                    constituent_syntaxes.push(constituent_entry)
+                    constituent_syntaxes.push(constituent_entry);
+                    constituent_nodes.push(Some(constituent.node.clone()));
                 }
             }
         }
@@ -546,7 +566,7 @@ impl SyntaxRenderer<'_> {
                     None
                 }
             }));
-        Ok(constituent_syntaxes)
+        Ok((constituent_syntaxes, constituent_nodes))
     }
 }
 
@@ -578,17 +598,15 @@ fn scope_from_browser_compat(browser_compat: Option<&str>) -> Option<&str> {
     }
 }
 
-pub fn render_formal_syntax(
+/// Resolves the syntax line to render, and whether it is an unnamed syntax
+/// string whose line is left out.
+fn resolve_syntax_input(
     syntax: SyntaxInput,
     browser_compat: Option<&str>,
-    locale_str: &str,
-    value_definition_url: &str,
-    syntax_tooltip: &HashMap<LinkedToken, String>,
-    sources_prefix: Option<&str>,
-) -> Result<String, SyntaxError> {
+) -> Result<(SyntaxLine, bool), SyntaxError> {
     let scope = scope_from_browser_compat(browser_compat);
 
-    let (syntax, skip_first) = match syntax {
+    Ok(match syntax {
         SyntaxInput::SyntaxString(syntax_str) => {
             let (name, syntax, skip_first) =
                 if let Some((name, syntax)) = syntax_str.split_once("=") {
@@ -612,8 +630,39 @@ pub fn render_formal_syntax(
             }
             (syntax, false)
         }
-    };
+    })
+}
+
+pub fn render_formal_syntax(
+    syntax: SyntaxInput,
+    browser_compat: Option<&str>,
+    locale_str: &str,
+    value_definition_url: &str,
+    syntax_tooltip: &HashMap<LinkedToken, String>,
+    sources_prefix: Option<&str>,
+) -> Result<String, SyntaxError> {
+    render_formal_syntax_with_data(
+        syntax,
+        browser_compat,
+        locale_str,
+        value_definition_url,
+        syntax_tooltip,
+        sources_prefix,
+    )
+    .map(|(html, _)| html)
+}
 
+/// Like [`render_formal_syntax`], but also returns the [`FormalSyntax`] of the
+/// rendered constituents.
+pub fn render_formal_syntax_with_data(
+    syntax: SyntaxInput,
+    browser_compat: Option<&str>,
+    locale_str: &str,
+    value_definition_url: &str,
+    syntax_tooltip: &HashMap<LinkedToken, String>,
+    sources_prefix: Option<&str>,
+) -> Result<(String, FormalSyntax), SyntaxError> {
+    let (syntax, skip_first) = resolve_syntax_input(syntax, browser_compat)?;
     render_formal_syntax_internal(
         syntax,
         locale_str,
@@ -631,7 +680,7 @@ fn render_formal_syntax_internal(
     syntax_tooltip: &'_ HashMap<LinkedToken, String>,
     sources_prefix: Option<&str>,
     skip_first: bool,
-) -> Result<String, SyntaxError> {
+) -> Result<(String, FormalSyntax), SyntaxError> {
     let mut renderer = SyntaxRenderer {
         locale_str,
         value_definition_url,
@@ -640,7 +689,8 @@ fn render_formal_syntax_internal(
     };
     let mut out = String::new();
     write!(out, r#"<pre class="notranslate css-formal-syntax">"#)?;
-    let mut constituents = renderer.get_constituent_syntaxes(syntax)?;
+    let (mut constituents, nodes) = renderer.get_constituents(syntax)?;
+    let data = build_formal_syntax(&constituents, &nodes, skip_first, locale_str)?;
 
     for (i, constituent) in constituents
         .iter()
@@ -697,7 +747,137 @@ fn render_formal_syntax_internal(
         }
         out.push_str("</footer>");
     }
-    Ok(out)
+    Ok((out, data))
+}
+
+/// A specification defining (part of) a syntax.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "rari", derive(schemars::JsonSchema))]
+pub struct FormalSyntaxSpec {
+    pub title: String,
+    pub url: String,
+}
+
+/// A type, function or property used in a formal syntax, with its own
+/// syntax.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "rari", derive(schemars::JsonSchema))]
+pub struct FormalSyntaxConstituent {
+    /// The name as written in the syntax, e.g. `<color>` or `<'width'>`.
+    pub name: String,
+    pub syntax: String,
+    #[serde(rename = "mdnUrl", skip_serializing_if = "Option::is_none")]
+    pub mdn_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub specs: Vec<FormalSyntaxSpec>,
+}
+
+/// The machine-readable counterpart of [`render_formal_syntax`]: the syntax
+/// and all constituents it expands.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "rari", derive(schemars::JsonSchema))]
+pub struct FormalSyntax {
+    /// The name of the item, absent for unnamed syntax strings.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub syntax: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub specs: Vec<FormalSyntaxSpec>,
+    pub constituents: Vec<FormalSyntaxConstituent>,
+}
+
+fn formal_syntax_specs(specs: Option<&[&SpecLink]>) -> Vec<FormalSyntaxSpec> {
+    specs
+        .unwrap_or_default()
+        .iter()
+        .map(|spec| FormalSyntaxSpec {
+            title: spec.title.clone(),
+            url: spec.url.to_string(),
+        })
+        .collect()
+}
+
+/// Resolves the same syntax as [`render_formal_syntax`] into a
+/// [`FormalSyntax`].
+pub fn formal_syntax(
+    syntax: SyntaxInput,
+    browser_compat: Option<&str>,
+    locale_str: &str,
+) -> Result<FormalSyntax, SyntaxError> {
+    let (syntax, skip_first) = resolve_syntax_input(syntax, browser_compat)?;
+    let mut renderer = SyntaxRenderer {
+        locale_str,
+        value_definition_url: "",
+        syntax_tooltip: &HashMap::new(),
+        constituents: Default::default(),
+    };
+    let (lines, nodes) = renderer.get_constituents(syntax)?;
+    build_formal_syntax(&lines, &nodes, skip_first, locale_str)
+}
+
+/// Builds the [`FormalSyntax`] from the lines and nodes returned by
+/// `get_constituents`.
+fn build_formal_syntax(
+    lines: &[SyntaxLine],
+    nodes: &[Option<Node>],
+    skip_first: bool,
+    locale_str: &str,
+) -> Result<FormalSyntax, SyntaxError> {
+    let mut lines = lines.iter().zip(nodes);
+    let Some((first, _)) = lines.next() else {
+        return Err(SyntaxError::NoSyntaxFound);
+    };
+    let constituents = lines
+        .filter_map(|(line, node)| {
+            let (name, mdn_url) = match node.as_ref()? {
+                Node::Property(property) => {
+                    let name = format!("<'{}'>", property.name);
+                    let mdn_url = property_url(locale_str, &name);
+                    (name, mdn_url)
+                }
+                Node::Type(typ) => {
+                    let name = format!("<{}>", typ.name);
+                    let slug = type_slug(&name);
+                    let mdn_url = (!skip(slug)).then(|| type_url(locale_str, slug));
+                    (name, mdn_url)
+                }
+                _ => (line.name.clone(), None),
+            };
+            Some(FormalSyntaxConstituent {
+                name,
+                syntax: line.syntax.clone(),
+                mdn_url,
+                specs: formal_syntax_specs(line.specs.as_deref()),
+            })
+        })
+        .collect();
+    Ok(FormalSyntax {
+        name: (!skip_first).then(|| first.name.clone()),
+        syntax: first.syntax.clone(),
+        specs: formal_syntax_specs(first.specs.as_deref()),
+        constituents,
+    })
+}
+
+/// Renders the same syntax as [`render_formal_syntax`] as a railroad diagram
+/// SVG, with types and properties linking to their MDN pages.
+pub fn render_syntax_diagram(
+    syntax: SyntaxInput,
+    browser_compat: Option<&str>,
+    locale_str: &str,
+) -> Result<String, SyntaxError> {
+    let (syntax, skip_first) = resolve_syntax_input(syntax, browser_compat)?;
+    let title = if skip_first { "" } else { &syntax.name };
+    let ast = parse(&syntax.syntax)?;
+    Ok(railroad(title, &ast, |node| match node {
+        Node::Property(property) => property_url(locale_str, &format!("<'{}'>", property.name)),
+        Node::Type(typ) => {
+            let name = format!("<{}>", typ.name);
+            let slug = type_slug(&name);
+            (!skip(slug)).then(|| type_url(locale_str, slug))
+        }
+        _ => None,
+    })?)
 }
 
 fn get_nodes_for_syntaxes(
@@ -860,6 +1040,65 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_formal_syntax() -> Result<(), SyntaxError> {
+        let result = formal_syntax(SyntaxInput::Css(CssType::Property("border")), None, "en-US")?;
+        assert_eq!(result.name.as_deref(), Some("border"));
+        assert_eq!(result.syntax, "<line-width> || <line-style> || <color>");
+        assert!(!result.specs.is_empty());
+        let line_width = result
+            .constituents
+            .iter()
+            .find(|constituent| constituent.name == "<line-width>")
+            .unwrap();
+        assert_eq!(
+            line_width.mdn_url.as_deref(),
+            Some("/en-US/docs/Web/CSS/Reference/Values/line-width")
+        );
+        assert!(line_width.syntax.contains("thin"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_formal_syntax_with_data() -> Result<(), SyntaxError> {
+        let (html, data) = render_formal_syntax_with_data(
+            SyntaxInput::Css(CssType::Property("padding")),
+            None,
+            "en-US",
+            "/en-US/docs/Web/CSS/Guides/Values_and_units/Value_definition_syntax",
+            &TOOLTIPS,
+            None,
+        )?;
+        assert!(html.starts_with(r#"<pre class="notranslate css-formal-syntax">"#));
+        assert_eq!(
+            data,
+            formal_syntax(
+                SyntaxInput::Css(CssType::Property("padding")),
+                None,
+                "en-US"
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_syntax_diagram() -> Result<(), SyntaxError> {
+        let svg =
+            render_syntax_diagram(SyntaxInput::SyntaxString("auto | <length>"), None, "en-US")?;
+        assert!(svg.contains(r#"<a href="/en-US/docs/Web/CSS/Reference/Values/length">"#));
+        assert!(svg.contains("<title></title>"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_formal_syntax_unnamed_string() -> Result<(), SyntaxError> {
+        let result = formal_syntax(SyntaxInput::SyntaxString("foo | bar"), None, "en-US")?;
+        assert_eq!(result.name, None);
+        assert_eq!(result.syntax, "foo | bar");
+        assert!(result.constituents.is_empty());
+        Ok(())
+    }
+
     #[test]
     fn test_render_function() -> Result<(), SyntaxError> {
         let expected = "<pre class=\"notranslate css-formal-syntax\"><span class=\"token property\" id=\"&lt;hue-rotate()&gt;\">&lt;hue-rotate()&gt; = </span><br/>  <span class=\"token function\">hue-rotate(</span> <a href=\"/en-US/docs/Web/CSS/Guides/Values_and_units/Value_definition_syntax#brackets\" title=\"Brackets: enclose several entities, combinators, and multipliers to transform them as a single component\">[</a> <a href=\"/en-US/docs/Web/CSS/Reference/Values/angle\"><span class=\"token property\">&lt;angle&gt;</span></a> <a href=\"/en-US/docs/Web/CSS/Guides/Values_and_units/Value_definition_syntax#single_bar\" title=\"Single bar: exactly one of the entities must be present\">|</a> <a href=\"/en-US/docs/Web/CSS/Reference/Values/zero\"><span class=\"token property\">&lt;zero&gt;</span></a> <a href=\"/en-US/docs/Web/CSS/Guides/Values_and_units/Value_definition_syntax#brackets\" title=\"Brackets: enclose several entities, combinators, and multipliers to transform them as a single component\">]</a><a href=\"/en-US/docs/Web/CSS/Guides/Values_and_units/Value_definition_syntax#question_mark\" title=\"Question mark: the entity is optional\">?</a> <span class=\"token function\">)</span>  <br/></pre><footer></footer>";
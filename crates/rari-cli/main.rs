@@ -33,14 +33,21 @@ use rari_doc::templ::templs::TEMPL_MAP;
 use rari_doc::utils::{TEMPL_RECORDER_SENDER, TemplStatEvent, locale_and_typ_from_path};
 use rari_sitemap::Sitemaps;
 use rari_tools::add_redirect::add_redirect;
+use rari_tools::convert_macros::convert_macros;
 use rari_tools::fix::fixer::fix_all;
+use rari_tools::fmt::fmt_content;
 use rari_tools::history::gather_history;
 use rari_tools::inventory::gather_inventory;
+use rari_tools::macro_usage::{MacroUsageFormat, macro_usage};
 use rari_tools::r#move::r#move;
+use rari_tools::new::new_page;
 use rari_tools::redirects::{fix_redirects, validate_redirects};
+use rari_tools::redirects_export::{RedirectsExportFormat, export_redirects};
+use rari_tools::redirects_report::{prune_redirects, report_redirects};
 use rari_tools::remove::remove;
-use rari_tools::sidebars::{fmt_sidebars, sync_sidebars};
+use rari_tools::sidebars::{fmt_sidebars, sync_sidebars, validate_sidebars};
 use rari_tools::sync_translated_content::sync_translated_content;
+use rari_types::fm_types::PageType;
 use rari_types::globals::{
     SETTINGS, blog_root, build_out_root, content_root, content_translated_root,
     contributor_spotlight_root, curriculum_root, generic_content_root,
@@ -65,9 +72,16 @@ struct Cli {
     /// Skip updating dependencies (bcd, webref, ...)
     #[arg(short, long, env = "RARI_SKIP_UPDATES")]
     skip_updates: bool,
-    /// Force update all dependencies, ignoring cache
+    /// Force update all dependencies, ignoring cache and replacing unpacked
+    /// bundles
     #[arg(long, conflicts_with = "skip_updates")]
     force_updates: bool,
+    /// Never download dependencies, use the data unpacked by `rari deps unpack`
+    ///
+    /// The data is verified when unpacking, use `rari deps verify` to check
+    /// it again.
+    #[arg(long, env = "RARI_OFFLINE", conflicts_with = "force_updates")]
+    offline: bool,
     #[command(flatten)]
     verbose: Verbosity,
     #[command(subcommand)]
@@ -89,9 +103,49 @@ enum Commands {
     /// Subcommands for altering content programmatically
     #[command(subcommand)]
     Content(ContentSubcommand),
+    /// Subcommands for managing dependency data (bcd, webref, ...)
+    #[command(subcommand)]
+    Deps(DepsSubcommand),
     Lsp,
 }
 
+#[derive(Subcommand)]
+enum DepsSubcommand {
+    /// Packs the dependency data into a bundle for offline builds.
+    ///
+    /// The bundle is a tar.gz of the data directory with a manifest of
+    /// package versions and file checksums.
+    Pack(DepsPackArgs),
+    /// Verifies a bundle and replaces the dependency data with it.
+    Unpack(DepsUnpackArgs),
+    /// Lists the dependencies with their pinned range, installed version,
+    /// last check and available updates.
+    ///
+    /// Updates are not looked up with --offline.
+    Status(DepsStatusArgs),
+    /// Re-verifies the cached npm packages against their integrity hashes,
+    /// and an unpacked bundle against its checksums.
+    ///
+    /// Fails if a tarball or any file unpacked from it was modified.
+    Verify,
+}
+
+#[derive(Args)]
+struct DepsPackArgs {
+    output: PathBuf,
+}
+
+#[derive(Args)]
+struct DepsStatusArgs {
+    #[arg(long, help = "Print the status as JSON")]
+    json: bool,
+}
+
+#[derive(Args)]
+struct DepsUnpackArgs {
+    bundle: PathBuf,
+}
+
 #[derive(Args)]
 struct FixFlawsArgs {
     #[arg(short, long, help = "Only fix flaws for <LOCALE>")]
@@ -120,6 +174,10 @@ enum ContentSubcommand {
     Move(MoveArgs),
     /// Deletes content pages.
     Delete(DeleteArgs),
+    /// Creates a new en-US page from the template for its page type.
+    ///
+    /// The page is also added to sidebars that list its siblings.
+    New(NewArgs),
     /// Adds a redirect from->to pair to the redirect map.
     ///
     /// The locale is inferred from the from_url.
@@ -128,8 +186,18 @@ enum ContentSubcommand {
     SyncTranslatedContent(SyncTranslatedContentArgs),
     /// Formats all sidebars.
     FmtSidebars,
+    /// Formats the markdown of content pages.
+    ///
+    /// Normalizes front matter key order, list markers, tables and callouts.
+    /// Macro calls and code blocks are left untouched.
+    Fmt(FmtArgs),
     /// Sync sidebars with redirects
     SyncSidebars,
+    /// Validates all sidebars.
+    ///
+    /// Reports broken and redirected links, missing and unused l10n keys,
+    /// and duplicate entries as JSON.
+    ValidateSidebars,
     /// Fixes redirects across all locales.
     ///
     /// This shortens multiple redirect chains to single ones.
@@ -137,12 +205,112 @@ enum ContentSubcommand {
     FixRedirects(FixRedirectArgs),
     /// Validate redirects.
     ValidateRedirects(ValidateRedirectArgs),
+    /// Subcommands for analyzing and cleaning up the redirect map.
+    #[command(subcommand)]
+    Redirects(RedirectsSubcommand),
     /// Create content inventory as JSON
     Inventory,
+    /// Reports per-macro, per-locale and per-page macro usage.
+    ///
+    /// Macros are found by parsing pages, without rendering them.
+    MacroUsage(MacroUsageArgs),
+    /// Rewrites link macro calls that render as plain links to markdown
+    /// links.
+    ///
+    /// Calls rendering badges or other non-trivial HTML are left alone.
+    ConvertMacros(ConvertMacrosArgs),
     /// Fix all flaws (currently only broken_links)
     FixFlaws(FixFlawsArgs),
 }
 
+#[derive(Subcommand)]
+enum RedirectsSubcommand {
+    /// Report redirects that are likely dead weight as JSON.
+    ///
+    /// Lists redirects whose source is never linked from content, redirects
+    /// to external URLs, shadowed case variants, and redirects into other
+    /// locales.
+    Report(RedirectsReportArgs),
+    /// Remove shadowed case variants of redirects.
+    ///
+    /// Unlinked redirects are only reported, never removed.
+    Prune(RedirectsPruneArgs),
+    /// Export redirects as server configuration.
+    ///
+    /// Redirect chains are collapsed before exporting.
+    Export(RedirectsExportArgs),
+}
+
+#[derive(Args)]
+struct RedirectsReportArgs {
+    locales: Option<Vec<Locale>>,
+}
+
+#[derive(Args)]
+struct RedirectsPruneArgs {
+    locales: Option<Vec<Locale>>,
+    #[arg(
+        long,
+        help = "Never prune redirects from URLs listed in <ALLOW_LIST> (one per line, '*' suffix for prefixes)"
+    )]
+    allow_list: Option<PathBuf>,
+    #[arg(short = 'y', long, help = "Assume yes to all prompts")]
+    assume_yes: bool,
+}
+
+#[derive(Args)]
+struct RedirectsExportArgs {
+    locales: Option<Vec<Locale>>,
+    #[arg(long, value_enum, help = "Output format")]
+    format: RedirectsExportFormat,
+    #[arg(short, long, help = "Write to <OUTPUT> instead of stdout")]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct FmtArgs {
+    #[arg(long, help = "Only report unformatted files and fail if there are any")]
+    check: bool,
+    #[arg(
+        long = "locale",
+        value_name = "LOCALE",
+        help = "Only format <LOCALE> (can be repeated)"
+    )]
+    locales: Option<Vec<Locale>>,
+}
+
+#[derive(Args)]
+struct ConvertMacrosArgs {
+    #[arg(
+        long = "macro",
+        value_name = "MACRO",
+        help = "Convert calls of <MACRO>"
+    )]
+    macro_name: String,
+    #[arg(
+        long = "locale",
+        value_name = "LOCALE",
+        help = "Only convert <LOCALE> (can be repeated)"
+    )]
+    locales: Option<Vec<Locale>>,
+}
+
+#[derive(Args)]
+struct MacroUsageArgs {
+    #[arg(long = "macro", value_name = "MACRO", help = "Only report <MACRO>")]
+    macro_name: Option<String>,
+    #[arg(
+        long = "locale",
+        value_name = "LOCALE",
+        help = "Only report <LOCALE> (can be repeated)"
+    )]
+    locales: Option<Vec<Locale>>,
+    #[arg(long, value_enum, default_value_t = MacroUsageFormat::Json, help = "Output format")]
+    format: MacroUsageFormat,
+    #[arg(short, long, help = "Write to <OUTPUT> instead of stdout")]
+    output: Option<PathBuf>,
+}
+
 #[derive(Args)]
 struct MoveArgs {
     old_slug: String,
@@ -150,6 +318,19 @@ struct MoveArgs {
     locale: Option<Locale>,
     #[arg(short = 'y', long, help = "Assume yes to all prompts")]
     assume_yes: bool,
+    #[arg(long, help = "Rewrite links to the moved pages in all locales")]
+    rewrite_links: bool,
+}
+
+#[derive(Args)]
+struct NewArgs {
+    slug: String,
+    #[arg(long, help = "Page type, e.g. css-property")]
+    page_type: PageType,
+    #[arg(long, help = "Title (defaults to the last slug segment)")]
+    title: Option<String>,
+    #[arg(long, help = "Browser compat key, e.g. css.properties.color")]
+    bcd: Option<String>,
 }
 
 #[derive(Args)]
@@ -162,6 +343,12 @@ struct DeleteArgs {
     redirect: Option<String>,
     #[arg(short = 'y', long, help = "Assume yes to all prompts")]
     assume_yes: bool,
+    #[arg(
+        long,
+        requires = "redirect",
+        help = "Rewrite links to the deleted pages to the redirect target in all locales"
+    )]
+    rewrite_links: bool,
 }
 
 #[derive(Args)]
@@ -275,6 +462,26 @@ struct BuildArgs {
         help = "Add live_samples field to index.json docs docs and blog posts"
     )]
     json_live_samples: bool,
+    #[arg(
+        long,
+        help = "Write a standalone _sample_.<id>.html next to index.json for each live sample"
+    )]
+    live_sample_pages: bool,
+    #[arg(
+        long,
+        help = "Syntax highlight js, css, html, json and bash code blocks"
+    )]
+    syntax_highlighting: bool,
+    #[arg(
+        long,
+        help = "Report syntax errors in js, json, css and html code blocks (skips example-bad and no-syntax-check blocks)"
+    )]
+    check_code_syntax: bool,
+    #[arg(
+        long,
+        help = "Report unknown properties, invalid values and unknown at-rules in css code blocks"
+    )]
+    check_css_values: bool,
     #[arg(
         short,
         long,
@@ -474,10 +681,37 @@ fn main() -> Result<(), Error> {
     if cli.force_updates {
         tracing::info!("Forcing update of all dependencies...");
         clear_dependencies_last_checked(rari_types::globals::data_dir());
+        rari_deps::bundle::remove_manifest(rari_types::globals::data_dir())?;
+    }
+
+    // these work on the cached data as is
+    let local_deps_command = matches!(
+        cli.command,
+        Commands::Deps(
+            DepsSubcommand::Unpack(_) | DepsSubcommand::Status(_) | DepsSubcommand::Verify
+        )
+    );
+    let bundle = rari_deps::bundle::manifest(rari_types::globals::data_dir());
+    let has_bundle = bundle.is_ok();
+    if cli.offline && !local_deps_command {
+        let manifest = bundle?;
+        tracing::info!(
+            "Using offline dependency bundle from {}",
+            manifest.created.to_rfc3339()
+        );
+    } else if let Ok(manifest) = &bundle
+        && !local_deps_command
+    {
+        // updating would leave data that no longer matches the bundle
+        tracing::info!(
+            "Using dependency bundle from {}, skipping updates (use --force-updates to replace it)",
+            manifest.created.to_rfc3339()
+        );
     }
 
-    if !cli.skip_updates {
+    if !cli.skip_updates && !cli.offline && !has_bundle && !local_deps_command {
         rari_deps::webref_css::update_webref_css(rari_types::globals::data_dir())?;
+        rari_deps::webref_idl::update_webref_idl(rari_types::globals::data_dir())?;
         rari_deps::web_features::update_web_features(rari_types::globals::data_dir())?;
         rari_deps::developer_signals::update_developer_signals(rari_types::globals::data_dir())?;
         rari_deps::bcd::update_bcd(rari_types::globals::data_dir())?;
@@ -494,6 +728,10 @@ fn main() -> Result<(), Error> {
             settings.data_issues = args.data_issues;
             settings.json_issues = args.json_issues;
             settings.json_live_samples = args.json_live_samples;
+            settings.live_sample_pages = args.live_sample_pages;
+            settings.syntax_highlighting = args.syntax_highlighting;
+            settings.check_code_syntax = args.check_code_syntax;
+            settings.check_css_values = args.check_css_values;
             let _ = SETTINGS.set(settings);
 
             if !args.files_flag.is_empty() {
@@ -777,6 +1015,18 @@ fn main() -> Result<(), Error> {
                     sitemaps.sitemap_meta.len()
                 );
             }
+            if total_files > 0 {
+                // record the dependency data used, for reproducible builds
+                let out_path = build_out_root()?;
+                fs::create_dir_all(out_path)?;
+                fs::write(
+                    out_path.join("deps.json"),
+                    serde_json::to_string_pretty(&rari_deps::status::status(
+                        rari_types::globals::data_dir(),
+                        false,
+                    ))?,
+                )?;
+            }
             if let Some((recorder_handler, tx)) = templ_stats {
                 tx.send(TemplStatEvent::Stop)?;
                 recorder_handler
@@ -879,7 +1129,21 @@ fn main() -> Result<(), Error> {
         }
         Commands::Content(content_subcommand) => match content_subcommand {
             ContentSubcommand::Move(args) => {
-                r#move(&args.old_slug, &args.new_slug, args.locale, args.assume_yes)?;
+                r#move(
+                    &args.old_slug,
+                    &args.new_slug,
+                    args.locale,
+                    args.assume_yes,
+                    args.rewrite_links,
+                )?;
+            }
+            ContentSubcommand::New(args) => {
+                new_page(
+                    &args.slug,
+                    args.page_type,
+                    args.title.as_deref(),
+                    args.bcd.as_deref(),
+                )?;
             }
             ContentSubcommand::Delete(args) => {
                 remove(
@@ -888,6 +1152,7 @@ fn main() -> Result<(), Error> {
                     args.recursive,
                     args.redirect.as_deref(),
                     args.assume_yes,
+                    args.rewrite_links,
                 )?;
             }
             ContentSubcommand::AddRedirect(args) => {
@@ -900,18 +1165,51 @@ fn main() -> Result<(), Error> {
             ContentSubcommand::FmtSidebars => {
                 fmt_sidebars()?;
             }
+            ContentSubcommand::Fmt(args) => {
+                fmt_content(args.locales.as_deref(), args.check)?;
+            }
             ContentSubcommand::SyncSidebars => {
                 sync_sidebars()?;
             }
+            ContentSubcommand::ValidateSidebars => {
+                validate_sidebars()?;
+            }
             ContentSubcommand::FixRedirects(args) => {
                 fix_redirects(args.locales.as_deref())?;
             }
             ContentSubcommand::ValidateRedirects(args) => {
                 validate_redirects(args.locales.as_deref())?;
             }
+            ContentSubcommand::Redirects(redirects_subcommand) => match redirects_subcommand {
+                RedirectsSubcommand::Report(args) => {
+                    report_redirects(args.locales.as_deref())?;
+                }
+                RedirectsSubcommand::Prune(args) => {
+                    prune_redirects(
+                        args.locales.as_deref(),
+                        args.allow_list.as_deref(),
+                        args.assume_yes,
+                    )?;
+                }
+                RedirectsSubcommand::Export(args) => {
+                    export_redirects(args.locales.as_deref(), args.format, args.output.as_deref())?;
+                }
+            },
             ContentSubcommand::Inventory => {
                 gather_inventory()?;
             }
+            ContentSubcommand::MacroUsage(args) => {
+                macro_usage(
+                    args.macro_name.as_deref(),
+                    args.locales.as_deref(),
+                    args.format,
+                    args.output.as_deref(),
+                )?;
+            }
+            ContentSubcommand::ConvertMacros(args) => {
+                let converted = convert_macros(&args.macro_name, args.locales.as_deref())?;
+                info!("Converted {converted} {} call(s)", args.macro_name);
+            }
             ContentSubcommand::FixFlaws(args) => {
                 let mut settings = Settings::new()?;
                 settings.cache_content = true;
@@ -1009,6 +1307,89 @@ fn main() -> Result<(), Error> {
                 );
             }
         },
+        Commands::Deps(deps_subcommand) => match deps_subcommand {
+            DepsSubcommand::Pack(args) => {
+                let manifest =
+                    rari_deps::bundle::pack(rari_types::globals::data_dir(), &args.output)?;
+                info!(
+                    "Packed {} files into {}",
+                    manifest.files.len(),
+                    args.output.display()
+                );
+            }
+            DepsSubcommand::Unpack(args) => {
+                let manifest =
+                    rari_deps::bundle::unpack(&args.bundle, rari_types::globals::data_dir())?;
+                info!(
+                    "Unpacked {} files into {}",
+                    manifest.files.len(),
+                    rari_types::globals::data_dir().display()
+                );
+            }
+            DepsSubcommand::Status(args) => {
+                let status =
+                    rari_deps::status::status(rari_types::globals::data_dir(), !cli.offline);
+                let mut out = std::io::stdout().lock();
+                if args.json {
+                    serde_json::to_writer_pretty(&mut out, &status)?;
+                    writeln!(out)?;
+                } else {
+                    let mut tw = TabWriter::new(out);
+                    writeln!(tw, "dependency\trange\tinstalled\tlast check\tupdate")?;
+                    for dep in status {
+                        let update = match (dep.update, dep.latest) {
+                            (Some(update), Some(latest)) => {
+                                format!("{update} ({latest} outside range)")
+                            }
+                            (Some(update), None) => update.to_string(),
+                            (None, Some(latest)) => format!("{latest} outside range"),
+                            (None, None) => "-".to_string(),
+                        };
+                        writeln!(
+                            tw,
+                            "{}\t{}\t{}\t{}\t{update}",
+                            dep.name,
+                            dep.range.map(|r| r.to_string()).as_deref().unwrap_or("-"),
+                            dep.installed
+                                .map(|v| v.to_string())
+                                .as_deref()
+                                .unwrap_or("-"),
+                            dep.last_check
+                                .map(|d| d.format("%Y-%m-%d %H:%M").to_string())
+                                .as_deref()
+                                .unwrap_or("never"),
+                        )?;
+                    }
+                    tw.flush()?;
+                }
+            }
+            DepsSubcommand::Verify => {
+                let mut failed = 0;
+                if has_bundle {
+                    match rari_deps::bundle::verify(rari_types::globals::data_dir()) {
+                        Ok(manifest) => info!("bundle ({} files): ok", manifest.files.len()),
+                        Err(e) => {
+                            failed += 1;
+                            tracing::error!("bundle: {e}");
+                        }
+                    }
+                }
+                for (package, _) in rari_types::globals::deps().packages() {
+                    match rari_deps::npm::verify_package(package, rari_types::globals::data_dir()) {
+                        Ok(version) => info!("{package}@{version}: ok"),
+                        Err(e) => {
+                            failed += 1;
+                            tracing::error!("{package}: {e}");
+                        }
+                    }
+                }
+                if failed > 0 {
+                    return Err(anyhow!(
+                        "{failed} check(s) failed, rerun with --force-updates to download the data again"
+                    ));
+                }
+            }
+        },
         Commands::Update(args) => update(args.version)?,
         Commands::ExportSchema(args) => export_schema(args)?,
     }
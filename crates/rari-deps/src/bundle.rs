@@ -0,0 +1,277 @@
+//! Offline dependency bundles.
+//!
+//! A bundle is a gzipped tarball of the whole data directory together with a
+//! `bundle.json` manifest. The manifest records the bundle format, the
+//! versions of the npm packages in it and a SHA-256 checksum of every file.
+//! Unpacking verifies all checksums before the data directory is replaced.
+//! Later runs only read the manifest, as hashing the whole data directory
+//! (BCD, webref, …) takes a while; `verify` does that on request.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use rari_utils::io::read_to_string;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tar::{Archive, Builder};
+
+use crate::current::Current;
+use crate::error::DepsError;
+
+/// The version of the bundle layout, bumped on incompatible changes.
+pub const BUNDLE_FORMAT: u32 = 1;
+/// The name of the manifest, both in the bundle and the data directory.
+pub const BUNDLE_MANIFEST: &str = "bundle.json";
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct BundleManifest {
+    pub format: u32,
+    pub created: DateTime<Utc>,
+    /// Installed versions of the npm packages, by package name.
+    pub versions: BTreeMap<String, Version>,
+    /// Hex SHA-256 checksums, by `/`-separated path relative to the data directory.
+    pub files: BTreeMap<String, String>,
+}
+
+fn sha256(path: &Path) -> Result<String, DepsError> {
+    Ok(base16ct::lower::encode_string(&Sha256::digest(fs::read(
+        path,
+    )?)))
+}
+
+/// Collects all files below `dir` as (relative path, full path), sorted.
+fn collect_files(
+    base: &Path,
+    dir: &Path,
+    files: &mut Vec<(String, PathBuf)>,
+) -> Result<(), DepsError> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(base, &path, files)?;
+        } else {
+            let rel = path
+                .strip_prefix(base)
+                .map_err(|_| {
+                    DepsError::BundleError(format!("{} outside of data dir", path.display()))
+                })?
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            if rel != BUNDLE_MANIFEST {
+                files.push((rel, path));
+            }
+        }
+    }
+    files.sort();
+    Ok(())
+}
+
+/// Installed npm package versions from the `last_check.json` files in `files`.
+fn installed_versions(files: &[(String, PathBuf)]) -> BTreeMap<String, Version> {
+    files
+        .iter()
+        .filter_map(|(rel, path)| {
+            let package = rel.strip_suffix("/last_check.json")?;
+            let current = serde_json::from_str::<Current>(&read_to_string(path).ok()?).ok()?;
+            Some((package.to_string(), current.current_version?))
+        })
+        .collect()
+}
+
+/// Packs the data directory `data_dir` into a bundle at `out_file`.
+pub fn pack(data_dir: &Path, out_file: &Path) -> Result<BundleManifest, DepsError> {
+    if !data_dir.is_dir() {
+        return Err(DepsError::BundleError(format!(
+            "data dir {} does not exist",
+            data_dir.display()
+        )));
+    }
+    let mut files = vec![];
+    collect_files(data_dir, data_dir, &mut files)?;
+    let manifest = BundleManifest {
+        format: BUNDLE_FORMAT,
+        created: Utc::now(),
+        versions: installed_versions(&files),
+        files: files
+            .iter()
+            .map(|(rel, path)| Ok((rel.clone(), sha256(path)?)))
+            .collect::<Result<_, DepsError>>()?,
+    };
+
+    let gz = GzEncoder::new(
+        BufWriter::new(File::create(out_file)?),
+        Compression::default(),
+    );
+    let mut builder = Builder::new(gz);
+    let manifest_json = serde_json::to_vec_pretty(&manifest)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_json.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(manifest.created.timestamp() as u64);
+    header.set_cksum();
+    builder.append_data(&mut header, BUNDLE_MANIFEST, manifest_json.as_slice())?;
+    for (rel, path) in &files {
+        builder.append_path_with_name(path, rel)?;
+    }
+    builder.into_inner()?.finish()?;
+    Ok(manifest)
+}
+
+/// Reads the manifest of the bundle unpacked into `dir`, without checking
+/// the files.
+pub fn manifest(dir: &Path) -> Result<BundleManifest, DepsError> {
+    let manifest_path = dir.join(BUNDLE_MANIFEST);
+    if !manifest_path.is_file() {
+        return Err(DepsError::NoBundle(dir.to_path_buf()));
+    }
+    let manifest: BundleManifest = serde_json::from_str(&read_to_string(&manifest_path)?)?;
+    if manifest.format != BUNDLE_FORMAT {
+        return Err(DepsError::BundleError(format!(
+            "unsupported bundle format {} (expected {BUNDLE_FORMAT})",
+            manifest.format
+        )));
+    }
+    Ok(manifest)
+}
+
+/// Removes the manifest from `dir`, once its data no longer comes from the
+/// bundle.
+pub fn remove_manifest(dir: &Path) -> Result<(), DepsError> {
+    let manifest_path = dir.join(BUNDLE_MANIFEST);
+    if manifest_path.is_file() {
+        fs::remove_file(manifest_path)?;
+    }
+    Ok(())
+}
+
+/// Checks the files in `dir` against the manifest in `dir`. Every listed
+/// file must exist with a matching checksum, and no other files may exist.
+///
+/// This hashes the whole data directory.
+pub fn verify(dir: &Path) -> Result<BundleManifest, DepsError> {
+    let manifest = manifest(dir)?;
+    let mut files = vec![];
+    collect_files(dir, dir, &mut files)?;
+    if let Some((rel, _)) = files
+        .iter()
+        .find(|(rel, _)| !manifest.files.contains_key(rel))
+    {
+        return Err(DepsError::BundleError(format!("unexpected file {rel}")));
+    }
+    for (rel, checksum) in &manifest.files {
+        let path = dir.join(rel);
+        if !path.is_file() {
+            return Err(DepsError::BundleError(format!("missing file {rel}")));
+        }
+        if &sha256(&path)? != checksum {
+            return Err(DepsError::BundleChecksumMismatch(rel.clone()));
+        }
+    }
+    Ok(manifest)
+}
+
+/// Unpacks and verifies the bundle `bundle_file`, then replaces `data_dir`
+/// with its contents. `data_dir` is left untouched if verification fails.
+pub fn unpack(bundle_file: &Path, data_dir: &Path) -> Result<BundleManifest, DepsError> {
+    let gz = GzDecoder::new(BufReader::new(File::open(bundle_file)?));
+    let mut staging = data_dir.as_os_str().to_owned();
+    staging.push(".unpack");
+    let staging = PathBuf::from(staging);
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    fs::create_dir_all(&staging)?;
+
+    let result = Archive::new(gz)
+        .unpack(&staging)
+        .map_err(DepsError::from)
+        .and_then(|_| verify(&staging));
+    let manifest = match result {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            fs::remove_dir_all(&staging)?;
+            return Err(e);
+        }
+    };
+
+    if data_dir.exists() {
+        fs::remove_dir_all(data_dir)?;
+    }
+    fs::rename(&staging, data_dir)?;
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn data_dir(base: &Path) -> PathBuf {
+        let data_dir = base.join("rari");
+        fs::create_dir_all(data_dir.join("mdn-data/package")).unwrap();
+        fs::write(data_dir.join("mdn-data/package/data.json"), "{}").unwrap();
+        fs::write(
+            data_dir.join("mdn-data/last_check.json"),
+            r#"{"latest_last_check":null,"current_version":"2.1.0"}"#,
+        )
+        .unwrap();
+        data_dir
+    }
+
+    #[test]
+    fn test_pack_unpack() {
+        let tmp = tempfile::tempdir().unwrap();
+        let data_dir = data_dir(tmp.path());
+        let bundle = tmp.path().join("deps.tar.gz");
+
+        let manifest = pack(&data_dir, &bundle).unwrap();
+        assert_eq!(manifest.files.len(), 2);
+        assert_eq!(
+            manifest.versions.get("mdn-data"),
+            Some(&Version::new(2, 1, 0))
+        );
+
+        let out_dir = tmp.path().join("out");
+        unpack(&bundle, &out_dir).unwrap();
+        assert_eq!(
+            fs::read_to_string(out_dir.join("mdn-data/package/data.json")).unwrap(),
+            "{}"
+        );
+        assert!(verify(&out_dir).is_ok());
+
+        fs::write(out_dir.join("mdn-data/package/data.json"), "[]").unwrap();
+        assert!(matches!(
+            verify(&out_dir),
+            Err(DepsError::BundleChecksumMismatch(path)) if path == "mdn-data/package/data.json"
+        ));
+    }
+
+    #[test]
+    fn test_verify_without_bundle() {
+        let tmp = tempfile::tempdir().unwrap();
+        let data_dir = data_dir(tmp.path());
+        assert!(matches!(verify(&data_dir), Err(DepsError::NoBundle(_))));
+    }
+
+    #[test]
+    fn test_remove_manifest() {
+        let tmp = tempfile::tempdir().unwrap();
+        let data_dir = data_dir(tmp.path());
+        let bundle = tmp.path().join("deps.tar.gz");
+        pack(&data_dir, &bundle).unwrap();
+        let out_dir = tmp.path().join("out");
+        unpack(&bundle, &out_dir).unwrap();
+        assert!(manifest(&out_dir).is_ok());
+        remove_manifest(&out_dir).unwrap();
+        assert!(matches!(manifest(&out_dir), Err(DepsError::NoBundle(_))));
+        remove_manifest(&out_dir).unwrap();
+    }
+}
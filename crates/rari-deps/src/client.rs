@@ -1,18 +1,32 @@
-use reqwest::blocking::Response;
 use std::env;
+use std::fs::File;
+use std::io::Read;
+
 use url::Url;
 
 use crate::error::DepsError;
 
-pub fn get(url: impl AsRef<str>) -> Result<Response, DepsError> {
+/// Requests to the GitHub API are authenticated with `GH_TOKEN` or
+/// `GITHUB_TOKEN`.
+const GITHUB_API_URL: &str = "https://api.github.com";
+
+/// Opens `url` for reading. `file://` URLs are read from disk, everything
+/// else is downloaded.
+pub fn get(url: impl AsRef<str>) -> Result<Box<dyn Read>, DepsError> {
     let url = Url::parse(url.as_ref())?;
+    if url.scheme() == "file" {
+        let path = url
+            .to_file_path()
+            .map_err(|_| DepsError::InvalidFileUrl(url.to_string()))?;
+        return Ok(Box::new(File::open(path)?));
+    }
     let mut req_builder = reqwest::blocking::ClientBuilder::new()
         .user_agent("mdn/rari")
         .build()?
         .get(url.as_ref());
 
-    // check if the URL's host is api.github.com
-    if url.host_str() == Some("api.github.com") {
+    // check if the URL is on the GitHub API
+    if is_below(&url, GITHUB_API_URL) {
         // Use GH_TOKEN or GITHUB_TOKEN if set to avoid rate limiting.
         if let Ok(token) = env::var("GH_TOKEN").or_else(|_| env::var("GITHUB_TOKEN")) {
             req_builder = req_builder.bearer_auth(token);
@@ -23,5 +37,98 @@ pub fn get(url: impl AsRef<str>) -> Result<Response, DepsError> {
         }
     }
 
-    Ok(req_builder.send()?)
+    Ok(Box::new(req_builder.send()?))
+}
+
+/// Whether `url` has the same origin as `base` and its path is `base`'s path
+/// or below it.
+fn is_below(url: &Url, base: &str) -> bool {
+    let Ok(base) = Url::parse(base) else {
+        return false;
+    };
+    if url.scheme() != base.scheme()
+        || url.host() != base.host()
+        || url.port_or_known_default() != base.port_or_known_default()
+    {
+        return false;
+    }
+    let base_path = base.path().trim_end_matches('/');
+    url.path()
+        .strip_prefix(base_path)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Joins `path` to the configured base URL `base`.
+pub fn join_url(base: &str, path: &str) -> String {
+    format!(
+        "{}/{}",
+        base.trim_end_matches('/'),
+        path.trim_start_matches('/')
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_get_file_url() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("data.json");
+        fs::write(&path, "{}").unwrap();
+        let mut out = String::new();
+        get(Url::from_file_path(&path).unwrap())
+            .unwrap()
+            .read_to_string(&mut out)
+            .unwrap();
+        assert_eq!(out, "{}");
+    }
+
+    #[test]
+    fn test_is_below() {
+        let below = |url: &str, base: &str| is_below(&Url::parse(url).unwrap(), base);
+        assert!(below(
+            "https://api.github.com/repos/mdn/yari",
+            "https://api.github.com"
+        ));
+        assert!(below(
+            "https://api.github.com:443/repos",
+            "https://api.github.com/"
+        ));
+        assert!(below(
+            "https://ghe.example/api/v3/repos",
+            "https://ghe.example/api/v3"
+        ));
+        assert!(!below(
+            "https://api.github.com.attacker.example/repos",
+            "https://api.github.com"
+        ));
+        assert!(!below(
+            "http://api.github.com/repos",
+            "https://api.github.com"
+        ));
+        assert!(!below(
+            "https://api.github.com:8443/repos",
+            "https://api.github.com"
+        ));
+        assert!(!below(
+            "https://ghe.example/api/v30/repos",
+            "https://ghe.example/api/v3"
+        ));
+        assert!(!below("https://api.github.com/repos", "not a url"));
+    }
+
+    #[test]
+    fn test_join_url() {
+        assert_eq!(
+            join_url("https://registry.npmjs.org/", "mdn-data"),
+            "https://registry.npmjs.org/mdn-data"
+        );
+        assert_eq!(
+            join_url("file:///mirror", "@webref/css"),
+            "file:///mirror/@webref/css"
+        );
+    }
 }
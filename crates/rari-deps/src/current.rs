@@ -6,4 +6,7 @@ use serde::{Deserialize, Serialize};
 pub struct Current {
     pub latest_last_check: Option<DateTime<Utc>>,
     pub current_version: Option<Version>,
+    /// Subresource Integrity hash of the downloaded npm tarball.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub integrity: Option<String>,
 }
@@ -1,12 +1,14 @@
 use std::path::Path;
 
+use rari_types::globals::deps_sources;
+
 use crate::error::DepsError;
 use crate::external_json::get_json;
 
 pub fn update_developer_signals(base_path: &Path) -> Result<(), DepsError> {
     get_json(
         "developer_signals",
-        "https://web-platform-dx.github.io/developer-signals/web-features-signals.json",
+        &deps_sources().developer_signals_url,
         base_path,
     )?;
     Ok(())
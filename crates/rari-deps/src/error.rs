@@ -30,4 +30,18 @@ pub enum DepsError {
     GithubError(String),
     #[error("Webref error: {0}")]
     WebRefParseError(String),
+    #[error("Integrity check failed for {0}")]
+    IntegrityMismatch(String),
+    #[error("No integrity hash for {0}")]
+    IntegrityMissing(String),
+    #[error("Cached file {0} differs from the package tarball")]
+    CacheModified(String),
+    #[error("Invalid file url: {0}")]
+    InvalidFileUrl(String),
+    #[error("Invalid dependency bundle: {0}")]
+    BundleError(String),
+    #[error("Checksum mismatch for {0} in dependency bundle")]
+    BundleChecksumMismatch(String),
+    #[error("No dependency bundle in {0}, run `rari deps unpack <BUNDLE>` first")]
+    NoBundle(std::path::PathBuf),
 }
@@ -1,5 +1,5 @@
 use std::fs::{self, File};
-use std::io::{BufWriter, Write};
+use std::io::{BufWriter, Read, Write};
 use std::path::{Path, PathBuf};
 
 use chrono::{DateTime, Duration, Utc};
@@ -27,7 +27,8 @@ pub fn get_json(name: &str, url: &str, out_path: &Path) -> Result<Option<PathBuf
             fs::remove_dir_all(&package_path)?;
         }
         fs::create_dir_all(&package_path)?;
-        let buf = get(url)?.bytes()?;
+        let mut buf = vec![];
+        get(url)?.read_to_end(&mut buf)?;
 
         let out_file = package_path.join("data.json");
         let file = File::create(out_file).unwrap();
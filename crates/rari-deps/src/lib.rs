@@ -1,4 +1,5 @@
 pub mod bcd;
+pub mod bundle;
 pub mod client;
 pub mod current;
 pub mod developer_signals;
@@ -7,6 +8,8 @@ pub mod external_json;
 pub mod mdn_data;
 pub mod npm;
 pub mod popularities;
+pub mod status;
 pub mod web_ext_examples;
 pub mod web_features;
 pub mod webref_css;
+pub mod webref_idl;
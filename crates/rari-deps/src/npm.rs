@@ -2,22 +2,85 @@ use std::fs;
 use std::io::Read;
 use std::path::{Path, PathBuf};
 
+use base64::prelude::*;
 use chrono::{Duration, Utc};
 use flate2::read::GzDecoder;
 use indexmap::IndexMap;
+use rari_types::globals::deps_sources;
 use rari_utils::io::read_to_string;
 use semver::{Version, VersionReq};
 use serde::Deserialize;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
 use tar::Archive;
 use url::Url;
 
-use crate::client::get;
+use crate::client::{get, join_url};
 use crate::current::Current;
 use crate::error::DepsError;
 
 #[derive(Deserialize, Debug, Clone)]
 struct Dist {
     tarball: Url,
+    /// Subresource Integrity hash of the tarball, e.g. `sha512-…`.
+    integrity: Option<String>,
+    /// Hex SHA-1 of the tarball, the only hash of old registry entries.
+    shasum: Option<String>,
+}
+
+impl Dist {
+    /// The Subresource Integrity string to verify the tarball with, falling
+    /// back to the `shasum` as `sha1-…`.
+    fn integrity(&self) -> Option<String> {
+        self.integrity.clone().or_else(|| {
+            let shasum = base16ct::mixed::decode_vec(self.shasum.as_deref()?).ok()?;
+            Some(format!("sha1-{}", BASE64_STANDARD.encode(shasum)))
+        })
+    }
+}
+
+/// Supported integrity algorithms, from weakest to strongest.
+const ALGORITHMS: &[&str] = &["sha1", "sha256", "sha384", "sha512"];
+
+/// The downloaded tarball, kept next to the unpacked package for `verify_package`.
+const TARBALL: &str = "package.tgz";
+
+fn digest_base64<D: Digest>(data: &[u8]) -> String {
+    BASE64_STANDARD.encode(D::digest(data))
+}
+
+/// Checks `data` against the Subresource Integrity string `integrity`.
+///
+/// The SRI string may contain several space-separated hashes. Like browsers,
+/// only the hashes with the strongest supported algorithm are used, and `data`
+/// is valid if it matches one of them.
+fn check_integrity(package_name: &str, integrity: &str, data: &[u8]) -> Result<(), DepsError> {
+    let hashes = integrity
+        .split_whitespace()
+        .filter_map(|hash| {
+            let (algorithm, expected) = hash.split_once('-')?;
+            let strength = ALGORITHMS.iter().position(|a| *a == algorithm)?;
+            // options after `?` are reserved and ignored
+            Some((strength, expected.split('?').next().unwrap_or_default()))
+        })
+        .collect::<Vec<_>>();
+    let Some(strongest) = hashes.iter().map(|(strength, _)| *strength).max() else {
+        return Err(DepsError::IntegrityMissing(package_name.to_string()));
+    };
+    let actual = match ALGORITHMS[strongest] {
+        "sha1" => digest_base64::<Sha1>(data),
+        "sha256" => digest_base64::<Sha256>(data),
+        "sha384" => digest_base64::<Sha384>(data),
+        _ => digest_base64::<Sha512>(data),
+    };
+    if hashes
+        .iter()
+        .any(|(strength, expected)| *strength == strongest && *expected == actual)
+    {
+        Ok(())
+    } else {
+        Err(DepsError::IntegrityMismatch(package_name.to_string()))
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -26,13 +89,24 @@ struct VersionEntry {
     dist: Dist,
 }
 
+#[derive(Deserialize, Debug, Clone)]
+struct DistTags {
+    latest: Option<Version>,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 struct Package {
     versions: IndexMap<Version, VersionEntry>,
+    #[serde(rename = "dist-tags")]
+    dist_tags: Option<DistTags>,
 }
 
-fn get_version(package_name: &str, version_req: &VersionReq) -> Result<VersionEntry, DepsError> {
-    let package: Package = get(format!("https://registry.npmjs.org/{package_name}"))?.json()?;
+fn get_version(
+    registry_url: &str,
+    package_name: &str,
+    version_req: &VersionReq,
+) -> Result<VersionEntry, DepsError> {
+    let package: Package = serde_json::from_reader(get(join_url(registry_url, package_name))?)?;
     if let Some((_, entry)) = package
         .versions
         .iter()
@@ -53,6 +127,47 @@ fn get_version(package_name: &str, version_req: &VersionReq) -> Result<VersionEn
     }
 }
 
+/// The newest version of `package_name` in the registry matching
+/// `version_req` (if any) and the newest release overall.
+pub fn latest_versions(
+    package_name: &str,
+    version_req: &VersionReq,
+) -> Result<(Option<Version>, Option<Version>), DepsError> {
+    let package: Package = serde_json::from_reader(get(join_url(
+        &deps_sources().npm_registry_url,
+        package_name,
+    ))?)?;
+    Ok(package_latest_versions(&package, version_req))
+}
+
+/// See [`latest_versions`]. Versions are compared by semver, not by their
+/// order in the registry, which is the publishing order. The newest release
+/// is the `latest` dist-tag, or the highest version without prerelease.
+fn package_latest_versions(
+    package: &Package,
+    version_req: &VersionReq,
+) -> (Option<Version>, Option<Version>) {
+    let matching = package
+        .versions
+        .keys()
+        .filter(|k| version_req.matches(k))
+        .max()
+        .cloned();
+    let latest = package
+        .dist_tags
+        .as_ref()
+        .and_then(|tags| tags.latest.clone())
+        .or_else(|| {
+            package
+                .versions
+                .keys()
+                .filter(|k| k.pre.is_empty())
+                .max()
+                .cloned()
+        });
+    (matching, latest)
+}
+
 /// Download and unpack an npm package for a given version
 pub fn get_package(
     package: &str,
@@ -73,19 +188,32 @@ pub fn get_package(
         .map(|v| version_req.matches(v))
         .unwrap_or_default()
         || current.latest_last_check.unwrap_or_default() < now - Duration::days(1)
+        || current.integrity.is_none()
     {
-        let version_entry = get_version(package, version_req)?;
+        let version_entry = get_version(&deps_sources().npm_registry_url, package, version_req)?;
+        let integrity = version_entry.dist.integrity();
         let tarball_url = version_entry.dist.tarball;
-        let download_update = current.current_version.as_ref() != Some(&version_entry.version);
+        // caches from before integrity was recorded are downloaded again once
+        let download_update = current.current_version.as_ref() != Some(&version_entry.version)
+            || current.integrity.is_none();
 
         if download_update {
             tracing::info!("Updating {package} to {}", version_entry.version);
+            let mut buf = vec![];
+            let _ = get(tarball_url)?.read_to_end(&mut buf)?;
+            // verify before touching the cache, so a bad download keeps the old version
+            check_integrity(
+                package,
+                integrity
+                    .as_deref()
+                    .ok_or_else(|| DepsError::IntegrityMissing(package.to_string()))?,
+                &buf,
+            )?;
             if package_path.exists() {
                 fs::remove_dir_all(&package_path)?;
             }
             fs::create_dir_all(&package_path)?;
-            let mut buf = vec![];
-            let _ = get(tarball_url)?.read_to_end(&mut buf)?;
+            fs::write(package_path.join(TARBALL), &buf)?;
             let gz = GzDecoder::new(&buf[..]);
             let mut ar = Archive::new(gz);
             ar.unpack(&package_path)?;
@@ -96,6 +224,11 @@ pub fn get_package(
             serde_json::to_string_pretty(&Current {
                 current_version: Some(version_entry.version),
                 latest_last_check: Some(now),
+                integrity: if download_update {
+                    integrity
+                } else {
+                    current.integrity
+                },
             })?,
         )?;
         if download_update {
@@ -105,13 +238,184 @@ pub fn get_package(
     Ok(None)
 }
 
+/// Re-verifies the cached npm package `package` in `out_path`.
+///
+/// The kept tarball must match the integrity recorded when it was
+/// downloaded, and every file in it must be unpacked unchanged. Returns the
+/// verified version.
+pub fn verify_package(package: &str, out_path: &Path) -> Result<Version, DepsError> {
+    let package_path = out_path.join(package);
+    let current = read_to_string(package_path.join("last_check.json"))
+        .ok()
+        .and_then(|current| serde_json::from_str::<Current>(&current).ok())
+        .unwrap_or_default();
+    let (Some(version), Some(integrity)) = (current.current_version, current.integrity) else {
+        return Err(DepsError::IntegrityMissing(package.to_string()));
+    };
+    let buf = fs::read(package_path.join(TARBALL))?;
+    check_integrity(package, &integrity, &buf)?;
+
+    let mut ar = Archive::new(GzDecoder::new(&buf[..]));
+    for entry in ar.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry.path()?.into_owned();
+        let mut expected = vec![];
+        entry.read_to_end(&mut expected)?;
+        if fs::read(package_path.join(&path)).ok().as_ref() != Some(&expected) {
+            return Err(DepsError::CacheModified(format!(
+                "{package}/{}",
+                path.display()
+            )));
+        }
+    }
+    Ok(version)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn test_check_integrity() {
+        let data = b"rari";
+        let sha512 = format!("sha512-{}", digest_base64::<Sha512>(data));
+        assert!(check_integrity("pkg", &sha512, data).is_ok());
+        assert!(check_integrity("pkg", &format!("sha1-abc {sha512}?opt"), data).is_ok());
+        assert!(matches!(
+            check_integrity("pkg", &sha512, b"tampered"),
+            Err(DepsError::IntegrityMismatch(_))
+        ));
+        let sha1 = format!("sha1-{}", digest_base64::<Sha1>(data));
+        assert!(check_integrity("pkg", &sha1, data).is_ok());
+        // only the strongest algorithm counts
+        assert!(matches!(
+            check_integrity("pkg", &format!("{sha1} sha512-abc"), data),
+            Err(DepsError::IntegrityMismatch(_))
+        ));
+        assert!(matches!(
+            check_integrity("pkg", "md5-abc", data),
+            Err(DepsError::IntegrityMissing(_))
+        ));
+    }
+
+    #[test]
+    fn test_dist_integrity_from_shasum() {
+        let data = b"rari";
+        let dist = serde_json::from_value::<Dist>(serde_json::json!({
+            "tarball": "file:///mirror/pkg-1.0.0.tgz",
+            "shasum": base16ct::lower::encode_string(&Sha1::digest(data)),
+        }))
+        .unwrap();
+        let integrity = dist.integrity().unwrap();
+        assert!(integrity.starts_with("sha1-"));
+        assert!(check_integrity("pkg", &integrity, data).is_ok());
+        let dist = Dist {
+            integrity: Some("sha512-abc".to_string()),
+            ..dist
+        };
+        assert_eq!(dist.integrity().as_deref(), Some("sha512-abc"));
+    }
+
+    #[test]
+    fn test_verify_package() {
+        let tmp = tempfile::tempdir().unwrap();
+        let package_path = tmp.path().join("pkg");
+        fs::create_dir_all(&package_path).unwrap();
+
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            vec![],
+            flate2::Compression::default(),
+        ));
+        let mut header = tar::Header::new_gnu();
+        header.set_size(2);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "package/data.json", &b"{}"[..])
+            .unwrap();
+        let tarball = builder.into_inner().unwrap().finish().unwrap();
+        fs::write(package_path.join(TARBALL), &tarball).unwrap();
+        Archive::new(GzDecoder::new(&tarball[..]))
+            .unpack(&package_path)
+            .unwrap();
+        fs::write(
+            package_path.join("last_check.json"),
+            serde_json::to_string(&Current {
+                latest_last_check: None,
+                current_version: Some(Version::new(1, 0, 0)),
+                integrity: Some(format!("sha512-{}", digest_base64::<Sha512>(&tarball))),
+            })
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            verify_package("pkg", tmp.path()).unwrap(),
+            Version::new(1, 0, 0)
+        );
+        fs::write(package_path.join("package/data.json"), "[]").unwrap();
+        assert!(matches!(
+            verify_package("pkg", tmp.path()),
+            Err(DepsError::CacheModified(path)) if path == "pkg/package/data.json"
+        ));
+    }
+
+    #[test]
+    fn test_get_version_from_mirror() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::create_dir_all(tmp.path().join("@webref")).unwrap();
+        fs::write(
+            tmp.path().join("@webref/css"),
+            r#"{"versions": {
+                "7.0.0": {"version": "7.0.0", "dist": {"tarball": "file:///mirror/css-7.0.0.tgz"}},
+                "8.1.0": {"version": "8.1.0", "dist": {"tarball": "file:///mirror/css-8.1.0.tgz"}}
+            }}"#,
+        )
+        .unwrap();
+        let registry = Url::from_directory_path(tmp.path()).unwrap();
+        let e = get_version(
+            registry.as_str(),
+            "@webref/css",
+            &VersionReq::parse("^7").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(e.version, Version::new(7, 0, 0));
+        assert_eq!(e.dist.tarball.as_str(), "file:///mirror/css-7.0.0.tgz");
+    }
+
+    #[test]
+    fn test_package_latest_versions() {
+        let mut package: Package = serde_json::from_str(
+            r#"{"versions": {
+                "5.6.0": {"version": "5.6.0", "dist": {"tarball": "file:///a.tgz"}},
+                "6.0.0": {"version": "6.0.0", "dist": {"tarball": "file:///a.tgz"}},
+                "5.7.0": {"version": "5.7.0", "dist": {"tarball": "file:///a.tgz"}},
+                "7.0.0-beta.1": {"version": "7.0.0-beta.1", "dist": {"tarball": "file:///a.tgz"}},
+                "5.6.1": {"version": "5.6.1", "dist": {"tarball": "file:///a.tgz"}}
+            }}"#,
+        )
+        .unwrap();
+        let req = VersionReq::parse("~5.6").unwrap();
+        assert_eq!(
+            package_latest_versions(&package, &req),
+            (Some(Version::new(5, 6, 1)), Some(Version::new(6, 0, 0)))
+        );
+        package.dist_tags = Some(DistTags {
+            latest: Some(Version::new(5, 7, 0)),
+        });
+        assert_eq!(
+            package_latest_versions(&package, &req).1,
+            Some(Version::new(5, 7, 0))
+        );
+    }
+
     #[test]
     fn test_get_version() {
         let e = get_version(
+            "https://registry.npmjs.org",
             "/@mdn/browser-compat-data",
             &VersionReq::parse("5.6.33").unwrap(),
         )
@@ -4,9 +4,11 @@ use std::path::{Path, PathBuf};
 
 use chrono::{DateTime, Datelike, Utc};
 use rari_types::Popularities;
+use rari_types::globals::deps_sources;
 use rari_utils::io::read_to_string;
 use serde::Deserialize;
 
+use crate::client::get;
 use crate::current::Current;
 use crate::error::DepsError;
 
@@ -18,7 +20,6 @@ pub struct PopularityRow {
     pub page_views: f64,
 }
 
-const CURRENT_URL: &str = "https://popularities.mdn.mozilla.net/current.csv";
 const LIMIT: usize = 20_000;
 
 fn should_update(now: &DateTime<Utc>, current: &Option<DateTime<Utc>>) -> bool {
@@ -49,7 +50,7 @@ pub fn update_popularities(base_path: &Path) -> Result<Option<PathBuf>, DepsErro
         };
 
         let mut max = f64::INFINITY;
-        let pop_csv = reqwest::blocking::get(CURRENT_URL).expect("unable to download popularities");
+        let pop_csv = get(&deps_sources().popularities_url)?;
         let mut rdr = csv::Reader::from_reader(pop_csv);
         for row in rdr.deserialize::<PopularityRow>().flatten().take(LIMIT) {
             if row.page.contains("/docs/") && !row.page.contains(['$', '?']) {
@@ -78,6 +79,7 @@ pub fn update_popularities(base_path: &Path) -> Result<Option<PathBuf>, DepsErro
             serde_json::to_string_pretty(&Current {
                 current_version: None,
                 latest_last_check: Some(now),
+                integrity: None,
             })?,
         )?;
         return Ok(Some(package_path));
@@ -0,0 +1,110 @@
+//! Versions and freshness of the dependency data.
+
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use rari_types::globals::deps;
+use rari_utils::io::read_to_string;
+use semver::{Version, VersionReq};
+use serde::Serialize;
+
+use crate::current::Current;
+use crate::npm::latest_versions;
+
+/// Dependencies which are not npm packages, by their folder in the data dir.
+const EXTERNAL_DEPS: &[&str] = &["developer_signals", "popularities", "web_ext_examples"];
+
+#[derive(Serialize, Debug)]
+pub struct DepStatus {
+    pub name: String,
+    /// The pinned version range, for npm packages.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range: Option<VersionReq>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub installed: Option<Version>,
+    pub last_check: Option<DateTime<Utc>>,
+    /// The newest version within `range`, if newer than `installed`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update: Option<Version>,
+    /// The newest version outside of `range`, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest: Option<Version>,
+}
+
+fn current(base_path: &Path, name: &str) -> Current {
+    read_to_string(base_path.join(name).join("last_check.json"))
+        .ok()
+        .and_then(|current| serde_json::from_str::<Current>(&current).ok())
+        .unwrap_or_default()
+}
+
+/// Lists all dependencies with their installed version and last check.
+///
+/// With `check_updates`, the registry is asked for available updates of the
+/// npm packages. Failed lookups are logged and leave the updates empty.
+pub fn status(base_path: &Path, check_updates: bool) -> Vec<DepStatus> {
+    let npm = deps().packages().into_iter().map(|(name, range)| {
+        let current = current(base_path, name);
+        let (update, latest) = if check_updates {
+            match latest_versions(name, range) {
+                Ok((matching, latest)) => (
+                    matching.filter(|v| current.current_version.as_ref() < Some(v)),
+                    latest.filter(|v| !range.matches(v)),
+                ),
+                Err(e) => {
+                    tracing::warn!("Unable to check for updates of {name}: {e}");
+                    (None, None)
+                }
+            }
+        } else {
+            (None, None)
+        };
+        DepStatus {
+            name: name.to_string(),
+            range: Some(range.clone()),
+            installed: current.current_version,
+            last_check: current.latest_last_check,
+            update,
+            latest,
+        }
+    });
+    let external = EXTERNAL_DEPS.iter().map(|name| DepStatus {
+        name: name.to_string(),
+        range: None,
+        installed: None,
+        last_check: current(base_path, name).latest_last_check,
+        update: None,
+        latest: None,
+    });
+    npm.chain(external).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_status() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::create_dir_all(tmp.path().join("mdn-data")).unwrap();
+        fs::write(
+            tmp.path().join("mdn-data/last_check.json"),
+            r#"{"latest_last_check":"2026-01-02T03:04:05Z","current_version":"2.1.0"}"#,
+        )
+        .unwrap();
+
+        let status = status(tmp.path(), false);
+        assert_eq!(status.len(), deps().packages().len() + EXTERNAL_DEPS.len());
+        let mdn_data = status.iter().find(|s| s.name == "mdn-data").unwrap();
+        assert_eq!(mdn_data.installed, Some(Version::new(2, 1, 0)));
+        assert_eq!(
+            mdn_data.last_check.map(|d| d.to_rfc3339()).as_deref(),
+            Some("2026-01-02T03:04:05+00:00")
+        );
+        assert_eq!(mdn_data.range.as_ref(), Some(&deps().mdn_data));
+        let popularities = status.iter().find(|s| s.name == "popularities").unwrap();
+        assert!(popularities.installed.is_none() && popularities.last_check.is_none());
+    }
+}
@@ -1,12 +1,18 @@
 use std::path::Path;
 
+use rari_types::globals::deps_sources;
+
+use crate::client::join_url;
 use crate::error::DepsError;
 use crate::external_json::get_json;
 
 pub fn update_web_ext_examples(base_path: &Path) -> Result<(), DepsError> {
     get_json(
         "web_ext_examples",
-        "https://raw.githubusercontent.com/mdn/webextensions-examples/main/examples.json",
+        &join_url(
+            &deps_sources().github_raw_url,
+            "mdn/webextensions-examples/main/examples.json",
+        ),
         base_path,
     )?;
     Ok(())
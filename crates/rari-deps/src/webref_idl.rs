@@ -0,0 +1,308 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use css_syntax_types::{BrowserSpec, IdlFragmentInSpec, SpecLink, WebrefIdl};
+use rari_types::globals::deps;
+use rari_utils::io::read_to_string;
+use url::Url;
+
+use crate::error::DepsError;
+use crate::npm::get_package;
+
+/// Removes `//` and `/* */` comments from WebIDL, leaving string literals intact.
+fn strip_comments(idl: &str) -> String {
+    let mut out = String::with_capacity(idl.len());
+    let mut chars = idl.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                out.push(c);
+                for c in chars.by_ref() {
+                    out.push(c);
+                    if c == '"' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push(c);
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut last = ' ';
+                for c in chars.by_ref() {
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Splits WebIDL into its top-level definitions, each ending with `;`.
+fn split_definitions(idl: &str) -> Vec<&str> {
+    let mut definitions = vec![];
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut start = 0;
+    for (i, c) in idl.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            _ if in_string => {}
+            '{' | '(' | '[' => depth += 1,
+            '}' | ')' | ']' => depth = depth.saturating_sub(1),
+            ';' if depth == 0 => {
+                let definition = idl[start..=i].trim();
+                if definition != ";" {
+                    definitions.push(definition);
+                }
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    definitions
+}
+
+/// Skips a leading extended attribute list (`[Exposed=Window]`), if any.
+fn skip_extended_attributes(definition: &str) -> &str {
+    let definition = definition.trim_start();
+    if !definition.starts_with('[') {
+        return definition;
+    }
+    let mut depth = 0usize;
+    for (i, c) in definition.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return definition[i + 1..].trim_start();
+                }
+            }
+            _ => {}
+        }
+    }
+    ""
+}
+
+#[derive(Debug, PartialEq)]
+enum Definition<'a> {
+    Named(&'a str),
+    Includes(&'a str, &'a str),
+}
+
+/// Determines the name a top-level WebIDL definition defines.
+fn definition_name(definition: &str) -> Option<Definition<'_>> {
+    let definition = skip_extended_attributes(definition);
+    let header = definition
+        .split(['{', '=', ';', '(', ':'])
+        .next()
+        .unwrap_or_default();
+    let words = header.split_whitespace().collect::<Vec<_>>();
+    match words.as_slice() {
+        [interface, "includes", mixin] => Some(Definition::Includes(interface, mixin)),
+        ["typedef", ..] => {
+            let name = definition.trim_end_matches(';').split_whitespace().last()?;
+            Some(Definition::Named(name))
+        }
+        [.., name] => Some(Definition::Named(name)),
+        [] => None,
+    }
+}
+
+fn parse_idl(idl: &str, spec: &SpecLink, webref_idl: &mut WebrefIdl) {
+    let idl = strip_comments(idl);
+    for definition in split_definitions(&idl) {
+        match definition_name(definition) {
+            Some(Definition::Named(name)) => {
+                webref_idl
+                    .definitions
+                    .entry(name.to_string())
+                    .or_default()
+                    .push(IdlFragmentInSpec {
+                        fragment: definition.to_string(),
+                        href: None,
+                        spec: spec.clone(),
+                    });
+            }
+            Some(Definition::Includes(interface, mixin)) => {
+                let mixins = webref_idl
+                    .includes
+                    .entry(interface.to_string())
+                    .or_default();
+                if !mixins.iter().any(|m| m == mixin) {
+                    mixins.push(mixin.to_string());
+                }
+            }
+            None => {}
+        }
+    }
+}
+
+/// Maps spec shortnames (and series shortnames) to their spec link from the
+/// browser-specs package.
+fn shortname_to_spec(base_path: &Path) -> BTreeMap<String, SpecLink> {
+    let index_json_path = base_path
+        .join("browser-specs")
+        .join("package")
+        .join("index.json");
+    let Ok(specs) = read_to_string(index_json_path)
+        .map_err(DepsError::from)
+        .and_then(|text| Ok(serde_json::from_str::<Vec<BrowserSpec>>(&text)?))
+    else {
+        tracing::warn!("could not read browser-specs, IDL spec links will not be populated");
+        return BTreeMap::new();
+    };
+    let mut shortnames = BTreeMap::new();
+    for spec in specs {
+        let Ok(url) = Url::parse(&spec.url) else {
+            continue;
+        };
+        let link = SpecLink {
+            title: spec.title,
+            url,
+        };
+        shortnames
+            .entry(spec.series.shortname)
+            .or_insert_with(|| link.clone());
+        shortnames.insert(spec.shortname, link);
+    }
+    shortnames
+}
+
+fn transform(folder: &Path, specs: &BTreeMap<String, SpecLink>) -> Result<WebrefIdl, DepsError> {
+    let mut paths = fs::read_dir(folder.join("package"))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "idl"))
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    let mut webref_idl = WebrefIdl::default();
+    for path in paths {
+        let shortname = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default();
+        let spec = match specs.get(shortname) {
+            Some(spec) => spec.clone(),
+            None => SpecLink {
+                title: shortname.to_string(),
+                url: Url::parse(&format!("https://www.w3.org/TR/{shortname}/"))?,
+            },
+        };
+        parse_idl(&read_to_string(&path)?, &spec, &mut webref_idl);
+    }
+    if webref_idl.definitions.is_empty() {
+        return Err(DepsError::WebRefParseError(
+            "Webref-IDL data contains no definitions".to_string(),
+        ));
+    }
+    Ok(webref_idl)
+}
+
+// The `@webref/idl` package ships one raw `.idl` file per spec, named after the spec's
+// shortname. We split these into definitions and link each to its spec via browser-specs.
+pub fn update_webref_idl(base_path: &Path) -> Result<(), DepsError> {
+    if let Some(package_path) = get_package("@webref/idl", &deps().webref_idl, base_path)? {
+        let specs = shortname_to_spec(base_path);
+        let webref_idl = transform(&package_path, &specs)?;
+        let webref_idl_dest_path = package_path.join("webref_idl.json");
+        fs::write(webref_idl_dest_path, serde_json::to_string(&webref_idl)?)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const IDL: &str = r#"
+// The Node interface.
+[Exposed=Window]
+interface Node : EventTarget {
+  const unsigned short ELEMENT_NODE = 1;
+  readonly attribute DOMString nodeName; /* a comment; with a semicolon */
+  Node appendChild(Node node);
+};
+
+[Exposed=Window]
+partial interface Node {
+  boolean isSameNode(Node? otherNode); // "legacy"
+};
+
+interface mixin ParentNode {
+  readonly attribute unsigned long childElementCount;
+};
+Node includes ParentNode;
+
+typedef (DOMString or Node) NodeOrString;
+enum ShadowRootMode { "open", "closed" };
+callback NodeCallback = undefined (Node node);
+"#;
+
+    #[test]
+    fn test_definition_name() {
+        assert_eq!(
+            definition_name("[Exposed=Window]\ninterface Node : EventTarget {};"),
+            Some(Definition::Named("Node"))
+        );
+        assert_eq!(
+            definition_name("interface Node: EventTarget {};"),
+            Some(Definition::Named("Node"))
+        );
+        assert_eq!(
+            definition_name("Document includes ParentNode;"),
+            Some(Definition::Includes("Document", "ParentNode"))
+        );
+        assert_eq!(
+            definition_name("typedef (DOMString or Node) NodeOrString;"),
+            Some(Definition::Named("NodeOrString"))
+        );
+        assert_eq!(
+            definition_name("callback NodeCallback = undefined (Node node);"),
+            Some(Definition::Named("NodeCallback"))
+        );
+    }
+
+    #[test]
+    fn test_parse_idl() {
+        let spec = SpecLink {
+            title: "DOM Standard".to_string(),
+            url: Url::parse("https://dom.spec.whatwg.org/").unwrap(),
+        };
+        let mut webref_idl = WebrefIdl::default();
+        parse_idl(IDL, &spec, &mut webref_idl);
+
+        let node = &webref_idl.definitions["Node"];
+        assert_eq!(node.len(), 2);
+        assert!(
+            node[0]
+                .fragment
+                .starts_with("[Exposed=Window]\ninterface Node")
+        );
+        assert!(node[0].fragment.ends_with("};"));
+        assert!(!node[0].fragment.contains("a comment"));
+        assert!(node[1].fragment.contains("isSameNode"));
+        assert_eq!(node[1].spec, spec);
+        assert_eq!(webref_idl.includes["Node"], vec!["ParentNode"]);
+        for name in [
+            "ParentNode",
+            "NodeOrString",
+            "ShadowRootMode",
+            "NodeCallback",
+        ] {
+            assert!(webref_idl.definitions.contains_key(name), "{name}");
+        }
+    }
+}
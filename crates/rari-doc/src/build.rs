@@ -8,25 +8,27 @@ use std::collections::HashMap;
 use std::fs::{self, File};
 use std::io::{BufWriter, Write};
 use std::iter::once;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use chrono::NaiveDateTime;
 use itertools::Itertools;
 use rari_types::globals::{
     base_url, blog_root, build_out_root, contributor_spotlight_root, curriculum_root,
-    generic_content_root, git_history, settings,
+    generic_content_root, git_history, other_git_history, settings,
 };
 use rari_types::locale::{Locale, LocaleFilter, default_locale};
+use rari_utils::concat_strs;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use sha2::{Digest, Sha256};
 use tracing::{Level, span};
 
 use crate::cached_readers::{
-    blog_files, contributor_spotlight_files, curriculum_files, generic_content_files,
-    wiki_histories,
+    SPAData, blog_files, contributor_spotlight_files, curriculum_files, generic_content_config,
+    generic_content_files, wiki_histories,
 };
 use crate::contributors::contributors_txt;
 use crate::error::DocError;
+use crate::html::code::live_sample_html;
 use crate::issues::{IN_MEMORY, to_display_issues};
 use crate::pages::build::copy_additional_files;
 use crate::pages::json::{BuiltPage, JsonDocMetadata};
@@ -40,6 +42,37 @@ pub struct SitemapMeta<'a> {
     pub url: Cow<'a, str>,
     pub modified: Option<NaiveDateTime>,
     pub locale: Locale,
+    /// All locales the page is available in, including its own.
+    pub translations: Vec<Locale>,
+}
+
+/// The last modification of a non-docs page, from the git history of its root.
+fn other_modified(page: &Page) -> Option<NaiveDateTime> {
+    if let Page::SPA(spa) = page {
+        return spa_modified(spa);
+    }
+    other_git_history()
+        .get(page.full_path())
+        .map(|entry| entry.modified)
+}
+
+/// SPAs have no source file: blog indices change with their newest post and
+/// SPAs configured in the generic content with its `config.json`.
+fn spa_modified(spa: &SPA) -> Option<NaiveDateTime> {
+    match spa.data {
+        SPAData::BlogIndex(_) => {
+            let blog_root = blog_root()?;
+            other_git_history()
+                .iter()
+                .filter(|(path, _)| path.starts_with(blog_root))
+                .map(|(_, entry)| entry.modified)
+                .max()
+        }
+        _ if generic_content_config().spas.contains_key(spa.slug) => other_git_history()
+            .get(&generic_content_root()?.join("config.json"))
+            .map(|entry| entry.modified),
+        _ => None,
+    }
 }
 
 /// Builds a single documentation page and writes the output to a JSON file.
@@ -86,6 +119,9 @@ pub fn build_single_page(page: &Page) -> Result<(BuiltPage, String), DocError> {
         .expect("No BUILD_OUT_ROOT")
         .join(url_to_folder_path(page.url().trim_start_matches('/')));
     fs::create_dir_all(&out_path)?;
+    if settings().live_sample_pages {
+        write_live_sample_pages(&mut built_page, &out_path, page.locale())?;
+    }
     let out_file = out_path.join("index.json");
     let file = File::create(out_file).unwrap();
     let mut buffed = BufWriter::new(file);
@@ -98,6 +134,35 @@ pub fn build_single_page(page: &Page) -> Result<(BuiltPage, String), DocError> {
     Ok((built_page, hash))
 }
 
+/// Writes a `_sample_.<id>.html` document for each live sample of a doc or
+/// blog post to `out_path`.
+///
+/// The live samples are removed from the built page again, unless they were
+/// requested in the JSON as well.
+fn write_live_sample_pages(
+    built_page: &mut BuiltPage,
+    out_path: &Path,
+    locale: Locale,
+) -> Result<(), DocError> {
+    let (live_samples, title) = match built_page {
+        BuiltPage::Doc(json_doc) => (&mut json_doc.doc.live_samples, &json_doc.doc.title),
+        BuiltPage::BlogPost(json_blog_post) => (
+            &mut json_blog_post.doc.live_samples,
+            &json_blog_post.doc.title,
+        ),
+        _ => return Ok(()),
+    };
+    for code in live_samples.iter().flatten() {
+        let file = File::create(out_path.join(concat_strs!("_sample_.", &code.id, ".html")))?;
+        let mut buffed = BufWriter::new(file);
+        buffed.write_all(live_sample_html(code, title, locale).as_bytes())?;
+    }
+    if !settings().json_live_samples {
+        *live_samples = None;
+    }
+    Ok(())
+}
+
 pub fn build_single_doc(page: &Page) -> Result<JsonDocMetadata, DocError> {
     let (built_doc, hash) = build_single_page(page)?;
     if let BuiltPage::Doc(json) = built_doc {
@@ -160,6 +225,11 @@ pub fn build_docs<'a, 'b: 'a>(
                         url: Cow::Borrowed(page.url()),
                         locale: page.locale(),
                         modified,
+                        translations: meta
+                            .other_translations
+                            .iter()
+                            .map(|translation| translation.locale)
+                            .collect(),
                     },
                     meta,
                 )
@@ -214,6 +284,7 @@ pub fn build_curriculum_pages<'a>() -> Result<Vec<SitemapMeta<'a>>, DocError> {
             build_single_page(page).map(|_| SitemapMeta {
                 url: Cow::Owned(page.url().to_string()),
                 locale: page.locale(),
+                modified: other_modified(page),
                 ..Default::default()
             })
         })
@@ -293,6 +364,7 @@ pub fn build_blog_pages<'a>() -> Result<Vec<SitemapMeta<'a>>, DocError> {
             build_single_page(page).map(|_| SitemapMeta {
                 url: Cow::Owned(page.url().to_string()),
                 locale: page.locale(),
+                modified: other_modified(page),
                 ..Default::default()
             })
         })
@@ -324,6 +396,7 @@ pub fn build_generic_pages<'a>() -> Result<Vec<SitemapMeta<'a>>, DocError> {
             build_single_page(page).map(|_| SitemapMeta {
                 url: Cow::Owned(page.url().to_string()),
                 locale: page.locale(),
+                modified: other_modified(page),
                 ..Default::default()
             })
         })
@@ -355,6 +428,7 @@ pub fn build_contributor_spotlight_pages<'a>() -> Result<Vec<SitemapMeta<'a>>, D
             build_single_page(page).map(|_| SitemapMeta {
                 url: Cow::Owned(page.url().to_string()),
                 locale: page.locale(),
+                modified: other_modified(page),
                 ..Default::default()
             })
         })
@@ -379,12 +453,13 @@ pub fn build_contributor_spotlight_pages<'a>() -> Result<Vec<SitemapMeta<'a>>, D
 pub fn build_spas<'a>(filter: LocaleFilter<'_>) -> Result<Vec<SitemapMeta<'a>>, DocError> {
     SPA::all(filter)
         .iter()
-        .filter_map(|(slug, locale)| SPA::from_slug(slug, *locale))
-        .map(|page| {
+        .filter_map(|(slug, locale)| Some((slug, SPA::from_slug(slug, *locale)?)))
+        .map(|(slug, page)| {
             build_single_page(&page).map(|_| SitemapMeta {
                 url: Cow::Owned(page.url().to_string()),
                 locale: page.locale(),
-                ..Default::default()
+                modified: other_modified(&page),
+                translations: SPA::locales(slug),
             })
         })
         .collect()
@@ -52,6 +52,8 @@ pub enum DocError {
     NoFrontmatter,
     #[error("Invalid frontmatter: {0}")]
     InvalidFrontmatter(#[from] serde_yaml_ng::Error),
+    #[error("Invalid frontmatter value: {0}")]
+    InvalidFrontmatterValue(#[from] validator::ValidationErrors),
     #[error("Invalid frontmatter to format: {0}")]
     InvalidFrontmatterToFmt(#[from] yaml_parser::SyntaxError),
     #[error(transparent)]
@@ -74,6 +76,10 @@ pub enum DocError {
     UnknownMacro(String),
     #[error("CSS Page type required")]
     CssPageTypeRequired,
+    #[error("IDL interface name required")]
+    IdlNameRequired,
+    #[error("No IDL found for {0}")]
+    NoIdlFound(String),
     #[error(transparent)]
     ArgError(#[from] ArgError),
     #[error("pest error: {0}")]
@@ -86,6 +92,8 @@ pub enum DocError {
     FmtError(#[from] std::fmt::Error),
     #[error("invalid templ: {0}")]
     InvalidTempl(String),
+    #[error("External templs must be registered once, before rendering")]
+    TemplsAlreadyRegistered,
     #[error("doc not found {0}")]
     DocNotFound(PathBuf),
     #[error("page({1:?}) not found {0}")]
@@ -1,9 +1,12 @@
 use std::borrow::Cow;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use ego_tree::NodeId;
 use html5ever::{QualName, ns};
+use rari_md::anchor::anchorize;
+use rari_types::Arg;
 use rari_types::globals::settings;
+use rari_types::locale::Locale;
 use rari_utils::concat_strs;
 use schemars::JsonSchema;
 use scraper::{Element, ElementRef, Html, Node, Selector};
@@ -11,6 +14,9 @@ use serde::Serialize;
 
 use super::ids::uniquify_id;
 use super::modifier::insert_attribute;
+use crate::issues::get_issue_counter;
+use crate::pages::page::PageLike;
+use crate::templ::parser::{Token, parse};
 
 #[derive(Debug, Default, Clone)]
 pub struct CodeInternal {
@@ -56,13 +62,18 @@ impl From<CodeInternal> for Code {
     }
 }
 
-pub fn code_blocks(html: &mut Html) -> Option<Vec<Code>> {
+pub fn code_blocks(html: &mut Html, page: &impl PageLike) -> Option<Vec<Code>> {
     let mut ids = HashSet::new();
     let mut update_data_live_id = Vec::new();
     let mut examples = vec![];
+    let mut source = LiveSampleSource::new(page);
+    let mut embedded = HashSet::new();
     let selector = Selector::parse("iframe[data-live-id]").ok()?;
     for iframe in html.select(&selector) {
         if let Some(id) = iframe.attr("data-live-id") {
+            let iframe_id = id;
+            embedded.insert(id);
+            let nth = source.occurrence(id);
             let src = iframe.attr("data-live-path").map(String::from);
             if let Some(mut code) = code_by_query(
                 &html.root_element(),
@@ -75,10 +86,17 @@ pub fn code_blocks(html: &mut Html) -> Option<Vec<Code>> {
             } else {
                 let id_ = uniquify_id(&mut ids, Cow::Borrowed(id));
                 if id != id_ {
+                    warn_at(
+                        source.macro_position(id, nth),
+                        "live-sample-duplicate-id",
+                        id,
+                        Some(&id_),
+                    );
                     update_data_live_id.push((iframe.id(), id_.clone().to_string()));
                 }
                 let id = id_;
 
+                let mut found = false;
                 let mut css_id = String::with_capacity(id.len() + 1);
                 css_id.push('#');
                 cssparser::serialize_identifier(&id, &mut css_id).unwrap();
@@ -94,6 +112,7 @@ pub fn code_blocks(html: &mut Html) -> Option<Vec<Code>> {
                                 code.src = src;
                                 code.id = id.to_string();
                                 examples.push(code);
+                                found = true;
                                 break;
                             } else {
                                 next = closest_parent_heading(heading)
@@ -101,6 +120,31 @@ pub fn code_blocks(html: &mut Html) -> Option<Vec<Code>> {
                         }
                     }
                 }
+                if !found {
+                    warn_at(
+                        source.macro_position(iframe_id, nth),
+                        "live-sample-missing-code",
+                        &id,
+                        None,
+                    );
+                }
+            }
+        }
+    }
+    let selector = Selector::parse("pre[class*=live-sample___]").ok()?;
+    for pre in html.select(&selector) {
+        for id in pre
+            .value()
+            .classes()
+            .filter_map(|cls| cls.strip_prefix("live-sample___"))
+        {
+            if !embedded.contains(id) {
+                warn_at(
+                    source.code_block_position(id),
+                    "live-sample-unused-code",
+                    id,
+                    None,
+                );
             }
         }
     }
@@ -134,13 +178,167 @@ pub fn code_blocks(html: &mut Html) -> Option<Vec<Code>> {
         result.push(code.into())
     }
 
-    if settings().json_live_samples {
+    if settings().json_live_samples || settings().live_sample_pages {
         Some(result)
     } else {
         None
     }
 }
 
+/// Renders a live sample as a self-contained HTML document, as served for
+/// `EmbedLiveSample` iframes under `_sample_.<id>.html`.
+pub fn live_sample_html(code: &Code, title: &str, locale: Locale) -> String {
+    concat_strs!(
+        r#"<!doctype html>
+<html lang=""#,
+        locale.as_url_str(),
+        r#"">
+  <head>
+    <meta charset="utf-8" />
+    <meta name="viewport" content="width=device-width" />
+    <meta name="robots" content="noindex, nofollow" />
+    <title>"#,
+        &html_escape::encode_text(title),
+        r#"</title>
+    <style>
+      body {
+        padding: 0;
+        margin: 0;
+      }
+
+      svg:not(:root) {
+        display: block;
+      }
+    </style>
+    <style>
+"#,
+        &code.css.replace("</style", "<\\/style"),
+        r#"
+    </style>
+  </head>
+  <body>
+"#,
+        &code.html,
+        r#"
+    <script>
+"#,
+        &code.js.replace("</script", "<\\/script"),
+        r#"
+    </script>
+  </body>
+</html>
+"#
+    )
+}
+
+/// A source position as reported in issues: line, column and end column.
+type Position = (i64, i64, i64);
+
+/// Locates live sample macros and code blocks in the markdown source of a
+/// page, so issues can point authors to them.
+///
+/// Macros are only parsed once an issue needs a position.
+struct LiveSampleSource<'a> {
+    content: &'a str,
+    fm_offset: usize,
+    macros: Option<Vec<(String, Position)>>,
+    seen: HashMap<String, usize>,
+}
+
+impl<'a> LiveSampleSource<'a> {
+    fn new(page: &'a impl PageLike) -> Self {
+        Self {
+            content: page.content(),
+            fm_offset: page.fm_offset(),
+            macros: None,
+            seen: HashMap::new(),
+        }
+    }
+
+    /// Counts the `EmbedLiveSample` calls for `id`, returning how many came
+    /// before this one.
+    fn occurrence(&mut self, id: &str) -> usize {
+        let seen = self.seen.entry(id.to_string()).or_default();
+        *seen += 1;
+        *seen - 1
+    }
+
+    /// The position of the `nth` `EmbedLiveSample` call for `id`.
+    fn macro_position(&mut self, id: &str, nth: usize) -> Option<Position> {
+        let (content, fm_offset) = (self.content, self.fm_offset);
+        self.macros
+            .get_or_insert_with(|| live_sample_macros(content, fm_offset))
+            .iter()
+            .filter(|(macro_id, _)| macro_id == id)
+            .nth(nth)
+            .map(|(_, pos)| *pos)
+    }
+
+    fn code_block_position(&self, id: &str) -> Option<Position> {
+        code_block_position(self.content, self.fm_offset, id)
+    }
+}
+
+fn live_sample_macros(content: &str, fm_offset: usize) -> Vec<(String, Position)> {
+    let Ok(tokens) = parse(content) else {
+        return vec![];
+    };
+    tokens
+        .into_iter()
+        .filter_map(|token| match token {
+            Token::Macro(mac) if mac.ident.eq_ignore_ascii_case("embedlivesample") => {
+                let id = match mac.args.into_iter().next().flatten() {
+                    Some(Arg::String(id, _)) => anchorize(&id).into_owned(),
+                    _ => String::new(),
+                };
+                let line = (mac.pos.0 + fm_offset + 1) as i64;
+                let col = (mac.pos.1 + 1) as i64;
+                let end_col = (mac.pos.1 + mac.end - mac.start) as i64;
+                Some((id, (line, col, end_col)))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn code_block_position(content: &str, fm_offset: usize, id: &str) -> Option<Position> {
+    let needle = concat_strs!("live-sample___", id);
+    content.lines().enumerate().find_map(|(i, line)| {
+        let fence = line.trim_start();
+        if !(fence.starts_with("```") || fence.starts_with("~~~")) {
+            return None;
+        }
+        let col = line.match_indices(&needle).find_map(|(col, _)| {
+            let end = col + needle.len();
+            line[end..]
+                .chars()
+                .next()
+                .is_none_or(char::is_whitespace)
+                .then_some(col)
+        })?;
+        Some((
+            (i + fm_offset + 1) as i64,
+            (col + 1) as i64,
+            (col + needle.len()) as i64,
+        ))
+    })
+}
+
+fn warn_at(pos: Option<Position>, source: &str, id: &str, renamed: Option<&str>) {
+    let (line, col, end_col) = pos.unwrap_or_default();
+    let ic = get_issue_counter();
+    tracing::warn!(
+        source = source,
+        ic = ic,
+        line = line,
+        col = col,
+        end_line = line,
+        end_col = end_col,
+        id = id,
+        renamed = renamed,
+    );
+}
+
 fn gather_code(ref_element: ElementRef) -> Option<CodeInternal> {
     let h = ref_element.value().name();
 
@@ -243,3 +441,47 @@ fn is_heading(element: &ElementRef) -> bool {
 fn is_major_heading(element: &ElementRef) -> bool {
     matches!(element.value().name(), "h1" | "h2" | "h3")
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_live_sample_html() {
+        let code = Code {
+            css: "p { color: red; }".to_string(),
+            html: "<p>Hello</p>".to_string(),
+            js: "console.log(\"</script>\");".to_string(),
+            src: None,
+            id: "hello".to_string(),
+        };
+        let html = live_sample_html(&code, "A & B", Locale::PtBr);
+        assert!(html.contains(r#"<html lang="pt-BR">"#));
+        assert!(html.contains("<title>A &amp; B</title>"));
+        assert!(html.contains("p { color: red; }"));
+        assert!(html.contains("  <body>\n<p>Hello</p>\n"));
+        assert!(html.contains(r#"console.log("<\/script>");"#));
+        assert_eq!(html.matches("</script>").count(), 1);
+    }
+
+    #[test]
+    fn test_live_sample_macros() {
+        let content =
+            "Text\n\n  {{EmbedLiveSample(\"Some Example\", 100, 200)}}\n{{EmbedLiveSample}}\n";
+        assert_eq!(
+            live_sample_macros(content, 3),
+            vec![
+                ("some_example".to_string(), (6, 3, 47)),
+                (String::new(), (7, 1, 19)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_code_block_position() {
+        let content = "live-sample___foo\n```css live-sample___foobar\n```\n```css hidden live-sample___foo\n```\n";
+        assert_eq!(code_block_position(content, 0, "foo"), Some((4, 15, 31)));
+        assert_eq!(code_block_position(content, 2, "foobar"), Some((4, 8, 27)));
+        assert_eq!(code_block_position(content, 0, "bar"), None);
+    }
+}
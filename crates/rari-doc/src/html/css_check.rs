@@ -0,0 +1,213 @@
+//! Checks of CSS code blocks against the webref grammars.
+//!
+//! With `check_css_values` enabled, every `css` code block (including live
+//! sample stylesheets) is parsed with tree-sitter, and each declaration and
+//! at-rule is looked up in the webref data. Unknown properties, values not
+//! matching the property's value definition and unknown at-rules are
+//! reported as `css-unknown-property`, `css-invalid-value` and
+//! `css-unknown-at-rule` issues at their position in the markdown source.
+
+use css_syntax::matcher::{is_known_at_rule, is_known_property, match_property};
+use tree_sitter::{Node, Parser, Point};
+
+use crate::issues::get_issue_counter;
+
+/// At-rules whose blocks contain style rules or declarations for elements.
+/// Declarations in other at-rules (`@font-face`, `@page`, …) are
+/// descriptors, not properties.
+const GROUPING_AT_RULES: &[&str] = &["@container", "@layer", "@scope", "@starting-style"];
+
+/// A declaration or at-rule with 0-based positions relative to the code
+/// block.
+#[derive(Debug, PartialEq)]
+enum CssItem {
+    Declaration {
+        property: String,
+        value: String,
+        property_start: Point,
+        value_start: Point,
+    },
+    AtRule {
+        name: String,
+        start: Point,
+    },
+}
+
+fn css_items(code: &str) -> Vec<CssItem> {
+    let mut parser = Parser::new();
+    if parser
+        .set_language(&tree_sitter_css::LANGUAGE.into())
+        .is_err()
+    {
+        return vec![];
+    }
+    let Some(tree) = parser.parse(code, None) else {
+        return vec![];
+    };
+    let mut items = vec![];
+    collect_items(tree.root_node(), code, &mut items);
+    items
+}
+
+fn collect_items(node: Node, code: &str, items: &mut Vec<CssItem>) {
+    match node.kind() {
+        "declaration" if !node.has_error() => {
+            let Some(property) = node.child(0) else {
+                return;
+            };
+            // the value runs from after the colon to the end of the declaration
+            let Some(value) = node.child(2) else {
+                return;
+            };
+            let text = code[value.start_byte()..node.end_byte()].trim_end();
+            items.push(CssItem::Declaration {
+                property: code[property.byte_range()].to_string(),
+                value: text
+                    .strip_suffix(';')
+                    .unwrap_or(text)
+                    .trim_end()
+                    .to_string(),
+                property_start: property.start_position(),
+                value_start: value.start_position(),
+            });
+            return;
+        }
+        "at_rule" | "postcss_statement" => {
+            let Some(keyword) = node.child(0).filter(|child| child.kind() == "at_keyword") else {
+                return;
+            };
+            let name = code[keyword.byte_range()].to_ascii_lowercase();
+            items.push(CssItem::AtRule {
+                name: name.clone(),
+                start: keyword.start_position(),
+            });
+            if !GROUPING_AT_RULES.contains(&name.as_str()) {
+                return;
+            }
+        }
+        _ => {}
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_items(child, code, items);
+    }
+}
+
+/// Checks the declarations and at-rules in the CSS `code` against webref,
+/// emitting an issue for each problem.
+///
+/// `line` and `col` are the 1-based position of the opening fence in the
+/// markdown source. The code starts on the next line and is indented like
+/// the fence. Vendor-prefixed names are not checked, and values are only
+/// checked if their property is known.
+pub fn check_css(code: &str, line: i64, col: i64) {
+    for item in css_items(code) {
+        match item {
+            CssItem::Declaration {
+                property,
+                value,
+                property_start,
+                value_start,
+            } => {
+                if property.starts_with('-') && !property.starts_with("--") {
+                    continue;
+                }
+                if !is_known_property(&property) {
+                    let ic = get_issue_counter();
+                    tracing::warn!(
+                        source = "css-unknown-property",
+                        ic = ic,
+                        line = line + 1 + property_start.row as i64,
+                        col = col + property_start.column as i64,
+                        property = property,
+                    );
+                    continue;
+                }
+                // values using types webref has no grammar for can't be checked
+                if let Ok(false) = match_property(&property, &value) {
+                    let ic = get_issue_counter();
+                    tracing::warn!(
+                        source = "css-invalid-value",
+                        ic = ic,
+                        line = line + 1 + value_start.row as i64,
+                        col = col + value_start.column as i64,
+                        property = property,
+                        value = value,
+                    );
+                }
+            }
+            CssItem::AtRule { name, start } => {
+                if !name.starts_with("@-") && !is_known_at_rule(&name) {
+                    let ic = get_issue_counter();
+                    tracing::warn!(
+                        source = "css-unknown-at-rule",
+                        ic = ic,
+                        line = line + 1 + start.row as i64,
+                        col = col + start.column as i64,
+                        name = name,
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn declaration(
+        property: &str,
+        value: &str,
+        property_start: Point,
+        value_start: Point,
+    ) -> CssItem {
+        CssItem::Declaration {
+            property: property.to_string(),
+            value: value.to_string(),
+            property_start,
+            value_start,
+        }
+    }
+
+    #[test]
+    fn test_css_items() {
+        let code = "p {\n  colr: red;\n  margin: 0 auto !important\n}\n@meida screen {\n  a { color: blue; }\n}\n";
+        assert_eq!(
+            css_items(code),
+            vec![
+                declaration("colr", "red", Point::new(1, 2), Point::new(1, 8)),
+                declaration(
+                    "margin",
+                    "0 auto !important",
+                    Point::new(2, 2),
+                    Point::new(2, 10)
+                ),
+                CssItem::AtRule {
+                    name: "@meida".to_string(),
+                    start: Point::new(4, 0)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_css_items_in_at_rules() {
+        let code = "@font-face {\n  font-family: x;\n  src: url(x.woff2);\n}\n@layer base {\n  p { color: red; }\n}\n@media (width > 10px) {\n  p { color: red; }\n}\n";
+        assert_eq!(
+            css_items(code),
+            vec![
+                CssItem::AtRule {
+                    name: "@font-face".to_string(),
+                    start: Point::new(0, 0)
+                },
+                CssItem::AtRule {
+                    name: "@layer".to_string(),
+                    start: Point::new(4, 0)
+                },
+                declaration("color", "red", Point::new(5, 6), Point::new(5, 13)),
+                declaration("color", "red", Point::new(8, 6), Point::new(8, 13)),
+            ]
+        );
+    }
+}
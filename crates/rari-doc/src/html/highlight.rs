@@ -0,0 +1,409 @@
+//! Server-side syntax highlighting of code blocks.
+//!
+//! Code blocks are highlighted with tree-sitter grammars and marked up with
+//! the `token` classes used by the client-side highlighter, so pages render
+//! the same with or without JavaScript. HTTP messages and the WebAssembly
+//! text format have no tree-sitter grammar and use small tokenizers instead.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use tree_sitter::Language;
+use tree_sitter_highlight::{HighlightConfiguration, HighlightEvent, Highlighter};
+
+/// Recognized highlight names and the token class they render as.
+///
+/// tree-sitter matches capture names against these by longest prefix, so
+/// e.g. `punctuation.bracket` renders as `punctuation`.
+const HIGHLIGHTS: &[(&str, &str)] = &[
+    ("attribute", "attr-name"),
+    ("comment", "comment"),
+    ("constant", "constant"),
+    ("constant.builtin", "boolean"),
+    ("constructor", "class-name"),
+    ("function", "function"),
+    ("keyword", "keyword"),
+    ("number", "number"),
+    ("operator", "operator"),
+    ("property", "property"),
+    ("punctuation", "punctuation"),
+    ("string", "string"),
+    ("string.special.key", "property"),
+    ("tag", "tag"),
+    ("type", "class-name"),
+    ("variable.builtin", "builtin"),
+];
+
+fn configuration(
+    language: Language,
+    name: &str,
+    highlights: &str,
+    injections: &str,
+    locals: &str,
+) -> HighlightConfiguration {
+    let mut config = HighlightConfiguration::new(language, name, highlights, injections, locals)
+        .expect("invalid highlight query");
+    config.configure(&HIGHLIGHTS.iter().map(|(name, _)| *name).collect::<Vec<_>>());
+    config
+}
+
+static CONFIGURATIONS: LazyLock<HashMap<&'static str, HighlightConfiguration>> =
+    LazyLock::new(|| {
+        HashMap::from([
+            (
+                "javascript",
+                configuration(
+                    tree_sitter_javascript::LANGUAGE.into(),
+                    "javascript",
+                    tree_sitter_javascript::HIGHLIGHT_QUERY,
+                    tree_sitter_javascript::INJECTIONS_QUERY,
+                    tree_sitter_javascript::LOCALS_QUERY,
+                ),
+            ),
+            (
+                "css",
+                configuration(
+                    tree_sitter_css::LANGUAGE.into(),
+                    "css",
+                    tree_sitter_css::HIGHLIGHTS_QUERY,
+                    "",
+                    "",
+                ),
+            ),
+            (
+                "html",
+                configuration(
+                    tree_sitter_html::LANGUAGE.into(),
+                    "html",
+                    tree_sitter_html::HIGHLIGHTS_QUERY,
+                    tree_sitter_html::INJECTIONS_QUERY,
+                    "",
+                ),
+            ),
+            (
+                "json",
+                configuration(
+                    tree_sitter_json::LANGUAGE.into(),
+                    "json",
+                    tree_sitter_json::HIGHLIGHTS_QUERY,
+                    "",
+                    "",
+                ),
+            ),
+            (
+                "bash",
+                configuration(
+                    tree_sitter_bash::LANGUAGE.into(),
+                    "bash",
+                    tree_sitter_bash::HIGHLIGHT_QUERY,
+                    "",
+                    "",
+                ),
+            ),
+        ])
+    });
+
+thread_local! {
+    static HIGHLIGHTER: RefCell<Highlighter> = RefCell::new(Highlighter::new());
+}
+
+/// Maps a `brush:` name to the grammar highlighting it.
+fn grammar_for(lang: &str) -> Option<&'static str> {
+    Some(match lang {
+        "js" | "javascript" | "mjs" | "cjs" | "jsx" => "javascript",
+        "css" => "css",
+        "html" => "html",
+        "json" | "jsonc" | "json5" => "json",
+        "bash" | "sh" | "shell" | "zsh" => "bash",
+        _ => return None,
+    })
+}
+
+/// Maps a `brush:` name to the tokenizer highlighting it, for languages
+/// without a tree-sitter grammar.
+fn tokenizer_for(lang: &str) -> Option<fn(&str) -> String> {
+    Some(match lang {
+        "http" => highlight_http,
+        "wasm" | "wat" => highlight_wasm,
+        _ => return None,
+    })
+}
+
+/// Returns whether code blocks for the `brush:` name `lang` can be
+/// highlighted.
+pub fn can_highlight(lang: &str) -> bool {
+    grammar_for(lang).is_some() || tokenizer_for(lang).is_some()
+}
+
+/// Highlights `code` (plain text) as the `brush:` language `lang` and returns
+/// escaped HTML with `<span class="token …">` markup.
+///
+/// Returns `None` for languages without a grammar or if highlighting fails.
+pub fn highlight(lang: &str, code: &str) -> Option<String> {
+    if let Some(tokenizer) = tokenizer_for(lang) {
+        return Some(tokenizer(code));
+    }
+    let config = CONFIGURATIONS.get(grammar_for(lang)?)?;
+    HIGHLIGHTER.with_borrow_mut(|highlighter| {
+        let events = highlighter
+            .highlight(config, code.as_bytes(), None, |injected| {
+                CONFIGURATIONS.get(grammar_for(injected)?)
+            })
+            .ok()?;
+        let mut out = String::with_capacity(code.len() * 2);
+        for event in events {
+            match event.ok()? {
+                HighlightEvent::HighlightStart(highlight) => {
+                    out.push_str(r#"<span class="token "#);
+                    out.push_str(HIGHLIGHTS[highlight.0].1);
+                    out.push_str(r#"">"#);
+                }
+                HighlightEvent::HighlightEnd => out.push_str("</span>"),
+                HighlightEvent::Source { start, end } => {
+                    out.push_str(&html_escape::encode_text(&code[start..end]))
+                }
+            }
+        }
+        Some(out)
+    })
+}
+
+fn push_token(out: &mut String, class: &str, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    out.push_str(r#"<span class="token "#);
+    out.push_str(class);
+    out.push_str(r#"">"#);
+    out.push_str(&html_escape::encode_text(text));
+    out.push_str("</span>");
+}
+
+fn is_http_version(s: &str) -> bool {
+    s.strip_prefix("HTTP/").is_some_and(|version| {
+        !version.is_empty() && version.chars().all(|c| c.is_ascii_digit() || c == '.')
+    })
+}
+
+fn is_header_name(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c))
+}
+
+/// Highlights HTTP messages: request and status lines and the header
+/// fields following them. Bodies are left as they are.
+fn highlight_http(code: &str) -> String {
+    let mut out = String::with_capacity(code.len() * 2);
+    // Blocks often show header fields without a start line.
+    let mut in_headers = true;
+    for line in code.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\r', '\n']);
+        let eol = &line[content.len()..];
+        let parts = content.splitn(3, ' ').collect::<Vec<_>>();
+        match parts.as_slice() {
+            [version, status, reason @ ..]
+                if is_http_version(version)
+                    && status.len() == 3
+                    && status.chars().all(|c| c.is_ascii_digit()) =>
+            {
+                push_token(&mut out, "property", version);
+                out.push(' ');
+                push_token(&mut out, "number", status);
+                if let [reason] = reason {
+                    out.push(' ');
+                    push_token(&mut out, "string", reason);
+                }
+                in_headers = true;
+            }
+            [method, target, version]
+                if is_http_version(version) && method.chars().all(|c| c.is_ascii_uppercase()) =>
+            {
+                push_token(&mut out, "keyword", method);
+                out.push(' ');
+                push_token(&mut out, "url", target);
+                out.push(' ');
+                push_token(&mut out, "property", version);
+                in_headers = true;
+            }
+            _ => match content.split_once(':') {
+                Some((name, value)) if in_headers && is_header_name(name) => {
+                    push_token(&mut out, "keyword", name);
+                    push_token(&mut out, "punctuation", ":");
+                    out.push_str(&html_escape::encode_text(value));
+                }
+                _ => {
+                    if content.trim().is_empty() {
+                        in_headers = false;
+                    }
+                    out.push_str(&html_escape::encode_text(content));
+                }
+            },
+        }
+        out.push_str(eol);
+    }
+    out
+}
+
+fn is_wasm_number(s: &str) -> bool {
+    let s = s.trim_start_matches(['+', '-']);
+    s.starts_with(|c: char| c.is_ascii_digit())
+        || s == "inf"
+        || s == "nan"
+        || s.starts_with("nan:0x")
+}
+
+/// Highlights the WebAssembly text format.
+fn highlight_wasm(code: &str) -> String {
+    let mut out = String::with_capacity(code.len() * 2);
+    let mut rest = code;
+    while let Some(c) = rest.chars().next() {
+        let len = if rest.starts_with(";;") {
+            let len = rest.find('\n').unwrap_or(rest.len());
+            push_token(&mut out, "comment", &rest[..len]);
+            len
+        } else if rest.starts_with("(;") {
+            // Block comments nest.
+            let mut depth = 0;
+            let mut len = rest.len();
+            let mut i = 0;
+            while i < rest.len() {
+                if rest[i..].starts_with("(;") {
+                    depth += 1;
+                    i += 2;
+                } else if rest[i..].starts_with(";)") {
+                    depth -= 1;
+                    i += 2;
+                    if depth == 0 {
+                        len = i;
+                        break;
+                    }
+                } else {
+                    i += rest[i..].chars().next().map_or(1, char::len_utf8);
+                }
+            }
+            push_token(&mut out, "comment", &rest[..len]);
+            len
+        } else if c == '"' {
+            let mut escaped = false;
+            let len = rest[1..]
+                .char_indices()
+                .find(|&(_, c)| {
+                    let end = c == '"' && !escaped;
+                    escaped = c == '\\' && !escaped;
+                    end
+                })
+                .map_or(rest.len(), |(i, _)| i + 2);
+            push_token(&mut out, "string", &rest[..len]);
+            len
+        } else if c == '(' || c == ')' {
+            push_token(&mut out, "punctuation", &rest[..1]);
+            1
+        } else if c.is_whitespace() {
+            let len = rest
+                .find(|c: char| !c.is_whitespace())
+                .unwrap_or(rest.len());
+            out.push_str(&rest[..len]);
+            len
+        } else {
+            // An atom is at least one character, e.g. a stray `;`.
+            let len = rest[c.len_utf8()..]
+                .find(|c: char| c.is_whitespace() || matches!(c, '(' | ')' | '"' | ';'))
+                .map_or(rest.len(), |i| i + c.len_utf8());
+            let atom = &rest[..len];
+            if atom.starts_with('$') {
+                push_token(&mut out, "variable", atom);
+            } else if is_wasm_number(atom) {
+                push_token(&mut out, "number", atom);
+            } else if let Some((key, value)) = atom.split_once('=') {
+                push_token(&mut out, "keyword", key);
+                push_token(&mut out, "operator", "=");
+                push_token(&mut out, "number", value);
+            } else if atom.starts_with(|c: char| c.is_ascii_lowercase()) {
+                push_token(&mut out, "keyword", atom);
+            } else {
+                out.push_str(&html_escape::encode_text(atom));
+            }
+            len
+        };
+        rest = &rest[len..];
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_highlight_js() {
+        assert_eq!(
+            highlight("js", "const a = \"<b>\"; // hi").as_deref(),
+            Some(
+                r#"<span class="token keyword">const</span> a <span class="token operator">=</span> <span class="token string">"&lt;b&gt;"</span><span class="token punctuation">;</span> <span class="token comment">// hi</span>"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_highlight_html_injects_css() {
+        let highlighted = highlight("html", "<style>p { color: red; }</style>").unwrap();
+        assert!(highlighted.starts_with(
+            r#"<span class="token punctuation">&lt;</span><span class="token tag">style</span>"#
+        ));
+        assert!(highlighted.contains(r#"<span class="token property">color</span>"#));
+    }
+
+    #[test]
+    fn test_highlight_http() {
+        assert_eq!(
+            highlight("http", "GET /a?b=<c> HTTP/1.1\nHost: example.com\n\nx: y\n").as_deref(),
+            Some(
+                "<span class=\"token keyword\">GET</span> <span class=\"token url\">/a?b=&lt;c&gt;</span> <span class=\"token property\">HTTP/1.1</span>\n\
+                 <span class=\"token keyword\">Host</span><span class=\"token punctuation\">:</span> example.com\n\
+                 \n\
+                 x: y\n"
+            )
+        );
+        assert_eq!(
+            highlight("http", "HTTP/2 404 Not Found").as_deref(),
+            Some(
+                r#"<span class="token property">HTTP/2</span> <span class="token number">404</span> <span class="token string">Not Found</span>"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_highlight_wasm() {
+        assert_eq!(
+            highlight(
+                "wat",
+                "(func $add (param i32) ;; add\n  i32.load offset=4 (; a (; b ;) ;) \"s\\\"\")"
+            )
+            .as_deref(),
+            Some(
+                "<span class=\"token punctuation\">(</span><span class=\"token keyword\">func</span> <span class=\"token variable\">$add</span> \
+                 <span class=\"token punctuation\">(</span><span class=\"token keyword\">param</span> <span class=\"token keyword\">i32</span><span class=\"token punctuation\">)</span> \
+                 <span class=\"token comment\">;; add</span>\n  \
+                 <span class=\"token keyword\">i32.load</span> <span class=\"token keyword\">offset</span><span class=\"token operator\">=</span><span class=\"token number\">4</span> \
+                 <span class=\"token comment\">(; a (; b ;) ;)</span> \
+                 <span class=\"token string\">\"s\\\"\"</span><span class=\"token punctuation\">)</span>"
+            )
+        );
+    }
+
+    #[test]
+    fn test_highlight_wasm_stray_semicolon() {
+        assert_eq!(
+            highlight("wasm", "a;b").as_deref(),
+            Some(r#"<span class="token keyword">a</span>;b"#)
+        );
+    }
+
+    #[test]
+    fn test_highlight_unknown_language() {
+        assert!(!can_highlight("rust"));
+        assert_eq!(highlight("rust", "fn main() {}"), None);
+    }
+}
@@ -1,11 +1,14 @@
 pub mod banner;
 pub mod bubble_up;
 pub mod code;
+pub mod css_check;
 mod fix_img;
 mod fix_link;
+pub mod highlight;
 pub mod ids;
 pub mod links;
 pub mod modifier;
 pub mod rewriter;
 pub mod sections;
 pub mod sidebar;
+pub mod syntax_check;
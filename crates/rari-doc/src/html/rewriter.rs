@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use std::cell::RefCell;
 use std::collections::HashSet;
 
 use lol_html::html_content::ContentType;
@@ -11,8 +12,11 @@ use rari_utils::concat_strs;
 use url::Url;
 
 use crate::error::DocError;
+use crate::html::css_check::check_css;
 use crate::html::fix_img::handle_img;
 use crate::html::fix_link::check_and_fix_link;
+use crate::html::highlight::{can_highlight, highlight};
+use crate::html::syntax_check::{check_syntax, checked_language};
 use crate::pages::page::PageLike;
 use crate::pages::types::curriculum::Curriculum;
 
@@ -57,6 +61,15 @@ pub fn post_process_html<T: PageLike>(
     let base_url = options.base_url(Some(&base));
     let data_issues = settings().data_issues;
     let mut in_pre = false;
+    let syntax_highlighting = settings().syntax_highlighting;
+    // brush of the current code block, if it gets highlighted
+    let highlight_lang: RefCell<Option<String>> = RefCell::new(None);
+    let mut code = String::new();
+    let check_code_syntax = settings().check_code_syntax;
+    let check_css_values = settings().check_css_values;
+    // language and fence position of the current code block, if it gets checked
+    let checked_block: RefCell<Option<(&str, i64, i64)>> = RefCell::new(None);
+    let mut checked_code = String::new();
 
     let mut element_content_handlers = vec![
         element!("*[id]", |el| {
@@ -117,12 +130,38 @@ pub fn post_process_html<T: PageLike>(
             Ok(())
         }),
         text!("pre[class*=brush]", |text| {
+            if let Some((lang, line, col)) = *checked_block.borrow() {
+                checked_code.push_str(text.as_str());
+                if text.last_in_text_node() {
+                    let code = html_escape::decode_html_entities(&checked_code);
+                    if check_code_syntax {
+                        check_syntax(lang, &code, line, col);
+                    }
+                    if check_css_values && lang == "css" {
+                        check_css(&code, line, col);
+                    }
+                    checked_code.clear();
+                }
+            }
             // trim the first _empty_ line,
             // fixes issue: https://github.com/mdn/yari/issues/12364
             if !in_pre && text.as_str().starts_with('\n') {
                 text.as_mut_str().remove(0);
             }
             in_pre = true;
+            if let Some(lang) = highlight_lang.borrow().as_deref() {
+                // buffer the whole text node, lol_html may split it into chunks
+                code.push_str(text.as_str());
+                if text.last_in_text_node() {
+                    let decoded = html_escape::decode_html_entities(&code);
+                    let html =
+                        highlight(lang, &decoded).unwrap_or_else(|| std::mem::take(&mut code));
+                    text.replace(&html, ContentType::Html);
+                    code.clear();
+                } else {
+                    text.remove();
+                }
+            }
             if text.last_in_text_node() {
                 in_pre = false;
             }
@@ -141,6 +180,23 @@ pub fn post_process_html<T: PageLike>(
                 .nth(1)
                 .unwrap_or_default();
 
+            *highlight_lang.borrow_mut() =
+                (syntax_highlighting && can_highlight(name)).then(|| name.to_string());
+            *checked_block.borrow_mut() = if check_code_syntax || check_css_values {
+                checked_language(class)
+                    .zip(
+                        el.get_attribute("data-sourcepos")
+                            .as_deref()
+                            .and_then(|pos| pos.split_once('-'))
+                            .and_then(|(start, _)| start.split_once(':'))
+                            .and_then(|(line, col)| Some((line.parse().ok()?, col.parse().ok()?))),
+                    )
+                    .map(|(lang, (line, col)): (_, (i64, i64))| {
+                        (lang, line + page.fm_offset() as i64, col)
+                    })
+            } else {
+                None
+            };
             if !name.is_empty() && name != "plain" {
                 el.prepend("<code>", ContentType::Html);
                 el.append("</code>", ContentType::Html);
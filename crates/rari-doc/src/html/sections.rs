@@ -1,6 +1,9 @@
 use std::ops::Deref;
+use std::sync::LazyLock;
 
+use css_syntax::syntax::FormalSyntax;
 use scraper::{ElementRef, Html, Selector};
+use tracing::warn;
 
 use crate::error::DocError;
 
@@ -20,6 +23,8 @@ pub struct BuildSection<'a> {
     pub is_h3: bool,
     pub typ: BuildSectionType,
     pub id: Option<String>,
+    /// The formal syntax rendered in this section.
+    pub formal_syntax: Vec<FormalSyntax>,
 }
 
 pub struct Split<'a> {
@@ -28,7 +33,27 @@ pub struct Split<'a> {
     pub sidebar: Option<String>,
 }
 
-pub fn split_sections(html: &Html) -> Result<Split<'_>, DocError> {
+static FORMAL_SYNTAX_SELECTOR: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("pre.css-formal-syntax").unwrap());
+
+fn count_formal_syntax(element: ElementRef) -> usize {
+    element
+        .descendants()
+        .filter_map(ElementRef::wrap)
+        .filter(|element| FORMAL_SYNTAX_SELECTOR.matches(element))
+        .count()
+}
+
+/// Splits `html` into sections.
+///
+/// `formal_syntax` is the data of the rendered formal syntax blocks in
+/// document order (see [`crate::templ::render::Rendered`]). Each entry is
+/// assigned to the section containing the corresponding
+/// `<pre class="css-formal-syntax">`, at any depth.
+pub fn split_sections(
+    html: &Html,
+    mut formal_syntax: Vec<FormalSyntax>,
+) -> Result<Split<'_>, DocError> {
     let root_children = html.root_element().children();
     let raw_sections = root_children;
     let summary_selector = Selector::parse("html > p").unwrap();
@@ -38,9 +63,22 @@ pub fn split_sections(html: &Html) -> Result<Split<'_>, DocError> {
     });
     let mut sidebar = None;
 
+    let formal_syntax_count = html.select(&FORMAL_SYNTAX_SELECTOR).count();
+    if formal_syntax_count != formal_syntax.len() {
+        warn!(
+            "Found {formal_syntax_count} formal syntax blocks for {} rendered formal syntaxes, dropping formal syntax data",
+            formal_syntax.len()
+        );
+        formal_syntax.clear();
+    }
+
     let (mut sections, mut last) = raw_sections.fold(
         (Vec::new(), None::<BuildSection>),
         |(mut sections, mut maybe_section), current| {
+            let count = ElementRef::wrap(current)
+                .map(count_formal_syntax)
+                .unwrap_or_default()
+                .min(formal_syntax.len());
             match current.value() {
                 scraper::Node::Comment(comment) => {
                     let comment = format!("<!-- {} -->", comment.deref());
@@ -61,6 +99,7 @@ pub fn split_sections(html: &Html) -> Result<Split<'_>, DocError> {
                             query: None,
                             spec_urls: None,
                             id: None,
+                            formal_syntax: vec![],
                         });
                     }
                 }
@@ -78,6 +117,7 @@ pub fn split_sections(html: &Html) -> Result<Split<'_>, DocError> {
                                 query: None,
                                 spec_urls: None,
                                 id: None,
+                                formal_syntax: vec![],
                             });
                         }
                     }
@@ -98,6 +138,7 @@ pub fn split_sections(html: &Html) -> Result<Split<'_>, DocError> {
                             query: None,
                             spec_urls: None,
                             id,
+                            formal_syntax: vec![],
                         });
                     }
                     "section" if matches!(element.id(), Some("Quick_links" | "quick_links")) => {
@@ -133,6 +174,7 @@ pub fn split_sections(html: &Html) -> Result<Split<'_>, DocError> {
                                         query: Some(query.into()),
                                         spec_urls: None,
                                         id: None,
+                                        formal_syntax: vec![],
                                     });
                                 } else if let Some(ref mut section) = maybe_section {
                                     if section.body.is_empty() {
@@ -157,6 +199,7 @@ pub fn split_sections(html: &Html) -> Result<Split<'_>, DocError> {
                                             query: Some(query.into()),
                                             spec_urls: None,
                                             id,
+                                            formal_syntax: vec![],
                                         });
                                     }
                                 }
@@ -188,6 +231,7 @@ pub fn split_sections(html: &Html) -> Result<Split<'_>, DocError> {
                                             query: query.map(String::from),
                                             spec_urls: urls.map(String::from),
                                             id,
+                                            formal_syntax: vec![],
                                         });
                                     }
                                 }
@@ -213,6 +257,7 @@ pub fn split_sections(html: &Html) -> Result<Split<'_>, DocError> {
                                         query: None,
                                         spec_urls: None,
                                         id: None,
+                                        formal_syntax: vec![],
                                     });
                                 }
                             }
@@ -221,6 +266,10 @@ pub fn split_sections(html: &Html) -> Result<Split<'_>, DocError> {
                 },
                 _ => {}
             }
+            let data = formal_syntax.drain(..count);
+            if let Some(section) = maybe_section.as_mut() {
+                section.formal_syntax.extend(data);
+            }
             (sections, maybe_section)
         },
     );
@@ -0,0 +1,153 @@
+//! Parse checks for code blocks.
+//!
+//! With `check_code_syntax` enabled, every `js`, `json`, `css` and `html`
+//! code block is parsed with the corresponding tree-sitter grammar and each
+//! parse error is reported as a `code-syntax-error` issue at its position in
+//! the markdown source.
+
+use tree_sitter::{Language, Node, Parser, Point};
+
+use crate::issues::get_issue_counter;
+
+/// Classes marking code blocks that are invalid on purpose.
+///
+/// `example-bad` blocks show what not to do and often don't parse;
+/// `no-syntax-check` opts any other block out.
+const EXEMPT_CLASSES: &[&str] = &["example-bad", "no-syntax-check"];
+
+/// Returns the language to check a code block with the `class` of its `pre`
+/// as, or `None` if it's not checked.
+pub fn checked_language(class: &str) -> Option<&'static str> {
+    if class
+        .split_ascii_whitespace()
+        .any(|c| EXEMPT_CLASSES.contains(&c))
+    {
+        return None;
+    }
+    let name = class
+        .split_ascii_whitespace()
+        .skip_while(|s| *s != "brush:")
+        .nth(1)?;
+    ["js", "json", "css", "html"]
+        .into_iter()
+        .find(|lang| *lang == name)
+}
+
+fn grammar(lang: &str) -> Option<Language> {
+    Some(match lang {
+        "js" => tree_sitter_javascript::LANGUAGE.into(),
+        "json" => tree_sitter_json::LANGUAGE.into(),
+        "css" => tree_sitter_css::LANGUAGE.into(),
+        "html" => tree_sitter_html::LANGUAGE.into(),
+        _ => return None,
+    })
+}
+
+/// A parse error with 0-based positions relative to the code block.
+#[derive(Debug, PartialEq)]
+struct SyntaxError {
+    start: Point,
+    end: Point,
+    message: String,
+}
+
+fn syntax_errors(lang: &str, code: &str) -> Vec<SyntaxError> {
+    let mut parser = Parser::new();
+    let Some(Ok(())) = grammar(lang).map(|language| parser.set_language(&language)) else {
+        return vec![];
+    };
+    let Some(tree) = parser.parse(code, None) else {
+        return vec![];
+    };
+    let mut errors = vec![];
+    collect_errors(tree.root_node(), code, &mut errors);
+    errors
+}
+
+fn collect_errors(node: Node, code: &str, errors: &mut Vec<SyntaxError>) {
+    if node.is_missing() {
+        errors.push(SyntaxError {
+            start: node.start_position(),
+            end: node.end_position(),
+            message: format!("missing {}", node.kind()),
+        });
+    } else if node.is_error() {
+        let text = code[node.byte_range()].lines().next().unwrap_or_default();
+        errors.push(SyntaxError {
+            start: node.start_position(),
+            end: node.end_position(),
+            message: format!("unexpected `{}`", text.trim()),
+        });
+    } else if node.has_error() {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            collect_errors(child, code, errors);
+        }
+    }
+}
+
+/// Parses `code` as `lang` and emits a `code-syntax-error` issue for each
+/// error.
+///
+/// `line` and `col` are the 1-based position of the opening fence in the
+/// markdown source. The code starts on the next line and is indented like
+/// the fence.
+pub fn check_syntax(lang: &str, code: &str, line: i64, col: i64) {
+    for SyntaxError {
+        start,
+        end,
+        message,
+    } in syntax_errors(lang, code)
+    {
+        let ic = get_issue_counter();
+        tracing::warn!(
+            source = "code-syntax-error",
+            ic = ic,
+            line = line + 1 + start.row as i64,
+            col = col + start.column as i64,
+            end_line = line + 1 + end.row as i64,
+            end_col = col + end.column as i64,
+            lang = lang,
+            message = message,
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_checked_language() {
+        assert_eq!(checked_language("brush: js notranslate"), Some("js"));
+        assert_eq!(
+            checked_language("brush: css live-sample___foo"),
+            Some("css")
+        );
+        assert_eq!(checked_language("brush: js example-bad notranslate"), None);
+        assert_eq!(checked_language("brush: json no-syntax-check"), None);
+        assert_eq!(checked_language("brush: http notranslate"), None);
+        assert_eq!(checked_language("notranslate"), None);
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        assert_eq!(syntax_errors("js", "const a = 1;\nfoo(a);\n"), vec![]);
+        assert_eq!(syntax_errors("json", r#"{ "a": [1, 2] }"#), vec![]);
+        assert_eq!(syntax_errors("css", "p { color: red; }"), vec![]);
+
+        let errors = syntax_errors("js", "const a = 1;\nconst = 2;\n");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].start, Point::new(1, 0));
+        assert!(errors[0].message.starts_with("unexpected `"));
+
+        let errors = syntax_errors("json", "{\n  \"a\": 1,\n  \"b\": 2,\n}");
+        assert!(!errors.is_empty());
+        assert_eq!(errors[0].start.row, 2);
+    }
+
+    #[test]
+    fn test_unchecked_language() {
+        assert_eq!(syntax_errors("http", "GET / HTTP/1.1"), vec![]);
+    }
+}
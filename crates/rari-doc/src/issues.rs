@@ -289,7 +289,7 @@ pub struct DisplayIssue {
     pub name: IssueType,
 }
 
-#[derive(Serialize, Deserialize, Debug, Default, Clone, JsonSchema)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "PascalCase")]
 pub enum IssueType {
     TemplRedirectedLink,
@@ -297,9 +297,21 @@ pub enum IssueType {
     TemplIllCasedLink,
     TemplIllCasedArg,
     TemplInvalidArg,
+    TemplDeprecated,
     RedirectedLink,
     BrokenLink,
     IllCasedLink,
+    SidebarMissingL10n,
+    SidebarUnusedL10n,
+    SidebarDuplicateEntry,
+    LiveSampleMissingCode,
+    LiveSampleUnusedCode,
+    LiveSampleDuplicateId,
+    CodeSyntaxError,
+    CssUnknownProperty,
+    CssInvalidValue,
+    CssUnknownAtRule,
+    InvalidFrontMatter,
     #[default]
     Unknown,
 }
@@ -314,9 +326,21 @@ impl FromStr for IssueType {
             "templ-ill-cased-link" => Self::TemplIllCasedLink,
             "templ-ill-cased-arg" => Self::TemplIllCasedArg,
             "templ-invalid-arg" => Self::TemplInvalidArg,
+            "templ-deprecated" => Self::TemplDeprecated,
             "redirected-link" => Self::RedirectedLink,
             "broken-link" => Self::BrokenLink,
             "ill-cased-link" => Self::IllCasedLink,
+            "sidebar-missing-l10n" => Self::SidebarMissingL10n,
+            "sidebar-unused-l10n" => Self::SidebarUnusedL10n,
+            "sidebar-duplicate-entry" => Self::SidebarDuplicateEntry,
+            "live-sample-missing-code" => Self::LiveSampleMissingCode,
+            "live-sample-unused-code" => Self::LiveSampleUnusedCode,
+            "live-sample-duplicate-id" => Self::LiveSampleDuplicateId,
+            "code-syntax-error" => Self::CodeSyntaxError,
+            "css-unknown-property" => Self::CssUnknownProperty,
+            "css-invalid-value" => Self::CssInvalidValue,
+            "css-unknown-at-rule" => Self::CssUnknownAtRule,
+            "invalid-front-matter" => Self::InvalidFrontMatter,
             _ => Self::Unknown,
         })
     }
@@ -582,6 +606,100 @@ impl DIssue {
                         href: None,
                     }
                 }
+                IssueType::TemplDeprecated => {
+                    let source = issue_source(&mut additional);
+                    di.fixed = false;
+                    di.fixable = Some(true);
+                    di.suggestion = additional.remove("replacement");
+                    di.explanation = Some(match di.suggestion.as_deref() {
+                        Some("") | None => format!("{} is deprecated, remove it", source.label),
+                        Some(replacement) => {
+                            format!(
+                                "{} is deprecated, use {} instead",
+                                source.label, replacement
+                            )
+                        }
+                    });
+                    DIssue::Macros {
+                        display_issue: di,
+                        macro_name: source.name,
+                        href: additional.remove("macro_source"),
+                    }
+                }
+                IssueType::LiveSampleMissingCode => {
+                    di.fixable = Some(false);
+                    di.explanation = Some(format!(
+                        "Live sample {} has no code: found neither a heading with this id nor a live-sample___{} code block",
+                        additional.get("id").map(|s| s.as_str()).unwrap_or("?"),
+                        additional.get("id").map(|s| s.as_str()).unwrap_or("?")
+                    ));
+                    DIssue::Unknown { display_issue: di }
+                }
+                IssueType::LiveSampleUnusedCode => {
+                    di.fixable = Some(false);
+                    di.explanation = Some(format!(
+                        "Code block for live sample {} is not embedded by any EmbedLiveSample",
+                        additional.get("id").map(|s| s.as_str()).unwrap_or("?")
+                    ));
+                    DIssue::Unknown { display_issue: di }
+                }
+                IssueType::LiveSampleDuplicateId => {
+                    di.fixable = Some(false);
+                    di.explanation = Some(format!(
+                        "Live sample id {} is used more than once and was renamed to {}",
+                        additional.get("id").map(|s| s.as_str()).unwrap_or("?"),
+                        additional.get("renamed").map(|s| s.as_str()).unwrap_or("?")
+                    ));
+                    DIssue::Unknown { display_issue: di }
+                }
+                IssueType::CodeSyntaxError => {
+                    di.fixable = Some(false);
+                    di.explanation = Some(format!(
+                        "Syntax error in {} code block: {}",
+                        additional.get("lang").map(|s| s.as_str()).unwrap_or("?"),
+                        additional.get("message").map(|s| s.as_str()).unwrap_or("?")
+                    ));
+                    DIssue::Unknown { display_issue: di }
+                }
+                IssueType::CssUnknownProperty => {
+                    di.fixable = Some(false);
+                    di.explanation = Some(format!(
+                        "Unknown CSS property {}",
+                        additional
+                            .get("property")
+                            .map(|s| s.as_str())
+                            .unwrap_or("?")
+                    ));
+                    DIssue::Unknown { display_issue: di }
+                }
+                IssueType::CssInvalidValue => {
+                    di.fixable = Some(false);
+                    di.explanation = Some(format!(
+                        "Invalid value for CSS property {}: {}",
+                        additional
+                            .get("property")
+                            .map(|s| s.as_str())
+                            .unwrap_or("?"),
+                        additional.get("value").map(|s| s.as_str()).unwrap_or("?")
+                    ));
+                    DIssue::Unknown { display_issue: di }
+                }
+                IssueType::CssUnknownAtRule => {
+                    di.fixable = Some(false);
+                    di.explanation = Some(format!(
+                        "Unknown CSS at-rule {}",
+                        additional.get("name").map(|s| s.as_str()).unwrap_or("?")
+                    ));
+                    DIssue::Unknown { display_issue: di }
+                }
+                IssueType::InvalidFrontMatter => {
+                    di.fixable = Some(false);
+                    di.explanation = Some(format!(
+                        "Invalid front matter, using defaults: {}",
+                        additional.get("message").map(|s| s.as_str()).unwrap_or("?")
+                    ));
+                    DIssue::Unknown { display_issue: di }
+                }
                 _ => {
                     di.explanation = additional.remove("message");
                     DIssue::Unknown { display_issue: di }
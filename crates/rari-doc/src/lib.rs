@@ -60,6 +60,7 @@ pub mod translations;
 pub mod utils;
 pub mod walker;
 
+pub use rari_templ_func::rari_f;
 pub use templ::templs::Templ;
 
 #[cfg(test)]
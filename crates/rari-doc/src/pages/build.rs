@@ -43,7 +43,7 @@ use crate::pages::types::curriculum::{
     self, Curriculum, Template, build_landing_modules, build_overview_modules, build_sidebar,
     curriculum_group, prev_next_modules, prev_next_overview,
 };
-use crate::pages::types::doc::Doc;
+use crate::pages::types::doc::{Doc, TocOptions};
 use crate::pages::types::spa::SPA;
 use crate::pages::types::utils::FmTempl;
 use crate::specs::extract_specifications;
@@ -58,6 +58,7 @@ impl From<BuildSection<'_>> for Section {
                 content: value.body.join("\n"),
                 is_h3: value.is_h3,
                 id: value.id,
+                formal_syntax: value.formal_syntax,
             }),
             BuildSectionType::Specification => {
                 let title = value
@@ -149,24 +150,47 @@ pub struct PageContent {
     live_samples: Option<Vec<Code>>,
 }
 
-pub fn make_toc(sections: &[BuildSection], with_h3: bool) -> Vec<TocEntry> {
+/// Builds the table of contents, leaving out the sections with an id in
+/// `exclude` and, for excluded `h2`s, their `h3`s.
+pub fn make_toc(sections: &[BuildSection], with_h3: bool, exclude: &[String]) -> Vec<TocEntry> {
+    let has_id = |section: &BuildSection, id: &str| {
+        section
+            .id
+            .as_ref()
+            .is_some_and(|section_id| section_id.eq_ignore_ascii_case(id))
+    };
+    for id in exclude {
+        if !sections.iter().any(|section| has_id(section, id)) {
+            tracing::warn!("toc excludes unknown section: {id}");
+        }
+    }
+    let mut in_excluded = false;
     sections
         .iter()
+        .filter(|section| {
+            let is_excluded = exclude.iter().any(|id| has_id(section, id));
+            if !section.is_h3 && section.heading.is_some() {
+                in_excluded = is_excluded;
+            }
+            !(is_excluded || section.is_h3 && in_excluded)
+        })
         .filter_map(|section| section.make_toc_entry(with_h3))
         .collect()
 }
 
-fn build_content<T: PageLike>(page: &T) -> Result<PageContent, DocError> {
-    let (ks_rendered_doc, templs, sidebars) = if let Some(rari_env) = &page.rari_env() {
-        let Rendered {
-            content,
-            templs,
-            sidebars,
-        } = render(rari_env, page.content(), page.fm_offset())?;
-        (Cow::Owned(content), templs, sidebars)
-    } else {
-        (Cow::Borrowed(page.content()), vec![], vec![])
-    };
+fn build_content<T: PageLike>(page: &T, toc: Option<&TocOptions>) -> Result<PageContent, DocError> {
+    let (ks_rendered_doc, templs, sidebars, formal_syntax) =
+        if let Some(rari_env) = &page.rari_env() {
+            let Rendered {
+                content,
+                templs,
+                sidebars,
+                formal_syntax,
+            } = render(rari_env, page.content(), page.fm_offset())?;
+            (Cow::Owned(content), templs, sidebars, formal_syntax)
+        } else {
+            (Cow::Borrowed(page.content()), vec![], vec![], vec![])
+        };
     let banners = if let Some(banners) = page.banners() {
         Some(
             banners
@@ -189,12 +213,12 @@ fn build_content<T: PageLike>(page: &T) -> Result<PageContent, DocError> {
     add_missing_ids(&mut fragment)?;
     insert_self_links_for_dts(&mut fragment)?;
     expand_details_and_mark_current_for_inline_sidebar(&mut fragment, page.url())?;
-    let live_samples = code_blocks(&mut fragment);
+    let live_samples = code_blocks(&mut fragment, page);
     let Split {
         sections,
         summary,
         sidebar,
-    } = split_sections(&fragment).expect("DOOM");
+    } = split_sections(&fragment, formal_syntax).expect("DOOM");
 
     // TODO cleanup
     let mut sidebars = sidebars
@@ -210,7 +234,15 @@ fn build_content<T: PageLike>(page: &T) -> Result<PageContent, DocError> {
     } else {
         Some(sidebars.into_iter().collect::<Result<String, _>>()?)
     };
-    let toc = make_toc(&sections, matches!(page.page_type(), PageType::Curriculum));
+    let with_h3 = match toc.and_then(|toc| toc.depth) {
+        Some(depth) => depth >= 3,
+        None => page.page_type() == PageType::Curriculum,
+    };
+    let toc = make_toc(
+        &sections,
+        with_h3,
+        toc.map(|toc| toc.exclude.as_slice()).unwrap_or_default(),
+    );
     let fragments = collect_fragment_ids(&fragment);
     let body = sections.into_iter().map(Into::into).collect();
     Ok(PageContent {
@@ -240,7 +272,7 @@ fn build_doc(doc: &Doc) -> Result<BuiltPage, DocError> {
         summary,
         sidebar,
         live_samples,
-    } = build_content(doc)?;
+    } = build_content(doc, doc.meta.toc.as_ref())?;
     let sidebar_html = if sidebar.is_some() {
         sidebar
     } else {
@@ -362,7 +394,7 @@ fn build_blog_post(post: &BlogPost) -> Result<BuiltPage, DocError> {
         fragments,
         live_samples,
         ..
-    } = build_content(post)?;
+    } = build_content(post, None)?;
     Ok(BuiltPage::BlogPost(Box::new(JsonBlogPostPage {
         doc: JsonBlogPostDoc {
             title: post.title().to_string(),
@@ -402,7 +434,7 @@ fn build_blog_post(post: &BlogPost) -> Result<BuiltPage, DocError> {
 }
 
 fn build_generic_page(page: &Generic) -> Result<BuiltPage, DocError> {
-    let built = build_content(page);
+    let built = build_content(page, None);
     let PageContent {
         body,
         toc,
@@ -447,7 +479,7 @@ fn build_curriculum(curriculum: &Curriculum) -> Result<BuiltPage, DocError> {
         toc,
         fragments,
         ..
-    } = build_content(curriculum)?;
+    } = build_content(curriculum, None)?;
     let sidebar = build_sidebar().ok();
     let group = curriculum_group(&parents(curriculum));
     let modules = match curriculum.meta.template {
@@ -494,7 +526,7 @@ fn build_curriculum(curriculum: &Curriculum) -> Result<BuiltPage, DocError> {
 }
 
 fn build_contributor_spotlight(cs: &ContributorSpotlight) -> Result<BuiltPage, DocError> {
-    let PageContent { body, .. } = build_content(cs)?;
+    let PageContent { body, .. } = build_content(cs, None)?;
     let contributor_spotlight_data = ContributorSpotlightHyData {
         sections: body,
         contributor_name: cs.meta.contributor_name.clone(),
@@ -563,3 +595,60 @@ impl PageBuilder for Page {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use css_syntax::syntax::FormalSyntax;
+
+    use super::*;
+
+    fn toc_ids(html: &str, with_h3: bool, exclude: &[&str]) -> Vec<String> {
+        let fragment = Html::parse_fragment(html);
+        let Split { sections, .. } = split_sections(&fragment, vec![]).unwrap();
+        let exclude = exclude.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        make_toc(&sections, with_h3, &exclude)
+            .into_iter()
+            .map(|entry| entry.id)
+            .collect()
+    }
+
+    #[test]
+    fn test_make_toc() {
+        let html = r#"<p>Intro</p><h2 id="a">A</h2><h3 id="a1">A1</h3><h2 id="b">B</h2><h3 id="b1">B1</h3><h2 id="c">C</h2>"#;
+        assert_eq!(toc_ids(html, false, &[]), vec!["a", "b", "c"]);
+        assert_eq!(toc_ids(html, true, &[]), vec!["a", "a1", "b", "b1", "c"]);
+        assert_eq!(toc_ids(html, true, &["A", "b1"]), vec!["b", "c"]);
+    }
+
+    #[test]
+    fn test_split_sections_formal_syntax() {
+        let data = |syntax: &str| FormalSyntax {
+            name: None,
+            syntax: syntax.to_string(),
+            specs: vec![],
+            constituents: vec![],
+        };
+        let html = r#"<h2 id="a">A</h2><pre class="css-formal-syntax">a</pre><dl><dt>x</dt><dd><pre class="css-formal-syntax">b</pre></dd></dl><h2 id="b">B</h2><ul><li><pre class="css-formal-syntax">c</pre></li></ul>"#;
+        let fragment = Html::parse_fragment(html);
+        let Split { sections, .. } =
+            split_sections(&fragment, vec![data("a"), data("b"), data("c")]).unwrap();
+        let syntaxes = sections
+            .iter()
+            .map(|section| {
+                section
+                    .formal_syntax
+                    .iter()
+                    .map(|data| data.syntax.as_str())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(syntaxes, vec![vec!["a", "b"], vec!["c"]]);
+
+        let Split { sections, .. } = split_sections(&fragment, vec![data("a")]).unwrap();
+        assert!(
+            sections
+                .iter()
+                .all(|section| section.formal_syntax.is_empty())
+        );
+    }
+}
@@ -7,6 +7,7 @@
 use std::path::PathBuf;
 
 use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use css_syntax::syntax::FormalSyntax;
 use rari_data::baseline::{Baseline, BaselineStatus};
 use rari_types::fm_types::PageType;
 use rari_types::locale::{Locale, Native};
@@ -112,6 +113,8 @@ pub struct Translation {
 /// * `is_h3` - A `bool` that indicates whether the prose section's `title` will be rendered as a &lt;H3&gt;
 ///   heading. This field is serialized as `isH3`.
 /// * `content` - A `String` that holds the actual prose HTML content.
+/// * `formal_syntax` - A `Vec<FormalSyntax>` that holds the resolved CSS formal syntaxes rendered in `content`,
+///   with the syntax of each constituent. This field is serialized as `formalSyntax` and skipped if it is empty.
 #[derive(Debug, Clone, Serialize, Default, JsonSchema)]
 pub struct Prose {
     pub id: Option<String>,
@@ -119,6 +122,8 @@ pub struct Prose {
     #[serde(rename = "isH3")]
     pub is_h3: bool,
     pub content: String,
+    #[serde(rename = "formalSyntax", skip_serializing_if = "Vec::is_empty")]
+    pub formal_syntax: Vec<FormalSyntax>,
 }
 
 /// Represents a browser compatibility (BCD) section on a page, one of the possible `Section` items in the list of body sections.
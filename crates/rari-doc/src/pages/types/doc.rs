@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::fs;
 use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
@@ -11,7 +11,7 @@ use rari_types::fm_types::{FeatureStatus, PageType};
 use rari_types::locale::{Locale, default_locale};
 use rari_utils::concat_strs;
 use rari_utils::io::read_to_string;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_yaml_ng::Value;
 use tracing::debug;
 use validator::Validate;
@@ -19,6 +19,7 @@ use validator::Validate;
 use crate::cached_readers::{CACHED_DOC_PAGE_FILES, doc_page_from_static_files};
 use crate::error::DocError;
 use crate::helpers::title::{TitleFormat, render_title};
+use crate::issues::get_issue_counter;
 use crate::pages::page::{Page, PageCategory, PageLike, PageReader, PageWriter};
 use crate::pages::types::utils::FmTempl;
 use crate::redirects::resolve_redirect;
@@ -39,6 +40,37 @@ use crate::utils::{
  ]
 */
 
+/// Table of contents options from the `toc` front matter key.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Validate)]
+#[serde(default, deny_unknown_fields)]
+pub struct TocOptions {
+    /// The deepest heading level listed: 2 for `h2`s only, 3 to include
+    /// `h3`s.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 2, max = 3))]
+    pub depth: Option<u8>,
+    /// Ids of sections to leave out, including their subsections.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+}
+
+/// Parses a `toc` front matter value, rejecting unknown keys and values out
+/// of range.
+fn parse_toc(value: Value) -> Result<TocOptions, String> {
+    let toc = TocOptions::deserialize(value).map_err(|e| e.to_string())?;
+    toc.validate().map_err(|e| e.to_string())?;
+    Ok(toc)
+}
+
+/// Deserializes `toc`, falling back to the defaults if it is invalid.
+/// [`doc_from_raw`] reports invalid values as issues.
+fn lenient_toc<'de, D>(deserializer: D) -> Result<Option<TocOptions>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<Value>::deserialize(deserializer)?.and_then(|value| parse_toc(value).ok()))
+}
+
 fn is_page_type_none(page_type: &PageType) -> bool {
     matches!(page_type, PageType::None)
 }
@@ -90,8 +122,15 @@ pub struct FrontMatter {
         skip_serializing_if = "Vec::is_empty"
     )]
     pub banners: Vec<FmTempl>,
+    #[serde(
+        default,
+        deserialize_with = "lenient_toc",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[validate(nested)]
+    pub toc: Option<TocOptions>,
     #[serde(flatten)]
-    pub other: HashMap<String, Value>,
+    pub other: BTreeMap<String, Value>,
 }
 
 #[derive(Debug, Clone)]
@@ -112,6 +151,7 @@ pub struct Meta {
     pub path: PathBuf,
     pub url: String,
     pub banners: Vec<FmTempl>,
+    pub toc: Option<TocOptions>,
 }
 
 #[derive(Debug, Clone)]
@@ -349,8 +389,24 @@ pub fn doc_from_raw(raw: String, full_path: impl Into<PathBuf>) -> Result<Doc, D
         original_slug,
         sidebar,
         banners,
+        toc,
         ..
     } = serde_yaml_ng::from_str(fm)?;
+    if let Some(index) = fm.lines().position(|line| line.starts_with("toc:"))
+        && let Some(value) = serde_yaml_ng::from_str::<Value>(fm)?.get("toc")
+        && let Err(message) = parse_toc(value.clone())
+    {
+        let ic = get_issue_counter();
+        tracing::warn!(
+            source = "invalid-front-matter",
+            ic = ic,
+            file = full_path.to_string_lossy().as_ref(),
+            // The front matter starts after the opening `---` line.
+            line = index as i64 + 2,
+            col = 1i64,
+            message = message,
+        );
+    }
     let title = render_title(&title_raw, TitleFormat::Plain);
     let url = build_url(&slug, locale, PageCategory::Doc)?;
     let path = full_path
@@ -397,6 +453,7 @@ pub fn doc_from_raw(raw: String, full_path: impl Into<PathBuf>) -> Result<Doc, D
             path,
             url,
             banners,
+            toc,
         },
         raw,
         content_start,
@@ -475,7 +532,8 @@ fn write_doc(doc: &Doc) -> Result<(), DocError> {
     Ok(())
 }
 
-fn fm_to_string(fm: &FrontMatter) -> Result<String, DocError> {
+/// Serializes front matter in MDN's attribute order with double quotes.
+pub fn fm_to_string(fm: &FrontMatter) -> Result<String, DocError> {
     let fm_str = serde_yaml_ng::to_string(fm)?;
     Ok(pretty_yaml::format_text(
         &fm_str,
@@ -538,4 +596,33 @@ mod tests {
         let meta = serde_yaml_ng::from_str::<FrontMatter>(fm).unwrap();
         assert_eq!(meta.browser_compat.len(), 1);
     }
+
+    #[test]
+    fn toc_test() {
+        let fm = indoc!(
+            r#"
+            title: Foo
+            slug: foo
+            toc:
+              depth: 3
+              exclude:
+                - see_also
+            "#
+        );
+        let meta = serde_yaml_ng::from_str::<FrontMatter>(fm).unwrap();
+        let toc = meta.toc.as_ref().unwrap();
+        assert_eq!(toc.depth, Some(3));
+        assert_eq!(toc.exclude, vec!["see_also"]);
+        assert!(meta.validate().is_ok());
+        assert_eq!(fm, fm_to_string(&meta).unwrap());
+
+        // Invalid options fall back to the defaults.
+        for fm in ["toc:\n  depth: 4\n", "toc:\n  dept: 3\n"] {
+            let meta = serde_yaml_ng::from_str::<FrontMatter>(fm).unwrap();
+            assert_eq!(meta.toc, None);
+            assert!(
+                parse_toc(serde_yaml_ng::from_str::<Value>(fm).unwrap()["toc"].clone()).is_err()
+            );
+        }
+    }
 }
@@ -82,6 +82,11 @@ impl SPA {
         })
     }
 
+    /// Returns the locales the SPA `slug` is available in.
+    pub fn locales(slug: &str) -> Vec<Locale> {
+        BASIC_SPAS.get(slug).map(locales).unwrap_or_default()
+    }
+
     pub fn is_spa(slug: &str, locale: Locale) -> bool {
         BASIC_SPAS
             .get(slug)
@@ -96,13 +101,7 @@ impl SPA {
         BASIC_SPAS
             .iter()
             .flat_map(|(slug, build_spa)| {
-                let candidates: Vec<Locale> =
-                    if build_spa.en_us_only || content_translated_root().is_none() {
-                        vec![Locale::EnUs]
-                    } else {
-                        Locale::for_generic_and_spas().to_vec()
-                    };
-                candidates
+                locales(build_spa)
                     .into_iter()
                     .filter(|loc| match filter {
                         LocaleFilter::All => true,
@@ -371,6 +370,14 @@ fn blog_indices() -> Vec<(String, BuildSPA)> {
     .collect()
 }
 
+fn locales(build_spa: &BuildSPA) -> Vec<Locale> {
+    if build_spa.en_us_only || content_translated_root().is_none() {
+        vec![Locale::EnUs]
+    } else {
+        Locale::for_generic_and_spas().to_vec()
+    }
+}
+
 static BASIC_SPAS: LazyLock<HashMap<String, BuildSPA>> = LazyLock::new(|| {
     generic_content_config()
         .spas
@@ -557,6 +564,15 @@ mod test {
         assert!(pairs.iter().all(|(_, locale)| *locale == Locale::Fr));
     }
 
+    #[test]
+    fn locales_match_all() {
+        let all = SPA::all(LocaleFilter::All);
+        for (slug, locale) in &all {
+            assert!(SPA::locales(slug).contains(locale));
+        }
+        assert!(SPA::locales("does/not/exist").is_empty());
+    }
+
     #[test]
     fn all_with_empty_filter_yields_nothing() {
         assert!(SPA::all(LocaleFilter::Only(&[])).is_empty());
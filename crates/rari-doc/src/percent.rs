@@ -1,4 +1,4 @@
-use percent_encoding::{AsciiSet, CONTROLS};
+use percent_encoding::{AsciiSet, CONTROLS, percent_decode_str, utf8_percent_encode};
 
 /// A set of ASCII characters that are to be percent-encoded in URL fragments.
 ///
@@ -88,3 +88,61 @@ pub const QUERY: &AsciiSet = &CONTROLS.add(b' ').add(b'"').add(b'#').add(b'<').a
 /// The `SPECIAL_QUERY` constant is an `&AsciiSet` that includes characters from the `QUERY` set
 /// and the single quote (`'\''`) character.
 pub const SPECIAL_QUERY: &AsciiSet = &QUERY.add(b'\'');
+
+/// Percent-encodes a site-relative URL (`/en-US/docs/Web/CSS/:is()#syntax`).
+///
+/// Each path segment is encoded with [`PATH_SEGMENT`] and the fragment, if any, with the
+/// fragment percent-encode set. Existing percent-encodings are decoded first, so encoding
+/// an already encoded URL returns it unchanged.
+pub fn encode_url_path(url: &str) -> String {
+    let (path, fragment) = match url.split_once('#') {
+        Some((path, fragment)) => (path, Some(fragment)),
+        None => (url, None),
+    };
+    let mut encoded = path
+        .split('/')
+        .map(|segment| {
+            utf8_percent_encode(
+                &percent_decode_str(segment).decode_utf8_lossy(),
+                PATH_SEGMENT,
+            )
+            .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    if let Some(fragment) = fragment {
+        encoded.push('#');
+        encoded.extend(utf8_percent_encode(
+            &percent_decode_str(fragment).decode_utf8_lossy(),
+            FRAGMENT,
+        ));
+    }
+    encoded
+}
+
+/// Decodes all percent-encodings in `url`, replacing invalid UTF-8 sequences.
+pub fn decode_url_path(url: &str) -> String {
+    percent_decode_str(url).decode_utf8_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_encode_url_path() {
+        assert_eq!(
+            encode_url_path("/en-US/docs/Web/CSS/:is()#the forgiving selector"),
+            "/en-US/docs/Web/CSS/:is()#the%20forgiving%20selector"
+        );
+        assert_eq!(
+            encode_url_path("/en-US/docs/Glossary/100% width"),
+            "/en-US/docs/Glossary/100%25%20width"
+        );
+        assert_eq!(
+            encode_url_path("/en-US/docs/Glossary/100%25%20width"),
+            "/en-US/docs/Glossary/100%25%20width"
+        );
+        assert_eq!(decode_url_path("/a%20b/%3F"), "/a b/?");
+    }
+}
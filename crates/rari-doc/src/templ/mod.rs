@@ -9,5 +9,6 @@ pub mod css_feature_index;
 pub mod js_ref_index;
 pub mod legacy;
 pub mod parser;
+pub mod policy;
 pub mod render;
 pub mod templs;
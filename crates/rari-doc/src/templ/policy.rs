@@ -0,0 +1,247 @@
+//! Policies for deprecated and removed templs.
+//!
+//! Instead of being invoked, templs with a policy are dropped, rendered as
+//! text or replaced by another templ. The built-in table in `policies.json`
+//! can be extended and overridden with the `templ_policies` setting. Each use
+//! is reported as a fixable `templ-deprecated` issue whose suggestion is the
+//! replacement source.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use rari_types::globals::settings;
+use rari_types::templ::{TemplPolicy, TemplType};
+use rari_types::{AnyArg, Arg, Quotes, RariEnv};
+use rari_utils::concat_strs;
+
+use crate::error::DocError;
+use crate::issues::get_issue_counter;
+use crate::templ::templs::invoke;
+
+static BUILTIN_POLICIES: LazyLock<HashMap<String, TemplPolicy>> = LazyLock::new(|| {
+    serde_json::from_str(include_str!("policies.json"))
+        .expect("built-in templ policies (policies.json) must be valid")
+});
+
+/// The policy for the templ `name` (lowercase), if it's deprecated.
+///
+/// Like [`invoke`], `-` in `name` is treated as `_`, so policies are keyed
+/// by the `_` spelling.
+pub fn templ_policy(name: &str) -> Option<&'static TemplPolicy> {
+    let name = name.replace('-', "_");
+    settings()
+        .templ_policies
+        .get(&name)
+        .or_else(|| BUILTIN_POLICIES.get(&name))
+}
+
+/// A short note on what happens to a deprecated templ, for documentation.
+pub fn deprecation_note(policy: &TemplPolicy) -> String {
+    match policy {
+        TemplPolicy::Drop => "Deprecated: renders nothing and should be removed.".to_string(),
+        TemplPolicy::Text { .. } => {
+            "Deprecated: renders as plain text and should be replaced by it.".to_string()
+        }
+        TemplPolicy::Replace { with, .. } => {
+            concat_strs!("Deprecated: use `", with, "` instead.")
+        }
+    }
+}
+
+/// Renders the templ `name` according to `policy` and reports it.
+///
+/// `source` is the templ call as written in the markdown, e.g.
+/// `{{XULElem("button")}}`.
+pub(crate) fn apply_policy(
+    env: &RariEnv,
+    name: &str,
+    policy: &TemplPolicy,
+    source: &str,
+    args: Vec<Option<Arg>>,
+) -> Result<(String, TemplType), DocError> {
+    let replacement = replacement_source(name, policy, source, &args);
+    let ic = get_issue_counter();
+    tracing::warn!(
+        source = "templ-deprecated",
+        ic = ic,
+        macro_source = source,
+        replacement = replacement,
+    );
+    match policy {
+        TemplPolicy::Drop => Ok((String::new(), TemplType::None)),
+        TemplPolicy::Text { text } => {
+            let text = text
+                .clone()
+                .or_else(|| args.into_iter().next().flatten().map(arg_text))
+                .unwrap_or_default();
+            Ok((
+                html_escape::encode_text(&text).into_owned(),
+                TemplType::None,
+            ))
+        }
+        TemplPolicy::Replace {
+            with,
+            args: mapping,
+        } => {
+            let args = match mapping {
+                Some(mapping) => mapping.iter().map(|arg| map_arg(arg, &args)).collect(),
+                None => args,
+            };
+            invoke(env, with, args)
+        }
+    }
+}
+
+/// Maps a `Replace` argument: `$n` is the n-th original argument, anything
+/// else a string.
+fn map_arg(arg: &str, args: &[Option<Arg>]) -> Option<Arg> {
+    match arg.strip_prefix('$').and_then(|n| n.parse::<usize>().ok()) {
+        Some(n) => args.get(n.checked_sub(1)?).cloned().flatten(),
+        None => Some(Arg::String(arg.to_string(), Quotes::Double)),
+    }
+}
+
+/// The markdown that replaces `source` when fixing the issue.
+fn replacement_source(
+    name: &str,
+    policy: &TemplPolicy,
+    source: &str,
+    args: &[Option<Arg>],
+) -> String {
+    match policy {
+        TemplPolicy::Drop => String::new(),
+        TemplPolicy::Text { text: Some(text) } => text.clone(),
+        TemplPolicy::Text { text: None } => args
+            .first()
+            .cloned()
+            .flatten()
+            .map(arg_text)
+            .unwrap_or_default(),
+        TemplPolicy::Replace {
+            with,
+            args: Some(mapping),
+        } => {
+            let mut args = mapping
+                .iter()
+                .map(|arg| map_arg(arg, args))
+                .collect::<Vec<_>>();
+            while args.last().is_some_and(Option::is_none) {
+                args.pop();
+            }
+            let args = args
+                .iter()
+                .map(|arg| arg.as_ref().map(arg_source).unwrap_or(r#""""#.to_string()))
+                .collect::<Vec<_>>();
+            concat_strs!("{{", with, "(", &args.join(", "), ")}}")
+        }
+        TemplPolicy::Replace { with, args: None } => {
+            // keep the arguments as written, only swap the name
+            match source.to_ascii_lowercase().replace('-', "_").find(name) {
+                Some(start) => concat_strs!(&source[..start], with, &source[start + name.len()..]),
+                None => source.to_string(),
+            }
+        }
+    }
+}
+
+fn arg_source(arg: &Arg) -> String {
+    match arg {
+        Arg::String(s, Quotes::Double) => concat_strs!("\"", &s.replace('"', "\\\""), "\""),
+        Arg::String(s, Quotes::Single) => concat_strs!("'", &s.replace('\'', "\\'"), "'"),
+        Arg::String(s, Quotes::Back) => concat_strs!("`", &s.replace('`', "\\`"), "`"),
+        arg => arg_text(arg.clone()),
+    }
+}
+
+fn arg_text(arg: Arg) -> String {
+    AnyArg { value: arg }.to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn string(s: &str) -> Option<Arg> {
+        Some(Arg::String(s.to_string(), Quotes::Double))
+    }
+
+    #[test]
+    fn test_builtin_policies() {
+        assert_eq!(BUILTIN_POLICIES.get("xulelem"), Some(&TemplPolicy::Drop));
+    }
+
+    #[test]
+    fn test_policy_from_json() {
+        let policy: TemplPolicy = serde_json::from_str(
+            r#"{ "action": "replace", "with": "htmlelement", "args": ["$2", "x"] }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            policy,
+            TemplPolicy::Replace {
+                with: "htmlelement".to_string(),
+                args: Some(vec!["$2".to_string(), "x".to_string()])
+            }
+        );
+    }
+
+    #[test]
+    fn test_replacement_source() {
+        let args = vec![string("href"), Some(Arg::Int(2))];
+        assert_eq!(
+            replacement_source(
+                "oldxref",
+                &TemplPolicy::Drop,
+                "{{OldXref(\"href\", 2)}}",
+                &args
+            ),
+            ""
+        );
+        assert_eq!(
+            replacement_source(
+                "oldxref",
+                &TemplPolicy::Text { text: None },
+                "{{OldXref(\"href\", 2)}}",
+                &args
+            ),
+            "href"
+        );
+        assert_eq!(
+            replacement_source(
+                "oldxref",
+                &TemplPolicy::Replace {
+                    with: "newxref".to_string(),
+                    args: None
+                },
+                "{{ OldXref(\"href\", 2) }}",
+                &args
+            ),
+            "{{ newxref(\"href\", 2) }}"
+        );
+        assert_eq!(
+            replacement_source(
+                "oldxref",
+                &TemplPolicy::Replace {
+                    with: "newxref".to_string(),
+                    args: Some(vec![
+                        "$2".to_string(),
+                        "a \"b\"".to_string(),
+                        "$3".to_string()
+                    ])
+                },
+                "{{OldXref(\"href\", 2)}}",
+                &args
+            ),
+            r#"{{newxref(2, "a \"b\"")}}"#
+        );
+    }
+
+    #[test]
+    fn test_map_arg() {
+        let args = vec![string("a"), None];
+        assert_eq!(map_arg("$1", &args), string("a"));
+        assert_eq!(map_arg("$2", &args), None);
+        assert_eq!(map_arg("$0", &args), None);
+        assert_eq!(map_arg("b", &args), string("b"));
+    }
+}
@@ -1,5 +1,6 @@
 use std::fmt::Write;
 
+use css_syntax::syntax::FormalSyntax;
 use rari_md::ext::{DELIM_END, DELIM_END_LEN, DELIM_START, DELIM_START_LEN};
 use rari_types::globals::deny_warnings;
 use rari_types::templ::TemplType;
@@ -7,6 +8,8 @@ use rari_types::{AnyArg, RariEnv};
 use tracing::{Level, span, warn};
 
 use super::parser::{Token, parse};
+use super::policy::{apply_policy, templ_policy};
+use super::templs::csssyntax::take_formal_syntax;
 use super::templs::invoke;
 use crate::error::DocError;
 
@@ -14,6 +17,8 @@ pub struct Rendered {
     pub content: String,
     pub templs: Vec<String>,
     pub sidebars: Vec<String>,
+    /// The formal syntax rendered by templs, in document order.
+    pub formal_syntax: Vec<FormalSyntax>,
 }
 
 pub(crate) fn render_for_summary(input: &str) -> Result<String, DocError> {
@@ -61,6 +66,7 @@ pub(crate) fn render(env: &RariEnv, input: &str, offset: usize) -> Result<Render
     let tokens = parse(input)?;
     let mut templs = vec![];
     let mut sidebars = vec![];
+    let mut formal_syntax = vec![];
     let mut out = String::with_capacity(input.len());
     for token in tokens {
         match token {
@@ -90,7 +96,17 @@ pub(crate) fn render(env: &RariEnv, input: &str, offset: usize) -> Result<Render
                     end_col = end_col
                 );
                 let _enter = span.enter();
-                match invoke(env, &name, mac.args) {
+                let rendered = match templ_policy(&name) {
+                    Some(policy) => {
+                        apply_policy(env, &name, policy, &input[mac.start..mac.end], mac.args)
+                    }
+                    None => invoke(env, &name, mac.args),
+                };
+                let rendered_formal_syntax = take_formal_syntax();
+                if rendered.is_ok() {
+                    formal_syntax.extend(rendered_formal_syntax);
+                }
+                match rendered {
                     Ok((rendered, TemplType::Sidebar)) => {
                         encode_ref(templs.len(), &mut out, mac.end - mac.start)?;
                         templs.push(String::default());
@@ -115,6 +131,7 @@ pub(crate) fn render(env: &RariEnv, input: &str, offset: usize) -> Result<Render
         content: out,
         templs,
         sidebars,
+        formal_syntax,
     })
 }
 
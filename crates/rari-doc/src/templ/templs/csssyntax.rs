@@ -1,8 +1,14 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::sync::LazyLock;
 
-use css_syntax::syntax::{CssType, LinkedToken, SyntaxInput, render_formal_syntax};
+use css_syntax::syntax::{
+    CssType, FormalSyntax, LinkedToken, SyntaxInput, render_formal_syntax_with_data,
+    render_syntax_diagram,
+};
 use rari_templ_func::rari_f;
+use rari_types::RariEnv;
+use rari_utils::concat_strs;
 use tracing::{error, warn};
 
 use crate::error::DocError;
@@ -22,13 +28,31 @@ static TOOLTIPS: LazyLock<HashMap<LinkedToken, String>> = LazyLock::new(|| {
     (LinkedToken::DoubleAmpersand, "Double ampersand: all of the entities must be present, in any order".to_string())].into_iter().collect()
 });
 
-#[rari_f(register = "crate::Templ")]
-pub fn csssyntax(name: Option<String>) -> Result<String, DocError> {
+thread_local! {
+    static FORMAL_SYNTAX: RefCell<Vec<FormalSyntax>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Takes the formal syntax rendered on this thread since the last call.
+///
+/// `render` calls this after every templ, so the data of a page ends up in
+/// [`Rendered::formal_syntax`](crate::templ::render::Rendered) in document
+/// order, one entry per `<pre class="css-formal-syntax">`.
+pub(crate) fn take_formal_syntax() -> Vec<FormalSyntax> {
+    FORMAL_SYNTAX.with_borrow_mut(std::mem::take)
+}
+
+fn push_formal_syntax(data: FormalSyntax) {
+    FORMAL_SYNTAX.with_borrow_mut(|formal_syntax| formal_syntax.push(data));
+}
+
+/// The kind of CSS item the page of `env` documents, named `name` or after
+/// the page.
+fn css_type<'a>(env: &'a RariEnv, name: Option<&'a str>) -> Result<CssType<'a>, DocError> {
     let page_type = env.page_type;
     let mut slug_rev_iter = env.slug.rsplitn(3, '/');
     let slug_name = slug_rev_iter.next().unwrap();
-    let name = name.as_deref().unwrap_or(slug_name);
-    let typ = match page_type {
+    let name = name.unwrap_or(slug_name);
+    Ok(match page_type {
         rari_types::fm_types::PageType::CssAtRule => CssType::AtRule(name),
         rari_types::fm_types::PageType::CssAtRuleDescriptor => {
             CssType::AtRuleDescriptor(name, slug_rev_iter.next().unwrap())
@@ -64,7 +88,12 @@ pub fn csssyntax(name: Option<String>) -> Result<String, DocError> {
             error!("CSS syntax not available for page type {:?}", page_type);
             return Err(DocError::CssPageTypeRequired);
         }
-    };
+    })
+}
+
+#[rari_f(register = "crate::Templ")]
+pub fn csssyntax(name: Option<String>) -> Result<String, DocError> {
+    let typ = css_type(env, name.as_deref())?;
 
     let sources_prefix = l10n_json_data("Template", "formal_syntax_footer", env.locale)?;
 
@@ -75,7 +104,7 @@ pub fn csssyntax(name: Option<String>) -> Result<String, DocError> {
         );
     }
 
-    let html = render_formal_syntax(
+    let (html, data) = render_formal_syntax_with_data(
         SyntaxInput::Css(typ),
         env.browser_compat.first().map(|s| s.as_str()),
         env.locale.as_url_str(),
@@ -86,13 +115,15 @@ pub fn csssyntax(name: Option<String>) -> Result<String, DocError> {
         &TOOLTIPS,
         Some(sources_prefix),
     )?;
-    post_process_templ_links(&html)
+    let html = post_process_templ_links(&html)?;
+    push_formal_syntax(data);
+    Ok(html)
 }
 
 #[rari_f(register = "crate::Templ")]
 pub fn csssyntaxraw(syntax: String) -> Result<String, DocError> {
     let sources_prefix = l10n_json_data("Template", "formal_syntax_footer", env.locale)?;
-    let html = render_formal_syntax(
+    let (html, data) = render_formal_syntax_with_data(
         SyntaxInput::SyntaxString(&syntax),
         env.browser_compat.first().map(|s| s.as_str()),
         env.locale.as_url_str(),
@@ -103,5 +134,24 @@ pub fn csssyntaxraw(syntax: String) -> Result<String, DocError> {
         &TOOLTIPS,
         Some(sources_prefix),
     )?;
-    post_process_templ_links(&html)
+    let html = post_process_templ_links(&html)?;
+    push_formal_syntax(data);
+    Ok(html)
+}
+
+/// Renders the formal syntax of the page's CSS item (or `name`) as a
+/// railroad diagram.
+#[rari_f(register = "crate::Templ")]
+pub fn csssyntaxdiagram(name: Option<String>) -> Result<String, DocError> {
+    let typ = css_type(env, name.as_deref())?;
+    let svg = render_syntax_diagram(
+        SyntaxInput::Css(typ),
+        env.browser_compat.first().map(|s| s.as_str()),
+        env.locale.as_url_str(),
+    )?;
+    Ok(concat_strs!(
+        r#"<figure class="css-syntax-diagram">"#,
+        &post_process_templ_links(&svg)?,
+        "</figure>"
+    ))
 }
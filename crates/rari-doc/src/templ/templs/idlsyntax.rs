@@ -0,0 +1,393 @@
+use std::collections::BTreeSet;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+use css_syntax_types::{IdlFragmentInSpec, SpecLink, WebrefIdl};
+use rari_templ_func::rari_f;
+use rari_types::fm_types::PageType;
+use rari_types::globals::data_dir;
+use rari_utils::io::read_to_string;
+use tracing::error;
+
+use crate::error::DocError;
+use crate::helpers::l10n::l10n_json_data;
+use crate::html::links::post_process_templ_links;
+use crate::templ::api::RariApi;
+
+static WEBREF_IDL: LazyLock<Option<WebrefIdl>> = LazyLock::new(|| {
+    match read_to_string(data_dir().join("@webref/idl").join("webref_idl.json"))
+        .map_err(DocError::from)
+        .and_then(|json| Ok(serde_json::from_str::<WebrefIdl>(&json)?))
+    {
+        Ok(webref_idl) => Some(webref_idl),
+        Err(e) => {
+            error!("Failed to load webref IDL data: {e:?}");
+            None
+        }
+    }
+});
+
+/// A name in an IDL fragment which may link to an MDN page.
+#[derive(Debug, PartialEq)]
+enum IdlLink<'a> {
+    /// The interface an interface inherits from.
+    Interface(&'a str),
+    Constructor,
+    Member {
+        name: &'a str,
+        is_static: bool,
+    },
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-'
+}
+
+/// Skips a leading extended attribute list, returning the byte offset of the
+/// rest.
+fn skip_extended_attributes(text: &str) -> usize {
+    let start = text.len() - text.trim_start().len();
+    if !text[start..].starts_with('[') {
+        return start;
+    }
+    let mut depth = 0usize;
+    for (i, c) in text.char_indices().skip_while(|(i, _)| *i < start) {
+        match c {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    let rest = &text[i + 1..];
+                    return i + 1 + rest.len() - rest.trim_start().len();
+                }
+            }
+            _ => {}
+        }
+    }
+    text.len()
+}
+
+/// Finds the name defined by an interface member (without its `;`), as a
+/// byte range in `member`.
+///
+/// Unnamed special operations (`getter any (DOMString name)`), `stringifier`
+/// and declarations like `iterable<…>` have no name.
+fn member_name(member: &str) -> Option<(Range<usize>, IdlLink<'_>)> {
+    let offset = skip_extended_attributes(member);
+    let rest = member[offset..].trim_end();
+    if rest == "constructor" || rest.starts_with("constructor(") {
+        return Some((offset..offset + "constructor".len(), IdlLink::Constructor));
+    }
+    let name_end = if rest.ends_with(')') {
+        // the argument list is the last parenthesized group
+        let mut depth = 0usize;
+        let (open, _) = rest.char_indices().rev().find(|(_, c)| {
+            match c {
+                ')' => depth += 1,
+                '(' => depth -= 1,
+                _ => {}
+            }
+            depth == 0
+        })?;
+        open
+    } else {
+        rest.split('=').next().unwrap_or(rest).trim_end().len()
+    };
+    let name_start = rest[..name_end]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_ident_char(*c))
+        .last()
+        .map(|(i, _)| i)?;
+    // a name must follow a type
+    if name_start == 0 {
+        return None;
+    }
+    let name = &rest[name_start..name_end];
+    Some((
+        offset + name_start..offset + name_end,
+        IdlLink::Member {
+            name: name.strip_prefix('_').unwrap_or(name),
+            is_static: rest.starts_with("static "),
+        },
+    ))
+}
+
+fn push_linked(out: &mut String, text: &str, href: Option<String>) {
+    match href {
+        Some(href) => {
+            out.push_str(r#"<a href=""#);
+            out.push_str(&href);
+            out.push_str(r#"">"#);
+            out.push_str(&html_escape::encode_text(text));
+            out.push_str("</a>");
+        }
+        None => out.push_str(&html_escape::encode_text(text)),
+    }
+}
+
+/// Renders an IDL `fragment` as HTML, linking the inherited interface and
+/// the members `link` returns a URL for.
+fn render_fragment(out: &mut String, fragment: &str, link: &impl Fn(IdlLink) -> Option<String>) {
+    let (Some(open), Some(close)) = (fragment.find('{'), fragment.rfind('}')) else {
+        out.push_str(&html_escape::encode_text(fragment));
+        return;
+    };
+    let header = &fragment[..open];
+    let is_enum = header[skip_extended_attributes(header)..].starts_with("enum");
+    match header.split_once(':') {
+        Some((before, parent)) if !is_enum => {
+            out.push_str(&html_escape::encode_text(before));
+            out.push(':');
+            let parent_start = parent.len() - parent.trim_start().len();
+            let parent_name = parent.trim();
+            out.push_str(&parent[..parent_start]);
+            push_linked(out, parent_name, link(IdlLink::Interface(parent_name)));
+            out.push_str(&parent[parent_start + parent_name.len()..]);
+        }
+        _ => out.push_str(&html_escape::encode_text(header)),
+    }
+    out.push('{');
+
+    let body = &fragment[open + 1..close];
+    if is_enum {
+        out.push_str(&html_escape::encode_text(body));
+    } else {
+        for (i, member) in body.split(';').enumerate() {
+            if i > 0 {
+                out.push(';');
+            }
+            match member_name(member) {
+                Some((range, idl_link)) => {
+                    out.push_str(&html_escape::encode_text(&member[..range.start]));
+                    push_linked(out, &member[range.clone()], link(idl_link));
+                    out.push_str(&html_escape::encode_text(&member[range.end..]));
+                }
+                None => out.push_str(&html_escape::encode_text(member)),
+            }
+        }
+    }
+    out.push_str(&html_escape::encode_text(&fragment[close..]));
+}
+
+enum IdlItem<'a> {
+    Fragment(&'a IdlFragmentInSpec),
+    /// An `includes` statement for a mixin.
+    Includes(&'a str),
+}
+
+/// Renders the IDL of `interface`, including partial definitions and the
+/// mixins it includes, followed by a footer listing the specs.
+fn render_idl(
+    webref_idl: &WebrefIdl,
+    interface: &str,
+    link: &impl Fn(IdlLink) -> Option<String>,
+    sources_prefix: Option<&str>,
+) -> Option<String> {
+    let mut items: Vec<IdlItem> = webref_idl
+        .definitions
+        .get(interface)?
+        .iter()
+        .map(IdlItem::Fragment)
+        .collect();
+    for mixin in webref_idl.includes.get(interface).into_iter().flatten() {
+        for fragment in webref_idl.definitions.get(mixin).into_iter().flatten() {
+            items.push(IdlItem::Fragment(fragment));
+        }
+        items.push(IdlItem::Includes(mixin));
+    }
+
+    let mut out = String::new();
+    let mut specs = BTreeSet::new();
+    out.push_str(r#"<pre class="notranslate idl-syntax">"#);
+    for (i, item) in items.into_iter().enumerate() {
+        if i > 0 {
+            out.push_str("\n\n");
+        }
+        match item {
+            IdlItem::Fragment(fragment) => {
+                render_fragment(&mut out, &fragment.fragment, link);
+                specs.insert(&fragment.spec);
+            }
+            IdlItem::Includes(mixin) => {
+                out.push_str(interface);
+                out.push_str(" includes ");
+                out.push_str(mixin);
+                out.push(';');
+            }
+        }
+    }
+    out.push_str("</pre>");
+
+    if let Some(sources_prefix) = sources_prefix
+        && !specs.is_empty()
+    {
+        out.push_str("<footer>");
+        out.push_str(
+            &sources_prefix.replace(
+                "{ $specs }",
+                &specs
+                    .iter()
+                    .map(|SpecLink { title, url }| format!(r#"<a href="{url}">{title}</a>"#))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+        );
+        out.push_str("</footer>");
+    }
+    Some(out)
+}
+
+fn existing_page(url: String) -> Option<String> {
+    RariApi::get_page_nowarn(&url).ok().map(|_| url)
+}
+
+/// Renders the WebIDL of a Web API interface, with its members linked to
+/// their MDN pages.
+///
+/// This is the IDL counterpart of `{{CSSSyntax}}`. The IDL comes from
+/// `@webref/idl` and includes partial interfaces and included mixins.
+///
+/// # Arguments
+/// * `name` - Optional interface name, defaults to the interface of a
+///   `web-api-interface` page. Required on other pages, e.g. to show the
+///   DOM interface of an HTML element.
+///
+/// # Examples
+/// * `{{IDLSyntax}}` on `Web/API/Node` -> the IDL of `Node`
+/// * `{{IDLSyntax("HTMLAnchorElement")}}` -> the IDL of `HTMLAnchorElement`
+#[rari_f(register = "crate::Templ")]
+pub fn idlsyntax(name: Option<String>) -> Result<String, DocError> {
+    let interface = match name.as_deref() {
+        Some(name) if !name.is_empty() => name,
+        _ if env.page_type == PageType::WebApiInterface => {
+            env.slug.rsplit('/').next().unwrap_or_default()
+        }
+        _ => return Err(DocError::IdlNameRequired),
+    };
+    let sources_prefix = l10n_json_data("Template", "formal_syntax_footer", env.locale)?;
+    let locale = env.locale.as_url_str();
+    let link = |idl_link: IdlLink| match idl_link {
+        IdlLink::Interface(name) => existing_page(format!("/{locale}/docs/Web/API/{name}")),
+        IdlLink::Constructor => {
+            existing_page(format!("/{locale}/docs/Web/API/{interface}/{interface}"))
+        }
+        IdlLink::Member { name, is_static } => is_static
+            .then(|| existing_page(format!("/{locale}/docs/Web/API/{interface}/{name}_static")))
+            .flatten()
+            .or_else(|| existing_page(format!("/{locale}/docs/Web/API/{interface}/{name}"))),
+    };
+    let html = WEBREF_IDL
+        .as_ref()
+        .and_then(|webref_idl| render_idl(webref_idl, interface, &link, Some(sources_prefix)))
+        .ok_or_else(|| DocError::NoIdlFound(interface.to_string()))?;
+    post_process_templ_links(&html)
+}
+
+#[cfg(test)]
+mod test {
+    use url::Url;
+
+    use super::*;
+
+    fn name(member: &str) -> Option<&str> {
+        member_name(member).map(|(range, _)| &member[range])
+    }
+
+    #[test]
+    fn test_member_name() {
+        assert_eq!(name("\n  constructor(DOMString type)"), Some("constructor"));
+        assert_eq!(
+            name("\n  [SameObject] readonly attribute NodeList childNodes"),
+            Some("childNodes")
+        );
+        assert_eq!(
+            name("\n  const unsigned short ELEMENT_NODE = 1"),
+            Some("ELEMENT_NODE")
+        );
+        assert_eq!(
+            name("\n  [CEReactions] Node appendChild(Node node)"),
+            Some("appendChild")
+        );
+        assert_eq!(
+            name("\n  (Element or Text) pick((Node or DOMString) x)"),
+            Some("pick")
+        );
+        assert_eq!(name("\n  boolean bubbles = false"), Some("bubbles"));
+        assert_eq!(name("\n  getter any (DOMString name)"), None);
+        assert_eq!(name("\n  iterable<Node>"), None);
+        assert_eq!(name("\n  stringifier"), None);
+        assert_eq!(name("\n"), None);
+        assert_eq!(
+            member_name("\n  static Response json(any data)").map(|(_, link)| link),
+            Some(IdlLink::Member {
+                name: "json",
+                is_static: true
+            })
+        );
+    }
+
+    #[test]
+    fn test_render_idl() {
+        let spec = SpecLink {
+            title: "DOM Standard".to_string(),
+            url: Url::parse("https://dom.spec.whatwg.org/").unwrap(),
+        };
+        let fragment = |fragment: &str| IdlFragmentInSpec {
+            fragment: fragment.to_string(),
+            href: None,
+            spec: spec.clone(),
+        };
+        let mut webref_idl = WebrefIdl::default();
+        webref_idl.definitions.insert(
+            "Text".to_string(),
+            vec![fragment(
+                "[Exposed=Window]\ninterface Text : CharacterData {\n  constructor(optional DOMString data = \"\");\n  [NewObject] Text splitText(unsigned long offset);\n  readonly attribute DOMString wholeText;\n};",
+            )],
+        );
+        webref_idl.definitions.insert(
+            "Slottable".to_string(),
+            vec![fragment(
+                "interface mixin Slottable {\n  readonly attribute HTMLSlotElement? assignedSlot;\n};",
+            )],
+        );
+        webref_idl
+            .includes
+            .insert("Text".to_string(), vec!["Slottable".to_string()]);
+
+        let link = |idl_link: IdlLink| match idl_link {
+            IdlLink::Interface(name) => Some(format!("/en-US/docs/Web/API/{name}")),
+            IdlLink::Constructor => Some("/en-US/docs/Web/API/Text/Text".to_string()),
+            IdlLink::Member { name, .. } if name != "wholeText" => {
+                Some(format!("/en-US/docs/Web/API/Text/{name}"))
+            }
+            IdlLink::Member { .. } => None,
+        };
+        let html = render_idl(
+            &webref_idl,
+            "Text",
+            &link,
+            Some("This syntax reflects { $specs }."),
+        )
+        .unwrap();
+        assert_eq!(
+            html,
+            concat!(
+                r#"<pre class="notranslate idl-syntax">[Exposed=Window]"#,
+                "\n",
+                r#"interface Text : <a href="/en-US/docs/Web/API/CharacterData">CharacterData</a> {"#,
+                "\n",
+                r#"  <a href="/en-US/docs/Web/API/Text/Text">constructor</a>(optional DOMString data = "");"#,
+                "\n",
+                r#"  [NewObject] Text <a href="/en-US/docs/Web/API/Text/splitText">splitText</a>(unsigned long offset);"#,
+                "\n",
+                "  readonly attribute DOMString wholeText;\n};\n\n",
+                "interface mixin Slottable {\n",
+                r#"  readonly attribute HTMLSlotElement? <a href="/en-US/docs/Web/API/Text/assignedSlot">assignedSlot</a>;"#,
+                "\n};\n\nText includes Slottable;</pre>",
+                r#"<footer>This syntax reflects <a href="https://dom.spec.whatwg.org/">DOM Standard</a>.</footer>"#,
+            )
+        );
+        assert!(render_idl(&webref_idl, "Node", &link, None).is_none());
+    }
+}
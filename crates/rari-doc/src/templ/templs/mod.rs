@@ -11,6 +11,7 @@ pub mod embeds;
 pub mod firefox_for_developers;
 pub mod glossary;
 pub mod glossarydisambiguation;
+pub mod idlsyntax;
 pub mod inheritance_diagram;
 pub mod inline_labels;
 pub mod js_property_attributes;
@@ -27,8 +28,8 @@ pub mod web_ext_examples;
 pub mod webext_all_examples;
 pub mod xsltref;
 
-use std::collections::HashMap;
-use std::sync::LazyLock;
+use std::collections::{HashMap, HashSet};
+use std::sync::{LazyLock, OnceLock};
 
 use rari_types::globals::deny_warnings;
 use rari_types::templ::{RariFn, TemplType};
@@ -38,6 +39,11 @@ use tracing::error;
 use crate::error::DocError;
 use crate::utils::{TEMPL_RECORDER, TemplStatEvent};
 
+/// A templ (KumaScript macro).
+///
+/// Built-in templs are collected via `inventory`. Templs defined outside of
+/// rari are added with [`register_templs`] and get the same [`RariEnv`],
+/// argument conversion and LSP documentation.
 #[derive(Debug)]
 pub struct Templ {
     pub name: &'static str,
@@ -51,11 +57,67 @@ pub struct Templ {
 
 inventory::collect!(Templ);
 
-pub static TEMPL_MAP: LazyLock<Vec<&'static Templ>> =
-    LazyLock::new(|| inventory::iter::<Templ>().collect());
+static EXTERNAL_TEMPLS: OnceLock<Vec<&'static Templ>> = OnceLock::new();
+
+fn all_templs() -> impl Iterator<Item = &'static Templ> {
+    inventory::iter::<Templ>().chain(EXTERNAL_TEMPLS.get_or_init(Vec::new).iter().copied())
+}
+
+pub static TEMPL_MAP: LazyLock<Vec<&'static Templ>> = LazyLock::new(|| all_templs().collect());
 
 pub static TEMPL_MAPPING: LazyLock<HashMap<&'static str, &'static Templ>> =
-    LazyLock::new(|| inventory::iter::<Templ>().map(|t| (t.name, t)).collect());
+    LazyLock::new(|| all_templs().map(|t| (t.name, t)).collect());
+
+/// Registers templs defined outside of rari, e.g. by a docs site built on
+/// rari.
+///
+/// Define them with `#[rari_f(define = "rari_doc::Templ")]`, which creates a
+/// `pub static <NAME>_TEMPL`, and register them once before anything is
+/// rendered:
+///
+/// ```ignore
+/// #[rari_f(define = "rari_doc::Templ")]
+/// pub fn sitebanner(text: String) -> Result<String, DocError> {
+///     Ok(format!(r#"<div class="site-banner">{text}</div>"#))
+/// }
+///
+/// register_templs(vec![&SITEBANNER_TEMPL])?;
+/// ```
+///
+/// Names must be lowercase `snake_case` (as macro names are looked up) and
+/// must not shadow a built-in templ.
+pub fn register_templs(templs: Vec<&'static Templ>) -> Result<(), DocError> {
+    validate_templs(&templs)?;
+    EXTERNAL_TEMPLS
+        .set(templs)
+        .map_err(|_| DocError::TemplsAlreadyRegistered)
+}
+
+fn validate_templs(templs: &[&Templ]) -> Result<(), DocError> {
+    let mut names = inventory::iter::<Templ>()
+        .map(|t| t.name)
+        .collect::<HashSet<_>>();
+    for templ in templs {
+        if templ.name.is_empty()
+            || !templ
+                .name
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_')
+        {
+            return Err(DocError::InvalidTempl(format!(
+                "{} is not a lowercase snake_case name",
+                templ.name
+            )));
+        }
+        if !names.insert(templ.name) {
+            return Err(DocError::InvalidTempl(format!(
+                "{} is already defined",
+                templ.name
+            )));
+        }
+    }
+    Ok(())
+}
 
 pub fn exists(name: &str) -> bool {
     TEMPL_MAPPING.contains_key(name)
@@ -83,7 +145,6 @@ pub fn invoke(
     let name = name.replace('-', "_");
     let (f, is_sidebar) = match TEMPL_MAPPING.get(name.as_str()) {
         Some(t) => (t.function, t.typ),
-        None if name == "xulelem" => return Ok((Default::default(), TemplType::None)),
         None if deny_warnings() => return Err(DocError::UnknownMacro(name.to_string())),
         None => {
             let rendered = format!("<s>unsupported templ: {name}</s>");
@@ -97,10 +158,70 @@ pub fn invoke(
 
 #[cfg(test)]
 mod test {
+    use rari_templ_func::rari_f;
+    use rari_types::Quotes;
+
     use super::*;
 
     #[test]
     fn test_kw() {
         println!("{:?}", *TEMPL_MAP);
     }
+
+    /// Says hello.
+    #[rari_f(define = "crate::Templ")]
+    fn external_hello(name: Option<String>) -> Result<String, DocError> {
+        Ok(format!("Hello {}!", name.as_deref().unwrap_or("world")))
+    }
+
+    #[rari_f(define = "crate::Templ")]
+    fn glossary(term: String) -> Result<String, DocError> {
+        Ok(term)
+    }
+
+    #[rari_f(define = "crate::Templ")]
+    #[allow(non_snake_case)]
+    fn ExternalHello() -> Result<String, DocError> {
+        Ok(String::new())
+    }
+
+    #[test]
+    fn test_define_templ() {
+        assert_eq!(EXTERNAL_HELLO_TEMPL.name, "external_hello");
+        assert_eq!(
+            EXTERNAL_HELLO_TEMPL.outline,
+            "external_hello(name?: String)"
+        );
+        assert_eq!(EXTERNAL_HELLO_TEMPL.doc.trim(), "Says hello.");
+        let env = RariEnv::default();
+        assert_eq!(
+            (EXTERNAL_HELLO_TEMPL.function)(&env, vec![]).unwrap(),
+            "Hello world!"
+        );
+        assert_eq!(
+            (EXTERNAL_HELLO_TEMPL.function)(
+                &env,
+                vec![Some(Arg::String("rari".into(), Quotes::Double))]
+            )
+            .unwrap(),
+            "Hello rari!"
+        );
+    }
+
+    #[test]
+    fn test_validate_templs() {
+        assert!(validate_templs(&[&EXTERNAL_HELLO_TEMPL]).is_ok());
+        assert!(matches!(
+            validate_templs(&[&GLOSSARY_TEMPL]),
+            Err(DocError::InvalidTempl(_))
+        ));
+        assert!(matches!(
+            validate_templs(&[&EXTERNALHELLO_TEMPL]),
+            Err(DocError::InvalidTempl(_))
+        ));
+        assert!(matches!(
+            validate_templs(&[&EXTERNAL_HELLO_TEMPL, &EXTERNAL_HELLO_TEMPL]),
+            Err(DocError::InvalidTempl(_))
+        ));
+    }
 }
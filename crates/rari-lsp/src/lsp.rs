@@ -9,17 +9,18 @@ use rari_doc::find::doc_pages_from_slugish;
 use rari_doc::issues::{DIssue, DisplayIssue};
 use rari_doc::pages::page::{Page, PageLike};
 use rari_doc::pages::types::doc::doc_from_raw;
+use rari_doc::templ::policy::{deprecation_note, templ_policy};
 use rari_doc::templ::templs::TEMPL_MAP;
-use rari_tools::fix::issues::get_fixable_issues;
+use rari_tools::fix::issues::get_issues;
 use rari_types::locale::Locale;
 use tower_lsp_server::ls_types::{
     CodeAction, CodeActionOrCommand, CodeActionParams, CodeActionProviderCapability,
-    CodeActionResponse, CompletionItem, CompletionItemKind, CompletionList, CompletionOptions,
-    CompletionParams, CompletionResponse, CompletionTextEdit, Diagnostic, DiagnosticSeverity,
-    DidChangeTextDocumentParams, DidOpenTextDocumentParams, DidSaveTextDocumentParams,
-    DocumentChanges, Documentation, Hover, HoverContents, HoverParams, HoverProviderCapability,
-    InitializeParams, InitializeResult, InitializedParams, InsertTextFormat, LanguageString,
-    MarkedString, MarkupContent, MarkupKind, MessageType, OneOf,
+    CodeActionResponse, CompletionItem, CompletionItemKind, CompletionItemTag, CompletionList,
+    CompletionOptions, CompletionParams, CompletionResponse, CompletionTextEdit, Diagnostic,
+    DiagnosticSeverity, DidChangeTextDocumentParams, DidOpenTextDocumentParams,
+    DidSaveTextDocumentParams, DocumentChanges, Documentation, Hover, HoverContents, HoverParams,
+    HoverProviderCapability, InitializeParams, InitializeResult, InitializedParams,
+    InsertTextFormat, LanguageString, MarkedString, MarkupContent, MarkupKind, MessageType, OneOf,
     OptionalVersionedTextDocumentIdentifier, Position, Range, ServerCapabilities, ServerInfo,
     TextDocumentContentChangeEvent, TextDocumentEdit, TextDocumentSyncCapability,
     TextDocumentSyncKind, TextEdit, Uri, WorkspaceEdit,
@@ -234,13 +235,15 @@ impl LanguageServer for Backend {
                             .replace("-", "_")
                             .as_str(),
                     ) {
-                        let hover_contents = HoverContents::Array(vec![
-                            MarkedString::LanguageString(LanguageString {
-                                language: "TypeScript".to_string(),
-                                value: t.outline.to_string(),
-                            }),
-                            MarkedString::String(t.doc.to_string()),
-                        ]);
+                        let mut contents = vec![MarkedString::LanguageString(LanguageString {
+                            language: "TypeScript".to_string(),
+                            value: t.outline.to_string(),
+                        })];
+                        if let Some(policy) = templ_policy(t.name) {
+                            contents.push(MarkedString::String(deprecation_note(policy)));
+                        }
+                        contents.push(MarkedString::String(t.doc.to_string()));
+                        let hover_contents = HoverContents::Array(contents);
                         let hover = Hover {
                             contents: hover_contents,
                             range: None,
@@ -356,6 +359,7 @@ impl LanguageServer for Backend {
                             })),
                             insert_text,
                             insert_text_format,
+                            tags: templ_policy(t.name).map(|_| vec![CompletionItemTag::DEPRECATED]),
                             ..CompletionItem::default()
                         }
                     })
@@ -412,7 +416,12 @@ impl LanguageServer for Backend {
                 (
                     d.data
                         .clone()
-                        .and_then(|data| serde_json::from_value::<DIssue>(data).ok()),
+                        .and_then(|data| serde_json::from_value::<DIssue>(data).ok())
+                        .filter(|issue| {
+                            let display_issue = issue.display_issue();
+                            display_issue.fixable.unwrap_or_default()
+                                && display_issue.suggestion.is_some()
+                        }),
                     d.range,
                 )
             }) {
@@ -464,7 +473,7 @@ impl Backend {
                 }
             };
 
-            let issues = match get_fixable_issues(&page) {
+            let issues = match get_issues(&page) {
                 Ok(issues) => issues,
                 Err(e) => {
                     self.client
@@ -10,7 +10,8 @@ use flate2::write::GzEncoder;
 use rari_doc::build::SitemapMeta;
 use rari_types::error::EnvError;
 use rari_types::globals::build_out_root;
-use rari_types::locale::Locale;
+use rari_types::locale::{Locale, default_locale};
+use rari_utils::concat_strs;
 use serde::Serialize;
 use thiserror::Error;
 
@@ -55,26 +56,48 @@ impl<'a> SitemapIndex<'a> {
     }
 }
 
+/// The maximum number of URLs in a single sitemap file.
+pub const MAX_SITEMAP_URLS: usize = 50_000;
+/// The maximum (uncompressed) size of a single sitemap file in bytes.
+pub const MAX_SITEMAP_BYTES: usize = 50 * 1024 * 1024;
+
 #[derive(Serialize)]
 #[serde(rename = "urlset")]
 pub struct Sitemap<'a> {
     #[serde(rename = "@xmlns")]
     xmlns: &'static str,
+    #[serde(rename = "@xmlns:xhtml")]
+    xmlns_xhtml: &'static str,
     url: Vec<Url<'a>>,
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 pub struct Url<'a> {
     #[serde(serialize_with = "prefix_base_url")]
     loc: Cow<'a, str>,
     #[serde(skip_serializing_if = "Option::is_none")]
     lastmod: Option<NaiveDate>,
+    #[serde(rename = "xhtml:link", skip_serializing_if = "Vec::is_empty")]
+    alternates: Vec<Alternate<'a>>,
+}
+
+/// A translated counterpart of a URL, see
+/// <https://developers.google.com/search/docs/specialty/international/localized-versions#sitemap>.
+#[derive(Clone, Serialize)]
+pub struct Alternate<'a> {
+    #[serde(rename = "@rel")]
+    rel: &'static str,
+    #[serde(rename = "@hreflang")]
+    hreflang: &'a str,
+    #[serde(rename = "@href", serialize_with = "prefix_base_url")]
+    href: Cow<'a, str>,
 }
 
 impl<'a> Sitemap<'a> {
     pub fn new(urls: impl Into<Vec<Url<'a>>>) -> Self {
         Sitemap {
             xmlns: "http://www.sitemaps.org/schemas/sitemap/0.9",
+            xmlns_xhtml: "http://www.w3.org/1999/xhtml",
             url: urls.into(),
         }
     }
@@ -87,19 +110,101 @@ impl<'a> Sitemap<'a> {
     }
 
     pub fn gzip_to_path(&self, path: impl AsRef<Path>) -> Result<(), SitemapError> {
-        let file = File::create(path)?;
-        let buffer = BufWriter::new(file);
-        let mut encoder = GzEncoder::new(buffer, Compression::default());
-        encoder.write_all(String::try_from(self)?.as_bytes())?;
+        gzip_xml_to_path(&String::try_from(self)?, path)
+    }
+}
+
+fn gzip_xml_to_path(xml: &str, path: impl AsRef<Path>) -> Result<(), SitemapError> {
+    let file = File::create(path)?;
+    let buffer = BufWriter::new(file);
+    let mut encoder = GzEncoder::new(buffer, Compression::default());
+    encoder.write_all(xml.as_bytes())?;
+    encoder.finish()?;
+    Ok(())
+}
+
+/// Renders `urls` into as many sitemaps as needed to stay within `max_urls`
+/// URLs and `max_bytes` bytes per sitemap.
+fn render_sitemaps(
+    urls: &[Url],
+    max_urls: usize,
+    max_bytes: usize,
+) -> Result<Vec<String>, SitemapError> {
+    fn render(urls: &[Url], max_bytes: usize, out: &mut Vec<String>) -> Result<(), SitemapError> {
+        let xml = String::try_from(&Sitemap::new(urls.to_vec()))?;
+        if xml.len() > max_bytes && urls.len() > 1 {
+            let (first, second) = urls.split_at(urls.len() / 2);
+            render(first, max_bytes, out)?;
+            render(second, max_bytes, out)?;
+        } else {
+            out.push(xml);
+        }
         Ok(())
     }
+
+    let mut out = vec![];
+    if urls.is_empty() {
+        out.push(String::try_from(&Sitemap::new(vec![]))?);
+    }
+    for chunk in urls.chunks(max_urls) {
+        render(chunk, max_bytes, &mut out)?;
+    }
+    Ok(out)
+}
+
+/// The file name of the `n`th sitemap of a locale: `sitemap.xml.gz`,
+/// `sitemap-2.xml.gz`, ...
+fn sitemap_file_name(n: usize) -> String {
+    if n == 0 {
+        "sitemap.xml.gz".to_string()
+    } else {
+        format!("sitemap-{}.xml.gz", n + 1)
+    }
+}
+
+/// Replaces the locale prefix of `url` (`/en-US/...`) with `locale`.
+fn with_locale(url: &str, from: Locale, locale: Locale) -> Option<String> {
+    let rest = url
+        .strip_prefix('/')?
+        .strip_prefix(from.as_url_str())?
+        .strip_prefix('/')?;
+    Some(concat_strs!("/", locale.as_url_str(), "/", rest))
 }
 
 impl<'a, 'b: 'a> From<&'b SitemapMeta<'b>> for Url<'a> {
     fn from(value: &'b SitemapMeta<'b>) -> Self {
+        let mut alternates = vec![];
+        if value.translations.len() > 1 {
+            alternates = value
+                .translations
+                .iter()
+                .filter_map(|locale| {
+                    Some(Alternate {
+                        rel: "alternate",
+                        hreflang: locale.as_url_str(),
+                        href: if *locale == value.locale {
+                            Cow::Borrowed(value.url.as_ref())
+                        } else {
+                            Cow::Owned(with_locale(&value.url, value.locale, *locale)?)
+                        },
+                    })
+                })
+                .collect::<Vec<_>>();
+            alternates.sort_by(|a, b| a.hreflang.cmp(b.hreflang));
+            if let Some(default) = alternates
+                .iter()
+                .find(|alternate| alternate.hreflang == default_locale().as_url_str())
+            {
+                alternates.push(Alternate {
+                    hreflang: "x-default",
+                    ..default.clone()
+                });
+            }
+        }
         Self {
             loc: Cow::Borrowed(value.url.as_ref()),
             lastmod: value.modified.map(NaiveDate::from),
+            alternates,
         }
     }
 }
@@ -148,11 +253,14 @@ impl Sitemaps<'_> {
         Ok(out_file)
     }
 
+    /// Writes the sitemaps of `locale` to `out_path`. Locales exceeding the
+    /// sitemap limits of 50,000 URLs or 50MB are split into `sitemap.xml.gz`,
+    /// `sitemap-2.xml.gz`, ... Returns the written files.
     pub fn write_sitemap_xml_gz(
         &self,
         out_path: impl Into<PathBuf>,
         locale: Locale,
-    ) -> Result<PathBuf, SitemapError> {
+    ) -> Result<Vec<PathBuf>, SitemapError> {
         let mut locale_urls = self
             .sitemap_meta
             .iter()
@@ -162,10 +270,16 @@ impl Sitemaps<'_> {
 
         locale_urls.sort_by(|a, b| a.loc.cmp(&b.loc));
 
-        let sitemap = Sitemap::new(locale_urls);
-        let out_file = out_path.into().join("sitemap.xml.gz");
-        sitemap.gzip_to_path(&out_file)?;
-        Ok(out_file)
+        let out_path = out_path.into();
+        render_sitemaps(&locale_urls, MAX_SITEMAP_URLS, MAX_SITEMAP_BYTES)?
+            .iter()
+            .enumerate()
+            .map(|(n, xml)| {
+                let out_file = out_path.join(sitemap_file_name(n));
+                gzip_xml_to_path(xml, &out_file)?;
+                Ok(out_file)
+            })
+            .collect()
     }
 
     pub fn write_all_sitemaps(
@@ -182,8 +296,9 @@ impl Sitemaps<'_> {
             .map(|locale| {
                 let out_path = sitemaps_out_path.join(locale.as_folder_str());
                 fs::create_dir_all(&out_path)?;
-                self.write_sitemap_xml_gz(&out_path, *locale)
-                    .and_then(|path| {
+                self.write_sitemap_xml_gz(&out_path, *locale)?
+                    .into_iter()
+                    .map(|path| {
                         Ok(Url {
                             loc: Cow::Owned(
                                 PathBuf::from("/")
@@ -192,10 +307,15 @@ impl Sitemaps<'_> {
                                     .to_string(),
                             ),
                             lastmod: Some(today),
+                            alternates: vec![],
                         })
                     })
+                    .collect::<Result<Vec<_>, SitemapError>>()
             })
-            .collect::<Result<Vec<_>, SitemapError>>()?;
+            .collect::<Result<Vec<_>, SitemapError>>()?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
 
         let sitemap_index = SitemapIndex::new(sitemaps);
         let out_file = out_path.join("sitemap.xml");
@@ -210,19 +330,85 @@ mod test {
 
     #[test]
     fn basic() {
-        let urlset = Sitemap {
-            xmlns: "http://www.sitemaps.org/schemas/sitemap/0.9",
-            url: vec![
-                Url {
-                    loc: Cow::Borrowed("foo"),
-                    lastmod: Some(NaiveDate::default()),
-                },
-                Url {
-                    loc: Cow::Borrowed("bar"),
-                    lastmod: None,
-                },
-            ],
-        };
+        let urlset = Sitemap::new(vec![
+            Url {
+                loc: Cow::Borrowed("foo"),
+                lastmod: Some(NaiveDate::default()),
+                alternates: vec![],
+            },
+            Url {
+                loc: Cow::Borrowed("bar"),
+                lastmod: None,
+                alternates: vec![],
+            },
+        ]);
         println!("{}", quick_xml::se::to_string(&urlset).unwrap());
     }
+
+    fn sitemap_meta(url: &str, locale: Locale, translations: Vec<Locale>) -> SitemapMeta<'_> {
+        SitemapMeta {
+            url: Cow::Borrowed(url),
+            locale,
+            translations,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn alternates() {
+        let meta = sitemap_meta(
+            "/de/docs/Web/HTML",
+            Locale::De,
+            vec![Locale::EnUs, Locale::De, Locale::Fr],
+        );
+        let xml = String::try_from(&Sitemap::new(vec![Url::from(&meta)])).unwrap();
+        assert!(xml.contains(r#"xmlns:xhtml="http://www.w3.org/1999/xhtml""#));
+        for (hreflang, url) in [
+            ("de", "/de/docs/Web/HTML"),
+            ("en-US", "/en-US/docs/Web/HTML"),
+            ("fr", "/fr/docs/Web/HTML"),
+            ("x-default", "/en-US/docs/Web/HTML"),
+        ] {
+            assert!(
+                xml.contains(&format!(
+                    r#"<xhtml:link rel="alternate" hreflang="{hreflang}" href="{}{url}"/>"#,
+                    rari_types::globals::base_url()
+                )),
+                "{xml}"
+            );
+        }
+
+        let meta = sitemap_meta("/en-US/docs/Web/HTML", Locale::EnUs, vec![Locale::EnUs]);
+        let xml = String::try_from(&Sitemap::new(vec![Url::from(&meta)])).unwrap();
+        assert!(!xml.contains("xhtml:link"));
+    }
+
+    #[test]
+    fn split() {
+        let urls = (0..10)
+            .map(|i| Url {
+                loc: Cow::Owned(format!("/en-US/docs/{i}")),
+                lastmod: None,
+                alternates: vec![],
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(render_sitemaps(&urls, 50, usize::MAX).unwrap().len(), 1);
+        assert_eq!(render_sitemaps(&urls, 4, usize::MAX).unwrap().len(), 3);
+
+        let max_bytes = String::try_from(&Sitemap::new(urls[..3].to_vec()))
+            .unwrap()
+            .len();
+        let sitemaps = render_sitemaps(&urls, 50, max_bytes).unwrap();
+        assert!(sitemaps.iter().all(|xml| xml.len() <= max_bytes));
+        assert_eq!(
+            sitemaps
+                .iter()
+                .map(|xml| xml.matches("<url>").count())
+                .sum::<usize>(),
+            10
+        );
+        assert_eq!(render_sitemaps(&[], 50, max_bytes).unwrap().len(), 1);
+        assert_eq!(sitemap_file_name(0), "sitemap.xml.gz");
+        assert_eq!(sitemap_file_name(1), "sitemap-2.xml.gz");
+    }
 }
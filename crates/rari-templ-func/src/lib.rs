@@ -70,6 +70,8 @@ struct RariFargs {
     register: Option<syn::TypePath>,
     #[darling(default)]
     typ: Option<syn::TypePath>,
+    #[darling(default)]
+    define: Option<syn::TypePath>,
 }
 
 /// Define rari templ functions.
@@ -85,6 +87,11 @@ struct RariFargs {
 /// ```
 /// This will automatically inject an argument `env` providing a
 /// [RariEnv] reference.
+///
+/// `register = "path::Templ"` submits the templ to the built-in inventory.
+/// `define = "path::Templ"` instead defines a `pub static <NAME>_TEMPL` to
+/// pass to `rari_doc::templ::templs::register_templs`, which is how templs
+/// outside of rari are added.
 #[proc_macro_attribute]
 pub fn rari_f(attr: TokenStream, input: TokenStream) -> TokenStream {
     let attr_args = match NestedMeta::parse_meta_list(attr.into()) {
@@ -191,20 +198,32 @@ pub fn rari_f(attr: TokenStream, input: TokenStream) -> TokenStream {
         .map(|typ| quote! { #typ })
         .unwrap_or(quote! { TemplType::None });
 
+    let templ = |templ_type: &syn::TypePath| {
+        quote! {
+            #templ_type {
+                name: #name,
+                outline: #outline,
+                outline_snippet: #outline_snippet,
+                outline_plain: #outline_plain,
+                doc: #doc_string,
+                function: #dup_ident,
+                typ: ::rari_types::templ::#typ,
+            }
+        }
+    };
     let collect = if let Some(inventory_type) = attr_args.register {
+        let templ = templ(&inventory_type);
         quote! {
                 inventory::submit! {
-                    #inventory_type {
-                        name: #name,
-                        outline: #outline,
-                        outline_snippet: #outline_snippet,
-                        outline_plain: #outline_plain,
-                        doc: #doc_string,
-                        function: #dup_ident,
-                        typ: rari_types::templ::#typ,
-                    }
+                    #templ
                 }
         }
+    } else if let Some(templ_type) = attr_args.define {
+        let templ = templ(&templ_type);
+        let static_ident = format_ident!("{}_TEMPL", name.to_uppercase());
+        quote! {
+            pub static #static_ident: #templ_type = #templ;
+        }
     } else {
         quote! {}
     };
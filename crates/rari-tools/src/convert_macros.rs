@@ -0,0 +1,265 @@
+use std::fs;
+
+use rari_doc::error::DocError;
+use rari_doc::pages::page::{Page, PageLike};
+use rari_doc::templ::parser::{Token, parse};
+use rari_doc::templ::templs::{exists, invoke};
+use rari_types::locale::Locale;
+use scraper::{Html, Node};
+
+use crate::error::ToolError;
+use crate::utils::read_all_doc_pages;
+
+/// Rewrites calls of the link macro `macro_name` that render as a plain link
+/// into the equivalent markdown link, optionally limited to some locales.
+///
+/// Each call is rendered through its templ. Calls rendering anything more
+/// than an `<a>` with text or `<code>` (badges, classes like
+/// `only-in-en-us`), links to missing pages, and calls in code or HTML
+/// blocks are left alone. Returns the number of converted calls.
+pub fn convert_macros(macro_name: &str, locales: Option<&[Locale]>) -> Result<usize, ToolError> {
+    let name = macro_name.to_ascii_lowercase().replace('-', "_");
+    if !exists(&name) {
+        return Err(ToolError::UnknownMacro(macro_name.to_string()));
+    }
+    let mut converted = 0;
+    for page in read_all_doc_pages()?.into_values() {
+        if locales.is_some_and(|locales| !locales.contains(&page.locale())) {
+            continue;
+        }
+        let (content, count) = convert_page(&page, &name)?;
+        if count > 0 {
+            let raw = page.raw_content();
+            let front_matter = &raw[..raw.len() - page.content().len()];
+            fs::write(page.full_path(), [front_matter, &content].concat())?;
+            tracing::info!(
+                "Converted {count} {name} call(s) in {}",
+                page.full_path().display()
+            );
+            converted += count;
+        }
+    }
+    Ok(converted)
+}
+
+fn convert_page(page: &Page, name: &str) -> Result<(String, usize), ToolError> {
+    let content = page.content();
+    let env = page.rari_env().ok_or(DocError::NoRariEnv)?;
+    let mut out = String::with_capacity(content.len());
+    let mut last = 0;
+    let mut count = 0;
+    for token in parse(content)? {
+        let Token::Macro(mac) = token else {
+            continue;
+        };
+        if mac.ident.to_ascii_lowercase().replace('-', "_") != name
+            || in_code_or_html(content, mac.start)
+        {
+            continue;
+        }
+        let html = match invoke(&env, name, mac.args) {
+            Ok((html, _)) => html,
+            Err(e) => {
+                tracing::warn!(
+                    "Skipping {} in {}: {e}",
+                    &content[mac.start..mac.end],
+                    page.full_path().display()
+                );
+                continue;
+            }
+        };
+        if let Some(link) = markdown_link(&html) {
+            out.push_str(&content[last..mac.start]);
+            out.push_str(&link);
+            last = mac.end;
+            count += 1;
+        }
+    }
+    out.push_str(&content[last..]);
+    Ok((out, count))
+}
+
+/// Whether `offset` is inside a fenced code block, an inline code span or on
+/// a line starting with an HTML tag, where markdown links don't work.
+fn in_code_or_html(content: &str, offset: usize) -> bool {
+    let line_start = content[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let mut fence: Option<&str> = None;
+    for line in content[..line_start].lines() {
+        let trimmed = line.trim_start();
+        match fence {
+            Some(open) if trimmed.starts_with(open) => fence = None,
+            Some(_) => {}
+            None => {
+                fence = ["```", "~~~"]
+                    .into_iter()
+                    .find(|open| trimmed.starts_with(open))
+            }
+        }
+    }
+    let prefix = &content[line_start..offset];
+    fence.is_some() || prefix.trim_start().starts_with('<') || prefix.matches('`').count() % 2 == 1
+}
+
+/// Converts the output of a link templ into a markdown link if it is a plain
+/// `<a>` containing only text or a single `<code>` with text.
+fn markdown_link(html: &str) -> Option<String> {
+    let fragment = Html::parse_fragment(html);
+    let root = fragment.root_element();
+    let mut children = root.children();
+    let a = children.next()?;
+    if children.next().is_some() {
+        return None;
+    }
+    let a = a.value().as_element().filter(|el| el.name() == "a")?;
+    if a.attrs()
+        .any(|(name, _)| !matches!(name, "href" | "title" | "data-templ-link"))
+    {
+        return None;
+    }
+    let href = a.attr("href")?;
+    if !Page::exists(href.split_once('#').map_or(href, |(url, _)| url)) {
+        return None;
+    }
+
+    let a_ref = root.first_child()?;
+    let mut children = a_ref.children();
+    let child = children.next()?;
+    if children.next().is_some() {
+        return None;
+    }
+    let text = match child.value() {
+        Node::Text(text) => escape_text(text),
+        Node::Element(el) if el.name() == "code" && el.attrs().next().is_none() => {
+            let mut children = child.children();
+            let Some(Node::Text(code)) = children.next().map(|c| c.value()) else {
+                return None;
+            };
+            if children.next().is_some() {
+                return None;
+            }
+            code_span(code)
+        }
+        _ => return None,
+    };
+
+    let href = if href.contains([' ', '(', ')']) {
+        format!("<{href}>")
+    } else {
+        href.to_string()
+    };
+    Some(match a.attr("title") {
+        Some(title) => format!(
+            "[{text}]({href} \"{}\")",
+            title.replace('\\', "\\\\").replace('"', "\\\"")
+        ),
+        None => format!("[{text}]({href})"),
+    })
+}
+
+fn escape_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '&') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// A code span for `code`, with a fence longer than any run of backticks in
+/// it.
+fn code_span(code: &str) -> String {
+    let longest = code
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+    let fence = "`".repeat(longest + 1);
+    if longest > 0 {
+        format!("{fence} {code} {fence}")
+    } else {
+        format!("{fence}{code}{fence}")
+    }
+}
+
+// These tests use file system fixtures to simulate content and translated content.
+// The file system is a shared resource, so we force tests to be run serially,
+// to avoid concurrent fixture management issues.
+// Using `file_serial` as a synchronization lock, we should be able to run all tests
+// using the same `key` (here: file_fixtures) to be serialized across modules.
+#[cfg(test)]
+use serial_test::file_serial;
+#[cfg(test)]
+#[file_serial(file_fixtures)]
+mod test {
+    use indoc::indoc;
+
+    use super::*;
+    use crate::tests::fixtures::docs::DocFixtures;
+
+    #[test]
+    fn test_convert_macros() {
+        let _docs = DocFixtures::new(
+            &[
+                "Web/API/ExampleOne".to_string(),
+                "Web/API/ExampleTwo".to_string(),
+            ],
+            Locale::EnUs,
+        );
+        DocFixtures::create_doc_with_content(
+            "Web/API/ExampleTwo",
+            Locale::EnUs,
+            indoc!(
+                r#"
+                See {{DOMxRef("ExampleOne")}} and {{domxref("ExampleOne", "the first example", "", 1)}}.
+
+                Not {{domxref("DoesNotExist")}} or `{{domxref("ExampleOne")}}`.
+
+                ```js
+                {{domxref("ExampleOne")}}
+                ```
+                "#
+            ),
+        );
+
+        assert_eq!(convert_macros("DOMxRef", Some(&[Locale::EnUs])).unwrap(), 2);
+        let page = Page::from_url("/en-US/docs/Web/API/ExampleTwo").unwrap();
+        let md = fs::read_to_string(page.full_path()).unwrap();
+        assert!(md.contains(
+            "See [`ExampleOne`](/en-US/docs/Web/API/ExampleOne) and [the first example](/en-US/docs/Web/API/ExampleOne \"the first example\")."
+        ));
+        assert!(md.contains("Not {{domxref(\"DoesNotExist\")}} or `{{domxref(\"ExampleOne\")}}`."));
+        assert!(md.contains("```js\n{{domxref(\"ExampleOne\")}}\n```"));
+        assert!(md.starts_with("---\ntitle: ExampleTwo\n"));
+    }
+
+    #[test]
+    fn test_unknown_macro() {
+        assert!(matches!(
+            convert_macros("NoSuchMacro", None),
+            Err(ToolError::UnknownMacro(_))
+        ));
+    }
+
+    #[test]
+    fn test_code_span() {
+        assert_eq!(code_span("a.b"), "`a.b`");
+        assert_eq!(code_span("a`b"), "`` a`b ``");
+    }
+
+    #[test]
+    fn test_escape_text() {
+        assert_eq!(escape_text("a_b [c] <d>"), r"a\_b \[c\] \<d\>");
+    }
+
+    #[test]
+    fn test_in_code_or_html() {
+        let content = "a {{x}} `{{x}}`\n<p>{{x}}</p>\n```\n{{x}}\n```\n{{x}}";
+        let offsets = content
+            .match_indices("{{x}}")
+            .map(|(i, _)| in_code_or_html(content, i))
+            .collect::<Vec<_>>();
+        assert_eq!(offsets, vec![false, true, true, true, false]);
+    }
+}
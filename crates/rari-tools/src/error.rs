@@ -60,6 +60,12 @@ pub enum ToolError {
     InvalidFrontmatter(#[from] serde_yaml_ng::Error),
     #[error("Page has subpages: {0}")]
     HasSubpagesError(Cow<'static, str>),
+    #[error("Found {0} issues in sidebars")]
+    InvalidSidebars(usize),
+    #[error("Found {0} unformatted files")]
+    UnformattedContent(usize),
+    #[error("Unknown macro: {0}")]
+    UnknownMacro(String),
     #[error("Target directory ({0}) for slug ({1}) already exists")]
     TargetDirExists(PathBuf, String),
 
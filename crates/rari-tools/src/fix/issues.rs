@@ -56,7 +56,9 @@ where
     None
 }
 
-pub fn get_fixable_issues(page: &Page) -> Result<Vec<DIssue>, ToolError> {
+/// Builds `page` and returns all of its issues that have a source position,
+/// sorted by position.
+pub fn get_issues(page: &Page) -> Result<Vec<DIssue>, ToolError> {
     let _ = page.build()?;
 
     let mut issues = {
@@ -67,18 +69,7 @@ pub fn get_fixable_issues(page: &Page) -> Result<Vec<DIssue>, ToolError> {
         req_issues
             .into_iter()
             .filter_map(|issue| DIssue::from_issue(issue, page))
-            .filter_map(|dissue| {
-                let display_issue = dissue.display_issue();
-                if display_issue.suggestion.is_some()
-                    && display_issue.fixable.unwrap_or_default()
-                    && display_issue.line.is_some()
-                {
-                    // Column is optional - if missing, we'll search from line start
-                    Some(dissue)
-                } else {
-                    None
-                }
-            })
+            .filter(|dissue| dissue.display_issue().line.is_some())
             .collect::<Vec<_>>()
     };
     issues.sort_by(|a, b| {
@@ -95,6 +86,16 @@ pub fn get_fixable_issues(page: &Page) -> Result<Vec<DIssue>, ToolError> {
     Ok(issues)
 }
 
+pub fn get_fixable_issues(page: &Page) -> Result<Vec<DIssue>, ToolError> {
+    let mut issues = get_issues(page)?;
+    // Column is optional - if missing, we'll search from line start
+    issues.retain(|dissue| {
+        let display_issue = dissue.display_issue();
+        display_issue.suggestion.is_some() && display_issue.fixable.unwrap_or_default()
+    });
+    Ok(issues)
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct SearchReplaceWithOffset {
     /// Byte offset in the source where the search string begins
@@ -105,6 +106,20 @@ pub struct SearchReplaceWithOffset {
     replace: String,
 }
 
+impl SearchReplaceWithOffset {
+    pub fn new(offset: usize, search: &str, replace: &str) -> Self {
+        Self {
+            offset,
+            search: search.to_string(),
+            replace: replace.to_string(),
+        }
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
 /// Converts issues into offset-based search/replace suggestions
 pub fn collect_suggestions(raw: &str, issues: &[DIssue]) -> Vec<SearchReplaceWithOffset> {
     // Track the byte offset past the last found occurrence for each (line, decoded_href) pair.
@@ -376,7 +391,11 @@ pub fn actual_offset(raw: &str, dissue: &DIssue, min_byte_offset: usize) -> usiz
 /// Finds a match for search_text that is NOT a prefix of a longer string.
 /// Checks that the match is followed by a delimiter (like ", /, ), or end of string),
 /// not by alphanumeric or / characters.
-fn find_non_prefix_match(raw: &str, line_start_offset: usize, search_text: &str) -> Option<usize> {
+pub(crate) fn find_non_prefix_match(
+    raw: &str,
+    line_start_offset: usize,
+    search_text: &str,
+) -> Option<usize> {
     let line_content = &raw[line_start_offset..];
     let mut search_pos = 0;
 
@@ -0,0 +1,576 @@
+use std::collections::BTreeSet;
+use std::fs;
+
+use comrak::nodes::{AstNode, ListType, NodeValue};
+use comrak::{Arena, Options, parse_document};
+use rari_doc::pages::page::PageLike;
+use rari_doc::pages::types::doc::{FrontMatter, fm_to_string};
+use rari_doc::templ::parser::{Token, parse};
+use rari_doc::utils::split_fm;
+use rari_md::node_card::NoteCard;
+use rari_types::locale::{Locale, default_locale};
+use rari_utils::concat_strs;
+use unicode_width::UnicodeWidthStr;
+
+use crate::error::ToolError;
+use crate::utils::read_all_doc_pages;
+
+/// Formats the markdown of all docs, optionally limited to some locales.
+///
+/// With `check`, nothing is written. Unformatted files are reported and
+/// [`ToolError::UnformattedContent`] is returned if there are any.
+pub fn fmt_content(locales: Option<&[Locale]>, check: bool) -> Result<(), ToolError> {
+    let mut unformatted = vec![];
+    for page in read_all_doc_pages()?.into_values() {
+        if locales.is_some_and(|locales| !locales.contains(&page.locale())) {
+            continue;
+        }
+        let raw = page.raw_content();
+        let formatted = fmt_markdown(raw, page.locale());
+        if formatted == raw {
+            continue;
+        }
+        if check {
+            unformatted.push(page.full_path().to_path_buf());
+        } else {
+            fs::write(page.full_path(), formatted)?;
+        }
+    }
+    if unformatted.is_empty() {
+        return Ok(());
+    }
+    unformatted.sort();
+    for path in &unformatted {
+        tracing::warn!("Not formatted: {}", path.display());
+    }
+    Err(ToolError::UnformattedContent(unformatted.len()))
+}
+
+/// Formats a markdown page, front matter included.
+///
+/// - Front matter is serialized like when writing docs, in MDN's attribute
+///   order, unless that would lose or change anything.
+/// - `*` and `+` list item bullets become `-`, unless the list is next to
+///   another bullet list it would merge with.
+/// - Table columns are padded to the same width, keeping their alignment.
+/// - `> **Note:** …` style notes (in English or the page's locale) and
+///   `> [!NOTE] …` with text on the marker line become `> [!NOTE]` on its
+///   own line.
+///
+/// Code blocks and macro calls are left untouched. If a macro call would
+/// change in any way, the body is returned as is.
+pub fn fmt_markdown(raw: &str, locale: Locale) -> String {
+    let (fm, content_start) = if raw.starts_with("---\n") {
+        split_fm(raw)
+    } else {
+        (None, 0)
+    };
+    let front_matter = match fm.and_then(fmt_front_matter) {
+        Some(fm) => concat_strs!("---\n", &fm, "---\n"),
+        None => raw[..content_start].to_string(),
+    };
+    concat_strs!(&front_matter, &fmt_body(&raw[content_start..], locale))
+}
+
+fn fmt_front_matter(fm: &str) -> Option<String> {
+    let mut formatted = fm_to_string(&serde_yaml_ng::from_str::<FrontMatter>(fm).ok()?).ok()?;
+    if !formatted.ends_with('\n') {
+        formatted.push('\n');
+    }
+    let before = serde_yaml_ng::from_str::<serde_yaml_ng::Value>(fm).ok()?;
+    let after = serde_yaml_ng::from_str::<serde_yaml_ng::Value>(&formatted).ok()?;
+    (before == after).then_some(formatted)
+}
+
+fn macro_sources(content: &str) -> Option<Vec<&str>> {
+    Some(
+        parse(content)
+            .ok()?
+            .into_iter()
+            .filter_map(|token| match token {
+                Token::Macro(mac) => Some(&content[mac.start..mac.end]),
+                Token::Text(_) => None,
+            })
+            .collect(),
+    )
+}
+
+fn fmt_body(content: &str, locale: Locale) -> String {
+    let Ok(tokens) = parse(content) else {
+        return content.to_string();
+    };
+    let macros = tokens
+        .iter()
+        .filter_map(|token| match token {
+            Token::Macro(mac) => Some((mac.start, mac.end)),
+            Token::Text(_) => None,
+        })
+        .collect::<Vec<_>>();
+    let mut offset = 0;
+    let mut lines = vec![];
+    // lines starting inside a macro call spanning several lines are kept as is
+    let mut protected = vec![];
+    for line in content.split_inclusive('\n') {
+        lines.push(line);
+        protected.push(
+            macros
+                .iter()
+                .any(|(start, end)| *start < offset && offset < *end),
+        );
+        offset += line.len();
+    }
+
+    let markers = list_markers(content);
+    let mut out = String::with_capacity(content.len());
+    let mut fence: Option<(char, usize)> = None;
+    let mut quote_depth = 0;
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let (prefix, rest) = split_prefix(line);
+        let depth = prefix.matches('>').count();
+        if protected[i] {
+            out.push_str(line);
+        } else if let Some(open) = fence {
+            if closes_fence(open, rest) {
+                fence = None;
+            }
+            out.push_str(line);
+        } else if let Some(open) = opening_fence(rest) {
+            fence = Some(open);
+            out.push_str(line);
+        } else if let Some((table, len)) = fmt_table(&lines[i..], &protected[i..]) {
+            out.push_str(&table);
+            i += len;
+            quote_depth = 0;
+            continue;
+        } else if let Some(callout) = (depth > quote_depth)
+            .then(|| fmt_callout(prefix, rest, locale))
+            .flatten()
+        {
+            out.push_str(&callout);
+        } else {
+            let mut line = line.to_string();
+            for &(_, column) in markers.range((i, 0)..(i + 1, 0)) {
+                if line
+                    .get(column..)
+                    .is_some_and(|s| s.starts_with(['*', '+']))
+                {
+                    line.replace_range(column..column + 1, "-");
+                }
+            }
+            out.push_str(&line);
+        }
+        quote_depth = depth;
+        i += 1;
+    }
+
+    if macro_sources(&out) != macro_sources(content) {
+        return content.to_string();
+    }
+    out
+}
+
+/// Splits a line into its block quote markers and indentation, and the rest.
+fn split_prefix(line: &str) -> (&str, &str) {
+    line.split_at(
+        line.find(|c| !matches!(c, ' ' | '\t' | '>'))
+            .unwrap_or(line.len()),
+    )
+}
+
+fn opening_fence(rest: &str) -> Option<(char, usize)> {
+    let c = rest.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let len = rest.chars().take_while(|d| *d == c).count();
+    (len >= 3).then_some((c, len))
+}
+
+fn closes_fence((c, len): (char, usize), rest: &str) -> bool {
+    let rest = rest.trim_end();
+    rest.len() >= len && rest.chars().all(|d| d == c)
+}
+
+fn is_bullet_list(node: &AstNode) -> bool {
+    matches!(&node.data.borrow().value, NodeValue::List(list) if list.list_type == ListType::Bullet)
+}
+
+/// Finds the markers of `*` and `+` list items as 0-based `(line, column)`
+/// byte positions.
+///
+/// A bullet list ends where the bullet character changes, so lists next to
+/// another bullet list are skipped, as they would merge with it.
+fn list_markers(content: &str) -> BTreeSet<(usize, usize)> {
+    let arena = Arena::new();
+    let mut options = Options::default();
+    options.extension.table = true;
+    let root = parse_document(&arena, content, &options);
+    root.descendants()
+        .filter(|node| {
+            matches!(
+                &node.data.borrow().value,
+                NodeValue::List(list) if list.list_type == ListType::Bullet && list.bullet_char != b'-'
+            ) && !node.previous_sibling().is_some_and(is_bullet_list)
+                && !node.next_sibling().is_some_and(is_bullet_list)
+        })
+        .flat_map(|list| list.children())
+        .map(|item| {
+            let start = item.data.borrow().sourcepos.start;
+            (start.line - 1, start.column - 1)
+        })
+        .collect()
+}
+
+/// Rewrites the first line of a block quote to the `> [!NOTE]` syntax.
+fn fmt_callout(prefix: &str, rest: &str, locale: Locale) -> Option<String> {
+    let prefix = prefix.trim_end();
+    if !prefix.ends_with('>') {
+        return None;
+    }
+    for card in [NoteCard::Note, NoteCard::Warning, NoteCard::Callout] {
+        let tail = if let Some(tail) = rest.strip_prefix(card.prefix()) {
+            if tail.trim().is_empty() {
+                // already formatted
+                return None;
+            }
+            tail
+        } else if let Some(tail) = [locale, default_locale()]
+            .into_iter()
+            .find_map(|l| rest.strip_prefix(&concat_strs!("**", card.prefix_for_locale(l), "**")))
+        {
+            tail
+        } else {
+            continue;
+        };
+        let tail = tail.trim_start();
+        return Some(if tail.is_empty() {
+            concat_strs!(prefix, " ", card.prefix(), "\n")
+        } else {
+            concat_strs!(prefix, " ", card.prefix(), "\n", prefix, " ", tail)
+        });
+    }
+    None
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Align {
+    None,
+    Left,
+    Center,
+    Right,
+}
+
+/// Formats the table starting at `lines[0]`, if there is one, returning it
+/// and the number of lines it spans.
+fn fmt_table(lines: &[&str], protected: &[bool]) -> Option<(String, usize)> {
+    let (header, delimiter) = (lines.first()?, lines.get(1)?);
+    if !header.contains('|') || protected.get(1) == Some(&true) {
+        return None;
+    }
+    let indent = &header[..header.len() - header.trim_start().len()];
+    if indent.contains('>') || header.trim_start().starts_with('>') {
+        return None;
+    }
+    let aligns = split_cells(delimiter)
+        .into_iter()
+        .map(|cell| {
+            let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+            if dashes.is_empty() || !dashes.chars().all(|c| c == '-') {
+                return None;
+            }
+            Some(match (cell.starts_with(':'), cell.ends_with(':')) {
+                (true, true) => Align::Center,
+                (true, false) => Align::Left,
+                (false, true) => Align::Right,
+                (false, false) => Align::None,
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let mut rows = vec![split_cells(header)];
+    if rows[0].len() != aligns.len() {
+        return None;
+    }
+    let mut len = 2;
+    while let Some(line) = lines.get(len) {
+        if protected[len] || line.trim().is_empty() || !line.contains('|') {
+            break;
+        }
+        let mut cells = split_cells(line);
+        if cells.len() > aligns.len() {
+            return None;
+        }
+        cells.resize(aligns.len(), "");
+        rows.push(cells);
+        len += 1;
+    }
+
+    let widths = (0..aligns.len())
+        .map(|col| {
+            rows.iter()
+                .map(|row| row[col].width())
+                .max()
+                .unwrap_or_default()
+                .max(3)
+        })
+        .collect::<Vec<_>>();
+    let fmt_row = |cells: Vec<String>| concat_strs!(indent, "| ", &cells.join(" | "), " |");
+    let mut out = vec![];
+    for (i, row) in rows.iter().enumerate() {
+        if i == 1 {
+            out.push(fmt_row(
+                aligns
+                    .iter()
+                    .zip(&widths)
+                    .map(|(align, width)| match align {
+                        Align::None => "-".repeat(*width),
+                        Align::Left => concat_strs!(":", &"-".repeat(width - 1)),
+                        Align::Right => concat_strs!(&"-".repeat(width - 1), ":"),
+                        Align::Center => concat_strs!(":", &"-".repeat(width - 2), ":"),
+                    })
+                    .collect(),
+            ));
+        }
+        out.push(fmt_row(
+            row.iter()
+                .zip(&aligns)
+                .zip(&widths)
+                .map(|((cell, align), width)| {
+                    let pad = width - cell.width();
+                    let left = match align {
+                        Align::Right => pad,
+                        Align::Center => pad / 2,
+                        Align::None | Align::Left => 0,
+                    };
+                    concat_strs!(&" ".repeat(left), cell, &" ".repeat(pad - left))
+                })
+                .collect(),
+        ));
+    }
+    let mut table = out.join("\n");
+    if lines[len - 1].ends_with('\n') {
+        table.push('\n');
+    }
+    Some((table, len))
+}
+
+/// Splits a table row into trimmed cells.
+///
+/// Escaped pipes and pipes in macro calls don't separate cells.
+fn split_cells(line: &str) -> Vec<&str> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = match line.strip_suffix('|') {
+        Some(stripped) if !stripped.ends_with('\\') => stripped,
+        _ => line,
+    };
+    let bytes = line.as_bytes();
+    let mut cells = vec![];
+    let mut start = 0;
+    let mut depth = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'{' if bytes.get(i + 1) == Some(&b'{') => {
+                depth += 1;
+                i += 1;
+            }
+            b'}' if depth > 0 && bytes.get(i + 1) == Some(&b'}') => {
+                depth -= 1;
+                i += 1;
+            }
+            b'|' if depth == 0 => {
+                cells.push(line[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    cells.push(line[start..].trim());
+    cells
+}
+
+// These tests use file system fixtures to simulate content and translated content.
+// The file system is a shared resource, so we force tests to be run serially,
+// to avoid concurrent fixture management issues.
+// Using `file_serial` as a synchronization lock, we should be able to run all tests
+// using the same `key` (here: file_fixtures) to be serialized across modules.
+#[cfg(test)]
+use serial_test::file_serial;
+#[cfg(test)]
+#[file_serial(file_fixtures)]
+mod test {
+    use indoc::indoc;
+    use rari_doc::pages::page::Page;
+
+    use super::*;
+    use crate::tests::fixtures::docs::DocFixtures;
+
+    #[test]
+    fn test_fmt_front_matter() {
+        let raw = indoc!(
+            r#"
+            ---
+            slug: Web/API/Foo
+            page-type: web-api-interface
+            title: 'Foo'
+            ---
+
+            Text.
+            "#
+        );
+        assert_eq!(
+            fmt_markdown(raw, Locale::EnUs),
+            indoc!(
+                r#"
+                ---
+                title: Foo
+                slug: Web/API/Foo
+                page-type: web-api-interface
+                ---
+
+                Text.
+                "#
+            )
+        );
+    }
+
+    #[test]
+    fn test_fmt_lists() {
+        let raw = indoc!(
+            r#"
+            * one
+            * two
+              + nested
+
+            ***
+
+            > * quoted
+
+            <pre>
+            * in html
+            </pre>
+
+                * indented code
+
+            ```md
+            * in code
+            ```
+
+            - dash
+            + plus, a separate list
+            "#
+        );
+        assert_eq!(
+            fmt_body(raw, Locale::EnUs),
+            indoc!(
+                r#"
+                - one
+                - two
+                  - nested
+
+                ***
+
+                > - quoted
+
+                <pre>
+                * in html
+                </pre>
+
+                    * indented code
+
+                ```md
+                * in code
+                ```
+
+                - dash
+                + plus, a separate list
+                "#
+            )
+        );
+    }
+
+    #[test]
+    fn test_fmt_table() {
+        let raw = indoc!(
+            r#"
+            |Name|Value|
+            |:-|-:|
+            |{{cssxref("a|b")}}|1|
+            |`c`|
+            "#
+        );
+        assert_eq!(
+            fmt_body(raw, Locale::EnUs),
+            indoc!(
+                r#"
+                | Name               | Value |
+                | :----------------- | ----: |
+                | {{cssxref("a|b")}} |     1 |
+                | `c`                |       |
+                "#
+            )
+        );
+    }
+
+    #[test]
+    fn test_fmt_callouts() {
+        let raw = indoc!(
+            r#"
+            > **Note:** Some {{domxref("Node")}}.
+
+            > [!WARNING] Careful.
+            > More.
+
+            > [!NOTE]
+            > Fine.
+            "#
+        );
+        assert_eq!(
+            fmt_body(raw, Locale::EnUs),
+            indoc!(
+                r#"
+                > [!NOTE]
+                > Some {{domxref("Node")}}.
+
+                > [!WARNING]
+                > Careful.
+                > More.
+
+                > [!NOTE]
+                > Fine.
+                "#
+            )
+        );
+        assert_eq!(
+            fmt_body("> **Remarque :** Texte.\n", Locale::Fr),
+            "> [!CALLOUT]\n> Texte.\n"
+        );
+    }
+
+    #[test]
+    fn test_fmt_keeps_macros() {
+        assert_eq!(
+            fmt_body("* {{Foo(\"a\",\n* b)}}\n", Locale::EnUs),
+            "- {{Foo(\"a\",\n* b)}}\n"
+        );
+    }
+
+    #[test]
+    fn test_fmt_content_check() {
+        let _docs = DocFixtures::new(&["Web/API/ExampleOne".to_string()], Locale::EnUs);
+        DocFixtures::create_doc_with_content("Web/API/ExampleOne", Locale::EnUs, "* item");
+        assert!(matches!(
+            fmt_content(None, true),
+            Err(ToolError::UnformattedContent(1))
+        ));
+        fmt_content(None, false).unwrap();
+        fmt_content(None, true).unwrap();
+        let page = Page::from_url("/en-US/docs/Web/API/ExampleOne").unwrap();
+        assert!(
+            fs::read_to_string(page.full_path())
+                .unwrap()
+                .contains("\n- item\n")
+        );
+    }
+}
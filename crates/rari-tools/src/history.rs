@@ -5,19 +5,35 @@ use std::path::{Path, PathBuf};
 use std::thread::spawn;
 
 use rari_types::HistoryEntry;
-use rari_types::globals::{content_root, content_translated_root};
+use rari_types::globals::{
+    blog_root, content_root, content_translated_root, contributor_spotlight_root, curriculum_root,
+    generic_content_root,
+};
 use rari_utils::git::exec_git;
 
 use crate::error::ToolError;
 
+/// Writes the last modification of every markdown file (and `config.json`) to
+/// `_git_history.json` in the content roots (translated content, blog, curriculum, generic
+/// content and contributor spotlights, if set).
 pub fn gather_history() -> Result<(), ToolError> {
-    let handle = content_translated_root().map(|translated_root| {
+    let handles = [
+        content_translated_root(),
+        blog_root(),
+        curriculum_root(),
+        generic_content_root(),
+        contributor_spotlight_root(),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|root| {
         spawn(|| {
-            modification_times(translated_root).unwrap();
+            modification_times(root).unwrap();
         })
-    });
+    })
+    .collect::<Vec<_>>();
     modification_times(content_root())?;
-    if let Some(handle) = handle {
+    for handle in handles {
         handle.join().expect("Unable to join history thread.");
     }
     Ok(())
@@ -28,6 +44,17 @@ fn modification_times(path: &Path) -> Result<(), ToolError> {
 
     let repo_root_raw = String::from_utf8_lossy(&output.stdout);
     let repo_root = repo_root_raw.trim();
+    // git lists paths relative to the repo root, e.g. `files/` for content
+    let root_prefix = path
+        .canonicalize()?
+        .strip_prefix(Path::new(repo_root).canonicalize()?)
+        .map(Path::to_path_buf)
+        .map_err(|_| {
+            ToolError::GitError(format!(
+                "{} is not in the git repository {repo_root}",
+                path.display()
+            ))
+        })?;
 
     let output = exec_git(
         &[
@@ -68,8 +95,9 @@ fn modification_times(path: &Path) -> Result<(), ToolError> {
             {
                 parents.insert(parent_hash, HistoryEntry::new(date, hash));
             }
-        } else if line.ends_with("index.md")
-            && let Ok(rel_path) = PathBuf::from(line).strip_prefix("files")
+        } else if let Ok(rel_path) = Path::new(line).strip_prefix(&root_prefix)
+            // `config.json` defines the SPAs of the generic content
+            && (line.ends_with(".md") || rel_path == Path::new("config.json"))
         {
             history.insert(rel_path.to_path_buf(), HistoryEntry::new(date, hash));
         }
@@ -0,0 +1,444 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use rari_doc::pages::page::{Page, PageLike};
+use rari_doc::resolve::url_meta_from;
+use rari_types::locale::Locale;
+use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use tracing::{Level, span};
+
+use crate::error::ToolError;
+use crate::fix::issues::{SearchReplaceWithOffset, apply_suggestions, find_non_prefix_match};
+use crate::utils::read_all_doc_pages;
+
+type NormalizeArg = fn(&str) -> String;
+
+/// Link macros whose first argument is a slug relative to a fixed base slug,
+/// together with that base and the normalization the macro applies to the
+/// argument before appending it.
+///
+/// Macros that resolve their argument through an index (like `jsxref` or
+/// `cssxref`) are not listed, because the argument cannot be mapped back to a
+/// slug without building the page.
+const LINK_MACROS: &[(&str, &str, NormalizeArg)] = &[
+    ("domxref", "Web/API/", api_arg_to_path),
+    ("glossary", "Glossary/", underscore_spaces),
+    ("htmlelement", "Web/HTML/Reference/Elements/", identity),
+    ("httpheader", "Web/HTTP/Reference/Headers/", identity),
+    ("httpmethod", "Web/HTTP/Reference/Methods/", identity),
+    ("httpstatus", "Web/HTTP/Reference/Status/", identity),
+    ("mathmlelement", "Web/MathML/Reference/Element/", identity),
+    ("svgattr", "Web/SVG/Reference/Attribute/", identity),
+    ("svgelement", "Web/SVG/Reference/Element/", identity),
+    (
+        "webextapiref",
+        "Mozilla/Add-ons/WebExtensions/API/",
+        api_arg_to_path,
+    ),
+];
+
+fn identity(arg: &str) -> String {
+    arg.to_string()
+}
+
+fn underscore_spaces(arg: &str) -> String {
+    arg.replace(' ', "_")
+}
+
+fn api_arg_to_path(arg: &str) -> String {
+    arg.replace(' ', "_")
+        .replace("()", "")
+        .replace(".prototype.", ".")
+        .replace('.', "/")
+}
+
+/// A single old → new URL pair that inbound links should be rewritten for.
+#[derive(Debug, Clone)]
+struct Rewrite<'a> {
+    old_url: &'a str,
+    new_url: &'a str,
+    /// `(locale, slug)` of the old URL.
+    old: (Locale, &'a str),
+    /// `(locale, slug)` of the new URL, `None` for external targets.
+    new: Option<(Locale, &'a str)>,
+}
+
+/// Rewrites Markdown links and link-macro arguments pointing at the `from`
+/// side of each `(from_url, to_url)` pair to the `to` side, in all locales.
+///
+/// Full URLs (`/en-US/docs/Web/API/Foo`) are rewritten everywhere. Slug-only
+/// macro arguments (`{{domxref("Foo")}}`, `{{Glossary("Foo")}}`) are only
+/// rewritten in pages of the locale the URL belongs to, since they resolve
+/// relative to the page's locale.
+///
+/// Returns the paths of the files that were changed.
+pub fn rewrite_inbound_links(pairs: &[(String, String)]) -> Result<Vec<PathBuf>, ToolError> {
+    let rewrites = pairs
+        .iter()
+        .map(|(old_url, new_url)| {
+            let old_meta = url_meta_from(old_url)?;
+            let new = if new_url.starts_with('/') {
+                let new_meta = url_meta_from(new_url)?;
+                Some((new_meta.locale, new_meta.slug))
+            } else {
+                None
+            };
+            Ok(Rewrite {
+                old_url,
+                new_url,
+                old: (old_meta.locale, old_meta.slug),
+                new,
+            })
+        })
+        .collect::<Result<Vec<_>, ToolError>>()?;
+
+    let docs = read_all_doc_pages()?;
+    let docs = docs.values().collect::<Vec<_>>();
+    docs.par_iter()
+        .filter_map(|page| match rewrite_page(page, &rewrites) {
+            Ok(true) => Some(Ok(page.full_path().to_path_buf())),
+            Ok(false) => None,
+            Err(e) => Some(Err(e)),
+        })
+        .collect()
+}
+
+/// Builds an index of all doc URLs linked from `docs`, either as Markdown
+/// links or through link macros. URLs are lowercased, without fragment.
+///
+/// Links from translated pages are also recorded under their en-US
+/// equivalent, because en-US redirects apply to translated content that has
+/// no redirect of its own.
+pub(crate) fn link_index<'a>(docs: impl IntoParallelIterator<Item = &'a Page>) -> HashSet<String> {
+    docs.into_par_iter()
+        .flat_map_iter(|page| {
+            let raw = page.raw_content();
+            let locale = page.locale();
+            let mut urls = doc_links(raw)
+                .into_iter()
+                .map(str::to_lowercase)
+                .collect::<Vec<_>>();
+            urls.extend(
+                macro_first_args(raw)
+                    .into_iter()
+                    .filter_map(|(_, name, arg)| {
+                        LINK_MACROS
+                            .iter()
+                            .find(|(macro_name, _, _)| name == *macro_name)
+                            .map(|(_, base, normalize)| {
+                                format!("/{}/docs/{base}{}", locale.as_url_str(), normalize(arg))
+                                    .to_lowercase()
+                            })
+                    }),
+            );
+            let en_us = urls
+                .iter()
+                .filter_map(|url| {
+                    let (locale, slug) = url.strip_prefix('/')?.split_once("/docs/")?;
+                    (locale != "en-us").then(|| format!("/en-us/docs/{slug}"))
+                })
+                .collect::<Vec<_>>();
+            urls.extend(en_us);
+            urls
+        })
+        .collect()
+}
+
+/// Finds all `/<locale>/docs/…` URLs in `raw`, without fragment or query.
+fn doc_links(raw: &str) -> Vec<&str> {
+    let mut links = vec![];
+    let mut pos = 0;
+    while let Some(docs) = raw[pos..].find("/docs/").map(|i| i + pos) {
+        pos = docs + "/docs/".len();
+        let Some(start) = raw[..docs].rfind('/') else {
+            continue;
+        };
+        if Locale::from_str(&raw[start + 1..docs]).is_err() {
+            continue;
+        }
+        let end = raw[pos..]
+            .find(|c: char| {
+                c.is_whitespace()
+                    || matches!(c, ')' | '"' | '\'' | '#' | '?' | '<' | '>' | ']' | '`')
+            })
+            .map(|i| i + pos)
+            .unwrap_or(raw.len());
+        links.push(&raw[start..end]);
+        pos = end;
+    }
+    links
+}
+
+fn rewrite_page(page: &Page, rewrites: &[Rewrite]) -> Result<bool, ToolError> {
+    let span = span!(
+        Level::ERROR,
+        "page",
+        locale = page.locale().as_url_str(),
+        slug = page.slug(),
+        file = page.full_path().to_string_lossy().as_ref()
+    );
+    let _enter = span.enter();
+
+    let raw = page.raw_content();
+    let mut suggestions = collect_rewrites(raw, page.locale(), rewrites);
+    if suggestions.is_empty() {
+        return Ok(false);
+    }
+    suggestions.sort_by_key(|s| s.offset());
+    suggestions.dedup();
+
+    let rewritten = apply_suggestions(raw, &suggestions)?;
+    if rewritten == raw {
+        return Ok(false);
+    }
+    tracing::info!("rewriting links in {}", page.full_path().display());
+    let file = File::create(page.full_path())?;
+    let mut buffed = BufWriter::new(file);
+    buffed.write_all(rewritten.as_bytes())?;
+    Ok(true)
+}
+
+fn collect_rewrites(
+    raw: &str,
+    page_locale: Locale,
+    rewrites: &[Rewrite],
+) -> Vec<SearchReplaceWithOffset> {
+    let mut suggestions = vec![];
+    for rewrite in rewrites {
+        let mut from = 0;
+        while let Some(offset) = find_non_prefix_match(raw, from, rewrite.old_url) {
+            suggestions.push(SearchReplaceWithOffset::new(
+                offset,
+                rewrite.old_url,
+                rewrite.new_url,
+            ));
+            from = offset + rewrite.old_url.len();
+        }
+    }
+    for (offset, name, arg) in macro_first_args(raw) {
+        if let Some(replace) = rewrite_macro_arg(&name, arg, page_locale, rewrites) {
+            suggestions.push(SearchReplaceWithOffset::new(offset, arg, &replace));
+        }
+    }
+    suggestions
+}
+
+/// Computes the replacement for the first argument `arg` of macro `name`, if it
+/// refers to the old slug of one of the `rewrites`.
+fn rewrite_macro_arg(
+    name: &str,
+    arg: &str,
+    page_locale: Locale,
+    rewrites: &[Rewrite],
+) -> Option<String> {
+    let link_macro = LINK_MACROS
+        .iter()
+        .find(|(macro_name, _, _)| name.eq_ignore_ascii_case(macro_name));
+    for rewrite in rewrites {
+        let (old_locale, old_slug) = rewrite.old;
+        if old_locale != page_locale {
+            continue;
+        }
+        let matches_plain = arg.eq_ignore_ascii_case(old_slug);
+        let matches_macro = link_macro.is_some_and(|(_, base, normalize)| {
+            format!("{base}{}", normalize(arg)).eq_ignore_ascii_case(old_slug)
+        });
+        if !matches_plain && !matches_macro {
+            continue;
+        }
+        let Some((_, new_slug)) = rewrite.new.filter(|(locale, _)| *locale == page_locale) else {
+            tracing::warn!(
+                "Cannot rewrite {{{{{name}(\"{arg}\")}}}}: {} is not in the same locale",
+                rewrite.new_url
+            );
+            return None;
+        };
+        if matches_plain {
+            return Some(new_slug.to_string());
+        }
+        if let Some((_, base, _)) = link_macro
+            && let Some(rest) = new_slug.strip_prefix(base)
+        {
+            return Some(rest.to_string());
+        }
+        tracing::warn!(
+            "Cannot rewrite {{{{{name}(\"{arg}\")}}}}: {} is outside of the macro's base path",
+            rewrite.new_url
+        );
+        return None;
+    }
+    None
+}
+
+/// Finds all macro calls in `raw` that have a quoted first argument and
+/// returns the byte offset of the argument (without quotes), the macro name,
+/// and the argument.
+fn macro_first_args(raw: &str) -> Vec<(usize, String, &str)> {
+    let mut args = vec![];
+    let mut pos = 0;
+    while let Some(start) = raw[pos..].find("{{").map(|i| i + pos + 2) {
+        let Some(end) = raw[start..].find("}}").map(|i| i + start) else {
+            break;
+        };
+        pos = end + 2;
+        let call = &raw[start..end];
+        let Some(open) = call.find('(') else {
+            continue;
+        };
+        let name = call[..open].trim();
+        let after_open = &call[open + 1..];
+        let trimmed = after_open.trim_start();
+        let Some(quote) = trimmed.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            continue;
+        };
+        let arg_start = start + open + 1 + (after_open.len() - trimmed.len()) + 1;
+        let Some(arg_len) = raw[arg_start..end].find(quote) else {
+            continue;
+        };
+        args.push((
+            arg_start,
+            name.to_ascii_lowercase(),
+            &raw[arg_start..arg_start + arg_len],
+        ));
+    }
+    args
+}
+
+// These tests use file system fixtures to simulate content and translated content.
+// The file system is a shared resource, so we force tests to be run serially,
+// to avoid concurrent fixture management issues.
+#[cfg(test)]
+use serial_test::file_serial;
+#[cfg(test)]
+#[file_serial(file_fixtures)]
+mod test {
+    use super::*;
+    use crate::tests::fixtures::docs::DocFixtures;
+
+    fn rewrite<'a>(old_url: &'a str, new_url: &'a str) -> Rewrite<'a> {
+        let slug = |url: &'a str| url.split_once("/docs/").map(|(_, s)| s).unwrap();
+        Rewrite {
+            old_url,
+            new_url,
+            old: (Locale::EnUs, slug(old_url)),
+            new: new_url
+                .starts_with('/')
+                .then(|| (Locale::EnUs, slug(new_url))),
+        }
+    }
+
+    fn apply(raw: &str, page_locale: Locale, rewrites: &[Rewrite]) -> String {
+        let mut suggestions = collect_rewrites(raw, page_locale, rewrites);
+        suggestions.sort_by_key(|s| s.offset());
+        apply_suggestions(raw, &suggestions).unwrap()
+    }
+
+    #[test]
+    fn test_macro_first_args() {
+        let raw =
+            r#"See {{domxref("Foo.bar()")}} and {{ Glossary( 'HTML', "markup") }}, {{Compat}}."#;
+        let args = macro_first_args(raw);
+        assert_eq!(args.len(), 2);
+        assert_eq!(args[0].1, "domxref");
+        assert_eq!(args[0].2, "Foo.bar()");
+        assert_eq!(&raw[args[0].0..args[0].0 + args[0].2.len()], "Foo.bar()");
+        assert_eq!(args[1].1, "glossary");
+        assert_eq!(args[1].2, "HTML");
+        assert_eq!(&raw[args[1].0..args[1].0 + 4], "HTML");
+    }
+
+    #[test]
+    fn test_doc_links() {
+        let raw = "[a](/en-US/docs/Web/API/Foo#bar) [b](https://example.com/docs/x) <a href=\"/de/docs/Web\">c</a>\n[d]: /fr/docs/Glossary/DOM";
+        assert_eq!(
+            doc_links(raw),
+            vec![
+                "/en-US/docs/Web/API/Foo",
+                "/de/docs/Web",
+                "/fr/docs/Glossary/DOM"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rewrite_markdown_links() {
+        let rewrites = [rewrite(
+            "/en-US/docs/Web/API/Foo",
+            "/en-US/docs/Web/API/Bar",
+        )];
+        let raw = "[Foo](/en-US/docs/Web/API/Foo), [Foo#x](/en-US/docs/Web/API/Foo#x), [Sub](/en-US/docs/Web/API/Foo/Sub), [FooBar](/en-US/docs/Web/API/FooBar)\n\n[foo]: /en-US/docs/Web/API/Foo\n";
+        assert_eq!(
+            apply(raw, Locale::EnUs, &rewrites),
+            "[Foo](/en-US/docs/Web/API/Bar), [Foo#x](/en-US/docs/Web/API/Bar#x), [Sub](/en-US/docs/Web/API/Foo/Sub), [FooBar](/en-US/docs/Web/API/FooBar)\n\n[foo]: /en-US/docs/Web/API/Bar\n"
+        );
+        // Full URLs are rewritten in other locales as well.
+        assert_eq!(
+            apply("[Foo](/en-US/docs/Web/API/Foo)", Locale::De, &rewrites),
+            "[Foo](/en-US/docs/Web/API/Bar)"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_macro_args() {
+        let rewrites = [
+            rewrite("/en-US/docs/Web/API/Foo/bar", "/en-US/docs/Web/API/Baz/bar"),
+            rewrite(
+                "/en-US/docs/Glossary/Old_term",
+                "/en-US/docs/Glossary/New_term",
+            ),
+            rewrite("/en-US/docs/Web/API/Gone", "/en-US/docs/Web/HTML/Gone"),
+        ];
+        let raw = r#"{{domxref("Foo.bar()")}} {{Glossary("Old term", "old")}} {{domxref("Gone")}} {{EmbedLiveSample("x")}}"#;
+        assert_eq!(
+            apply(raw, Locale::EnUs, &rewrites),
+            r#"{{domxref("Baz/bar")}} {{Glossary("New_term", "old")}} {{domxref("Gone")}} {{EmbedLiveSample("x")}}"#
+        );
+        // Slug-only arguments resolve relative to the page's locale.
+        assert_eq!(apply(raw, Locale::De, &rewrites), raw);
+    }
+
+    #[test]
+    fn test_link_index() {
+        let slugs = vec!["Web/API/ExampleOne".to_string()];
+        let _docs = DocFixtures::new(&slugs, Locale::EnUs);
+        DocFixtures::create_doc_with_content(
+            "Web/API/ExampleOne",
+            Locale::EnUs,
+            "[Two](/en-US/docs/Web/API/ExampleTwo#x) {{domxref(\"ExampleThree.foo()\")}} {{Glossary(\"Some term\")}}",
+        );
+        let docs = read_all_doc_pages().unwrap();
+        let index = link_index(docs.values().collect::<Vec<_>>());
+        assert!(index.contains("/en-us/docs/web/api/exampletwo"));
+        assert!(index.contains("/en-us/docs/web/api/examplethree/foo"));
+        assert!(index.contains("/en-us/docs/glossary/some_term"));
+        assert!(!index.contains("/en-us/docs/web/api/exampleone"));
+    }
+
+    #[test]
+    fn test_rewrite_inbound_links() {
+        let slugs = vec![
+            "Web/API/ExampleOne".to_string(),
+            "Web/API/ExampleTwo".to_string(),
+        ];
+        let _docs = DocFixtures::new(&slugs, Locale::EnUs);
+        DocFixtures::create_doc_with_content(
+            "Web/API/ExampleTwo",
+            Locale::EnUs,
+            "See [one](/en-US/docs/Web/API/ExampleOne) and {{domxref(\"ExampleOne\")}}.",
+        );
+
+        let changed = rewrite_inbound_links(&[(
+            "/en-US/docs/Web/API/ExampleOne".to_string(),
+            "/en-US/docs/Web/API/ExampleOneNewLocation".to_string(),
+        )])
+        .unwrap();
+        assert_eq!(changed.len(), 1);
+        let content = std::fs::read_to_string(&changed[0]).unwrap();
+        assert!(content.contains(
+            "See [one](/en-US/docs/Web/API/ExampleOneNewLocation) and {{domxref(\"ExampleOneNewLocation\")}}."
+        ));
+    }
+}
@@ -1,11 +1,18 @@
 pub mod add_redirect;
+pub mod convert_macros;
 pub mod error;
 pub mod fix;
+pub mod fmt;
 pub mod git;
 pub mod history;
+pub mod inbound_links;
 pub mod inventory;
+pub mod macro_usage;
 pub mod r#move;
+pub mod new;
 pub mod redirects;
+pub mod redirects_export;
+pub mod redirects_report;
 pub mod remove;
 pub mod sidebars;
 pub mod sync_translated_content;
@@ -0,0 +1,223 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use rari_doc::pages::page::PageLike;
+use rari_doc::templ::parser::{Token, parse};
+use rari_doc::templ::templs::exists;
+use rari_types::locale::Locale;
+use serde::Serialize;
+
+use crate::error::ToolError;
+use crate::utils::{csv_field, read_all_doc_pages};
+
+/// Output formats for [`macro_usage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum MacroUsageFormat {
+    /// Usage nested by macro, locale and page.
+    Json,
+    /// `macro,locale,slug,line,column` rows with a header line.
+    Csv,
+}
+
+/// The 1-based position of a macro call in a page's markdown file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct MacroPosition {
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub struct LocaleMacroUsage {
+    pub count: usize,
+    /// Positions of the calls by page slug.
+    pub pages: BTreeMap<String, Vec<MacroPosition>>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub struct MacroUsage {
+    pub count: usize,
+    /// Whether rari implements the macro.
+    pub known: bool,
+    pub locales: BTreeMap<Locale, LocaleMacroUsage>,
+}
+
+/// Reports where macros are used across all docs, optionally limited to one
+/// macro and some locales, writing to `out` or stdout.
+///
+/// Macro calls are found by parsing the markdown, nothing is rendered.
+/// Macro names are normalized like when rendering: lowercase with `_` for
+/// `-`.
+pub fn macro_usage(
+    macro_filter: Option<&str>,
+    locale_filter: Option<&[Locale]>,
+    format: MacroUsageFormat,
+    out: Option<&Path>,
+) -> Result<(), ToolError> {
+    let usage = gather_macro_usage(macro_filter, locale_filter)?;
+    let rendered = render(&usage, format)?;
+    match out {
+        Some(path) => {
+            let mut file = BufWriter::new(File::create(path)?);
+            file.write_all(rendered.as_bytes())?;
+        }
+        None => std::io::stdout().write_all(rendered.as_bytes())?,
+    }
+    Ok(())
+}
+
+fn normalize_name(name: &str) -> String {
+    name.to_ascii_lowercase().replace('-', "_")
+}
+
+fn gather_macro_usage(
+    macro_filter: Option<&str>,
+    locale_filter: Option<&[Locale]>,
+) -> Result<BTreeMap<String, MacroUsage>, ToolError> {
+    let macro_filter = macro_filter.map(normalize_name);
+    let mut usage = BTreeMap::<String, MacroUsage>::new();
+    for page in read_all_doc_pages()?.into_values() {
+        if locale_filter.is_some_and(|filter| !filter.contains(&page.locale())) {
+            continue;
+        }
+        let tokens = match parse(page.content()) {
+            Ok(tokens) => tokens,
+            Err(e) => {
+                tracing::warn!("Could not parse {}: {e}", page.full_path().display());
+                continue;
+            }
+        };
+        for token in tokens {
+            let Token::Macro(mac) = token else {
+                continue;
+            };
+            let name = normalize_name(&mac.ident);
+            if macro_filter.as_ref().is_some_and(|filter| *filter != name) {
+                continue;
+            }
+            let macro_usage = usage.entry(name).or_default();
+            macro_usage.count += 1;
+            let locale_usage = macro_usage.locales.entry(page.locale()).or_default();
+            locale_usage.count += 1;
+            locale_usage
+                .pages
+                .entry(page.slug().to_string())
+                .or_default()
+                .push(MacroPosition {
+                    line: mac.pos.0 + page.fm_offset() + 1,
+                    column: mac.pos.1 + 1,
+                });
+        }
+    }
+    for (name, macro_usage) in usage.iter_mut() {
+        macro_usage.known = exists(name);
+        for locale_usage in macro_usage.locales.values_mut() {
+            for positions in locale_usage.pages.values_mut() {
+                positions.sort();
+            }
+        }
+    }
+    Ok(usage)
+}
+
+fn render(
+    usage: &BTreeMap<String, MacroUsage>,
+    format: MacroUsageFormat,
+) -> Result<String, ToolError> {
+    Ok(match format {
+        MacroUsageFormat::Json => {
+            let mut out = serde_json::to_string_pretty(usage)?;
+            out.push('\n');
+            out
+        }
+        MacroUsageFormat::Csv => {
+            let mut out = String::from("macro,locale,slug,line,column\n");
+            for (name, macro_usage) in usage {
+                for (locale, locale_usage) in &macro_usage.locales {
+                    for (slug, positions) in &locale_usage.pages {
+                        for MacroPosition { line, column } in positions {
+                            out.push_str(&format!(
+                                "{name},{locale},{},{line},{column}\n",
+                                csv_field(slug)
+                            ));
+                        }
+                    }
+                }
+            }
+            out
+        }
+    })
+}
+
+// These tests use file system fixtures to simulate content and translated content.
+// The file system is a shared resource, so we force tests to be run serially,
+// to avoid concurrent fixture management issues.
+// Using `file_serial` as a synchronization lock, we should be able to run all tests
+// using the same `key` (here: file_fixtures) to be serialized across modules.
+#[cfg(test)]
+use serial_test::file_serial;
+#[cfg(test)]
+#[file_serial(file_fixtures)]
+mod test {
+    use clap::ValueEnum;
+    use indoc::indoc;
+
+    use super::*;
+    use crate::tests::fixtures::docs::DocFixtures;
+
+    #[test]
+    fn test_macro_usage() {
+        let _docs = DocFixtures::new(&["Web/API/ExampleOne".to_string()], Locale::EnUs);
+        DocFixtures::create_doc_with_content(
+            "Web/API/ExampleOne",
+            Locale::EnUs,
+            indoc!(
+                r#"
+                {{DOMxRef("Node")}} and {{domxref("Element")}}.
+
+                {{Some-Unknown-Macro}}
+                "#
+            ),
+        );
+        let _translated = DocFixtures::new(&["Web/API/ExampleOne".to_string()], Locale::PtBr);
+        DocFixtures::create_doc_with_content(
+            "Web/API/ExampleOne",
+            Locale::PtBr,
+            r#"{{domxref("Node")}}"#,
+        );
+
+        let usage = gather_macro_usage(None, None).unwrap();
+        let domxref = &usage["domxref"];
+        assert!(domxref.known);
+        assert_eq!(domxref.count, 3);
+        assert_eq!(
+            domxref.locales[&Locale::EnUs].pages["Web/API/ExampleOne"],
+            vec![
+                MacroPosition { line: 6, column: 1 },
+                MacroPosition {
+                    line: 6,
+                    column: 25
+                }
+            ]
+        );
+        assert_eq!(domxref.locales[&Locale::PtBr].count, 1);
+        assert!(!usage["some_unknown_macro"].known);
+
+        let usage = gather_macro_usage(Some("DOMxRef"), Some(&[Locale::PtBr])).unwrap();
+        assert_eq!(usage.keys().collect::<Vec<_>>(), vec!["domxref"]);
+        assert_eq!(
+            render(&usage, MacroUsageFormat::Csv).unwrap(),
+            "macro,locale,slug,line,column\ndomxref,pt-BR,Web/API/ExampleOne,6,1\n"
+        );
+    }
+
+    #[test]
+    fn test_format_from_str() {
+        assert_eq!(
+            MacroUsageFormat::from_str("csv", false).unwrap(),
+            MacroUsageFormat::Csv
+        );
+        assert!(MacroUsageFormat::from_str("xml", false).is_err());
+    }
+}
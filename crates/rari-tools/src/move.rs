@@ -16,6 +16,7 @@ use rari_types::locale::Locale;
 
 use crate::error::ToolError;
 use crate::git::exec_git_with_test_fallback;
+use crate::inbound_links::rewrite_inbound_links;
 use crate::redirects::add_redirects;
 use crate::sidebars::update_sidebars;
 use crate::utils::parent_slug;
@@ -26,6 +27,7 @@ pub fn r#move(
     new_slug: &str,
     locale: Option<Locale>,
     assume_yes: bool,
+    rewrite_links: bool,
 ) -> Result<(), ToolError> {
     validate_args(old_slug, new_slug)?;
     let locale = locale.unwrap_or_default();
@@ -56,6 +58,19 @@ pub fn r#move(
     {
         let moved = do_move(old_slug, new_slug, locale, false)?;
         tracing::info!("Moved {} documents", moved.len(),);
+
+        if rewrite_links {
+            let url_pairs = moved
+                .iter()
+                .map(|(old_slug, new_slug)| {
+                    let old_url = build_url(old_slug, locale, PageCategory::Doc)?;
+                    let new_url = build_url(new_slug, locale, PageCategory::Doc)?;
+                    Ok((old_url, new_url))
+                })
+                .collect::<Result<Vec<_>, ToolError>>()?;
+            let rewritten = rewrite_inbound_links(&url_pairs)?;
+            tracing::info!("Rewrote links in {} documents", rewritten.len());
+        }
     } else {
         return Ok(());
     }
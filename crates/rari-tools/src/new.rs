@@ -0,0 +1,371 @@
+use std::borrow::Cow;
+use std::fs;
+use std::path::PathBuf;
+
+use rari_doc::pages::page::PageCategory;
+use rari_doc::pages::types::doc::{FrontMatter, fm_to_string};
+use rari_doc::pages::types::utils::FmTempl;
+use rari_doc::resolve::{UrlMeta, build_url, url_meta_from};
+use rari_doc::utils::root_for_locale;
+use rari_types::fm_types::PageType;
+use rari_types::locale::Locale;
+use rari_utils::concat_strs;
+
+use crate::error::ToolError;
+use crate::sidebars::insert_into_sidebars;
+
+/// Creates a new en-US page at `slug` from the template for `page_type` and
+/// adds it to the sidebars listing its siblings.
+///
+/// The title defaults to the last segment of the slug. If `browser_compat`
+/// is given, it is added to the front matter so `{{Compat}}` and
+/// `{{Specifications}}` render without arguments.
+///
+/// Web API pages get an `{{APIRef}}` sidebar in the body with a placeholder
+/// for the API group, which has to be filled in by hand.
+pub fn new_page(
+    slug: &str,
+    page_type: PageType,
+    title: Option<&str>,
+    browser_compat: Option<&str>,
+) -> Result<PathBuf, ToolError> {
+    validate_args(slug)?;
+    let locale = Locale::default();
+    let url = build_url(slug, locale, PageCategory::Doc)?;
+    let UrlMeta { folder_path, .. } = url_meta_from(&url)?;
+    let mut path = root_for_locale(locale)?.to_path_buf();
+    path.push(locale.as_folder_str());
+    path.push(&folder_path);
+    if path.try_exists()? {
+        return Err(ToolError::TargetDirExists(folder_path, slug.to_string()));
+    }
+
+    let title = title.unwrap_or_else(|| slug.rsplit('/').next().unwrap_or(slug));
+    let content = render_page(slug, page_type, title, browser_compat)?;
+    fs::create_dir_all(&path)?;
+    path.push("index.md");
+    fs::write(&path, content)?;
+    tracing::info!("Created {}", path.display());
+
+    for sidebar in insert_into_sidebars(slug)? {
+        tracing::info!("Added {} to {}", url, sidebar.display());
+    }
+    Ok(path)
+}
+
+fn render_page(
+    slug: &str,
+    page_type: PageType,
+    title: &str,
+    browser_compat: Option<&str>,
+) -> Result<String, ToolError> {
+    let fm = FrontMatter {
+        title: title.to_string(),
+        slug: slug.to_string(),
+        page_type,
+        browser_compat: browser_compat.map(String::from).into_iter().collect(),
+        sidebar: sidebar_for(page_type)
+            .map(|name| FmTempl::NoArgs(name.to_string()))
+            .into_iter()
+            .collect(),
+        ..Default::default()
+    };
+    let mut content = concat_strs!("---\n", &fm_to_string(&fm)?, "---\n");
+    if is_web_api(page_type) {
+        content.push('\n');
+        content.push_str(API_REF);
+        content.push('\n');
+    }
+    content.push_str("\n<!-- Summary of ");
+    content.push_str(title);
+    content.push_str(". -->\n");
+    for (heading, body) in sections_for(page_type) {
+        content.push_str("\n## ");
+        content.push_str(heading);
+        content.push('\n');
+        if !body.is_empty() {
+            content.push('\n');
+            content.push_str(body);
+            content.push('\n');
+        }
+    }
+    Ok(content)
+}
+
+/// The sidebar template used by pages of this type.
+fn sidebar_for(page_type: PageType) -> Option<&'static str> {
+    Some(match page_type {
+        PageType::CssAtRule
+        | PageType::CssAtRuleDescriptor
+        | PageType::CssCombinator
+        | PageType::CssFunction
+        | PageType::CssKeyword
+        | PageType::CssMediaFeature
+        | PageType::CssModule
+        | PageType::CssProperty
+        | PageType::CssPseudoClass
+        | PageType::CssPseudoElement
+        | PageType::CssSelector
+        | PageType::CssShorthandProperty
+        | PageType::CssType => "cssref",
+        PageType::HtmlAttribute | PageType::HtmlAttributeValue | PageType::HtmlElement => {
+            "htmlsidebar"
+        }
+        PageType::JavascriptClass
+        | PageType::JavascriptConstructor
+        | PageType::JavascriptError
+        | PageType::JavascriptFunction
+        | PageType::JavascriptGlobalProperty
+        | PageType::JavascriptInstanceAccessorProperty
+        | PageType::JavascriptInstanceDataProperty
+        | PageType::JavascriptInstanceMethod
+        | PageType::JavascriptLanguageFeature
+        | PageType::JavascriptNamespace
+        | PageType::JavascriptOperator
+        | PageType::JavascriptStatement
+        | PageType::JavascriptStaticAccessorProperty
+        | PageType::JavascriptStaticDataProperty
+        | PageType::JavascriptStaticMethod => "jsref",
+        PageType::GlossaryDefinition | PageType::GlossaryDisambiguation => "glossarysidebar",
+        PageType::SvgAttribute | PageType::SvgElement => "svgref",
+        PageType::MathmlAttribute | PageType::MathmlElement => "mathmlref",
+        PageType::WebassemblyFunction
+        | PageType::WebassemblyConstructor
+        | PageType::WebassemblyInterface
+        | PageType::WebassemblyInstanceProperty
+        | PageType::WebassemblyInstanceMethod
+        | PageType::WebassemblyStaticMethod
+        | PageType::WebassemblyStaticProperty
+        | PageType::WebassemblyInstruction => "webassemblysidebar",
+        PageType::XsltElement
+        | PageType::XpathFunction
+        | PageType::ExsltFunction
+        | PageType::XsltAxis => "xsltsidebar",
+        _ => return None,
+    })
+}
+
+/// The sidebar of Web API pages, see [`is_web_api`]. `APIRef` needs the API
+/// group as argument, so the page author has to replace the placeholder.
+const API_REF: &str = r#"{{APIRef("API group from GroupData.json")}}"#;
+
+/// Whether pages of this type get an [`API_REF`] sidebar. It is added to the
+/// body instead of the front matter, like existing pages do.
+fn is_web_api(page_type: PageType) -> bool {
+    matches!(
+        page_type,
+        PageType::WebApiInterface
+            | PageType::WebApiConstructor
+            | PageType::WebApiInstanceMethod
+            | PageType::WebApiInstanceProperty
+            | PageType::WebApiStaticMethod
+            | PageType::WebApiStaticProperty
+            | PageType::WebApiEvent
+    )
+}
+
+/// Whether pages of this type document a feature, and so get the
+/// specifications and browser compatibility sections.
+fn is_reference(page_type: PageType) -> bool {
+    !matches!(
+        page_type,
+        PageType::None
+            | PageType::Guide
+            | PageType::LandingPage
+            | PageType::ListingPage
+            | PageType::HowTo
+            | PageType::Tutorial
+            | PageType::TutorialChapter
+            | PageType::LearnTopic
+            | PageType::LearnModule
+            | PageType::LearnModuleChapter
+            | PageType::LearnModuleAssessment
+            | PageType::LearnFaq
+            | PageType::GlossaryDefinition
+            | PageType::GlossaryDisambiguation
+            | PageType::MdnCommunityGuide
+            | PageType::MdnWritingGuide
+    )
+}
+
+const SPECIFICATIONS: (&str, &str) = ("Specifications", "{{Specifications}}");
+const BROWSER_COMPAT: (&str, &str) = ("Browser compatibility", "{{Compat}}");
+const EXAMPLES: (&str, &str) = ("Examples", "");
+const SEE_ALSO: (&str, &str) = ("See also", "");
+
+/// The standard section headings (and prefilled bodies) for pages of this
+/// type. Only [reference](is_reference) pages get the specifications and
+/// browser compatibility sections.
+fn sections_for(page_type: PageType) -> Vec<(&'static str, &'static str)> {
+    let specific: &[(&str, &str)] = match page_type {
+        PageType::CssProperty | PageType::CssShorthandProperty => &[
+            ("Syntax", ""),
+            ("Values", ""),
+            ("Formal definition", "{{CSSInfo}}"),
+            ("Formal syntax", "{{CSSSyntax}}"),
+        ],
+        PageType::CssAtRule
+        | PageType::CssFunction
+        | PageType::CssType
+        | PageType::CssPseudoClass
+        | PageType::CssPseudoElement
+        | PageType::CssSelector
+        | PageType::CssAtRuleDescriptor
+        | PageType::CssMediaFeature => &[("Syntax", ""), ("Formal syntax", "{{CSSSyntax}}")],
+        PageType::HtmlElement => &[
+            ("Attributes", ""),
+            ("Accessibility", ""),
+            ("Technical summary", ""),
+        ],
+        PageType::WebApiInterface => &[
+            ("Constructor", ""),
+            ("Static properties", ""),
+            ("Instance properties", ""),
+            ("Static methods", ""),
+            ("Instance methods", ""),
+            ("Events", ""),
+        ],
+        PageType::WebApiConstructor
+        | PageType::WebApiInstanceMethod
+        | PageType::WebApiStaticMethod
+        | PageType::JavascriptConstructor
+        | PageType::JavascriptFunction
+        | PageType::JavascriptInstanceMethod
+        | PageType::JavascriptStaticMethod => &[
+            ("Syntax", ""),
+            ("Parameters", ""),
+            ("Return value", ""),
+            ("Exceptions", ""),
+        ],
+        PageType::WebApiInstanceProperty | PageType::WebApiStaticProperty => &[("Value", "")],
+        PageType::WebApiEvent => &[("Syntax", ""), ("Event type", ""), ("Event properties", "")],
+        PageType::JavascriptClass => &[
+            ("Constructor", ""),
+            ("Static properties", ""),
+            ("Static methods", ""),
+            ("Instance properties", ""),
+            ("Instance methods", ""),
+        ],
+        PageType::JavascriptStatement | PageType::JavascriptOperator => {
+            &[("Syntax", ""), ("Description", "")]
+        }
+        PageType::HttpHeader => &[("Syntax", ""), ("Directives", "")],
+        _ => &[],
+    };
+    let mut sections = specific.to_vec();
+    sections.push(EXAMPLES);
+    if is_reference(page_type) {
+        sections.extend([SPECIFICATIONS, BROWSER_COMPAT]);
+    }
+    sections.push(SEE_ALSO);
+    sections
+}
+
+fn validate_args(slug: &str) -> Result<(), ToolError> {
+    if slug.is_empty() {
+        return Err(ToolError::InvalidSlug(Cow::Borrowed(
+            "slug cannot be empty",
+        )));
+    }
+    if slug.contains('#') {
+        return Err(ToolError::InvalidSlug(Cow::Borrowed(
+            "slug cannot contain '#'",
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_render_css_property() {
+        let page = render_page(
+            "Web/CSS/Reference/Properties/foo",
+            PageType::CssProperty,
+            "foo",
+            Some("css.properties.foo"),
+        )
+        .unwrap();
+        assert_eq!(
+            page,
+            indoc!(
+                r#"
+                ---
+                title: foo
+                slug: Web/CSS/Reference/Properties/foo
+                page-type: css-property
+                browser-compat: css.properties.foo
+                sidebar: cssref
+                ---
+
+                <!-- Summary of foo. -->
+
+                ## Syntax
+
+                ## Values
+
+                ## Formal definition
+
+                {{CSSInfo}}
+
+                ## Formal syntax
+
+                {{CSSSyntax}}
+
+                ## Examples
+
+                ## Specifications
+
+                {{Specifications}}
+
+                ## Browser compatibility
+
+                {{Compat}}
+
+                ## See also
+                "#
+            )
+            .trim_start()
+        );
+    }
+
+    #[test]
+    fn test_render_without_template() {
+        let page = render_page("Web/Guide", PageType::Guide, "Guide", None).unwrap();
+        assert!(page.starts_with("---\ntitle: Guide\nslug: Web/Guide\npage-type: guide\n---\n"));
+        assert!(page.contains("## Examples\n\n## See also\n"));
+        assert!(!page.contains("{{Specifications}}"));
+        assert!(!page.contains("{{Compat}}"));
+    }
+
+    #[test]
+    fn test_render_web_api() {
+        let page = render_page(
+            "Web/API/Foo/bar",
+            PageType::WebApiInstanceMethod,
+            "Foo: bar() method",
+            None,
+        )
+        .unwrap();
+        let head = indoc!(
+            r#"
+            ---
+            title: "Foo: bar() method"
+            slug: Web/API/Foo/bar
+            page-type: web-api-instance-method
+            ---
+
+            {{APIRef("API group from GroupData.json")}}
+
+            <!-- Summary of Foo: bar() method. -->
+            "#
+        )
+        .trim_start();
+        assert!(page.starts_with(head));
+        assert!(page.contains("## Specifications\n\n{{Specifications}}\n"));
+    }
+}
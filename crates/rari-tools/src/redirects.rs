@@ -402,7 +402,7 @@ pub fn fix_redirects(locale_filter: Option<&[Locale]>) -> Result<(), ToolError>
 ///
 /// - `ToolError::InvalidLocale`: If a locale in a redirect path is invalid
 /// - `ToolError::RedirectError`: If redirect validation fails or cycles are detected
-fn fix_redirects_internal(
+pub(crate) fn fix_redirects_internal(
     pairs: &HashMap<impl AsRef<str>, impl AsRef<str>>,
 ) -> Result<HashMap<Locale, HashMap<String, String>>, ToolError> {
     let clean_pairs: HashMap<String, String> = short_cuts(pairs)?.into_iter().collect();
@@ -850,7 +850,7 @@ pub(crate) fn read_redirects_raw(
 ///
 /// * `Ok(())` if the file is written successfully.
 /// * `Err(String)` with an error message if writing fails.
-fn write_redirects(path: &Path, map: &HashMap<String, String>) -> Result<(), ToolError> {
+pub(crate) fn write_redirects(path: &Path, map: &HashMap<String, String>) -> Result<(), ToolError> {
     let file = File::create(path)?;
     let mut buffed = BufWriter::new(file);
 
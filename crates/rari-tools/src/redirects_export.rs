@@ -0,0 +1,284 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use rari_doc::percent::{decode_url_path, encode_url_path};
+use rari_types::locale::Locale;
+use serde_json::json;
+
+use crate::error::ToolError;
+use crate::redirects::fix_redirects_internal;
+use crate::utils::{csv_field, get_redirects_map};
+
+/// Target formats for [`export_redirects`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum RedirectsExportFormat {
+    /// `location ~* "^…$" { return 301 "…"; }` blocks for nginx.
+    /// Redirects to targets containing a `$` are skipped.
+    Nginx,
+    /// `RedirectMatch 301 "(?i)^…$" "…"` directives for Apache `mod_alias`.
+    Apache,
+    /// A Netlify `_redirects` file.
+    Netlify,
+    /// A CloudFront KeyValueStore import file (`{"data": [{"key", "value"}]}`).
+    CloudfrontJson,
+    /// `from,to` rows with a header line.
+    Csv,
+}
+
+/// Exports the redirects of the given locales (all locales if `None`) in a
+/// server config format, writing to `out` or stdout.
+///
+/// Redirect chains are collapsed first (like `fix-redirects` does), so every
+/// exported redirect points to its final target.
+///
+/// Internal target URLs are always percent-encoded, see
+/// [`rari_doc::percent::encode_url_path`]. Source URLs are encoded as well,
+/// except for nginx and Apache, which match against the decoded path.
+///
+/// The nginx and Apache rules are anchored, case-insensitive regexes: rari
+/// resolves redirects case-insensitively, and an exact match keeps pages below
+/// a redirected URL from being redirected along with it.
+pub fn export_redirects(
+    locale_filter: Option<&[Locale]>,
+    format: RedirectsExportFormat,
+    out: Option<&Path>,
+) -> Result<(), ToolError> {
+    let mut pairs = HashMap::new();
+    for locale in Locale::for_generic_and_spas() {
+        pairs.extend(get_redirects_map(*locale));
+    }
+    let locale_pairs = fix_redirects_internal(&pairs)?;
+    let pairs = locale_pairs
+        .into_iter()
+        .filter(|(locale, _)| locale_filter.is_none_or(|filter| filter.contains(locale)))
+        .flat_map(|(_, pairs)| pairs)
+        .collect::<BTreeMap<_, _>>();
+
+    let exported = render(&pairs, format);
+    match out {
+        Some(path) => {
+            let file = File::create(path)?;
+            let mut buffed = BufWriter::new(file);
+            buffed.write_all(exported.as_bytes())?;
+        }
+        None => std::io::stdout().write_all(exported.as_bytes())?,
+    }
+    tracing::info!("Exported {} redirects as {format:?}", pairs.len());
+    Ok(())
+}
+
+fn encode_target(to: &str) -> String {
+    if to.starts_with('/') {
+        encode_url_path(to)
+    } else {
+        to.to_string()
+    }
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Quotes an Apache directive argument. Apache only unescapes `\"` in quoted
+/// arguments, so other backslashes are kept as they are.
+fn quote_apache(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "\\\""))
+}
+
+/// Escapes `s` to match literally in a PCRE pattern.
+fn regex_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(
+            c,
+            '\\' | '.' | '+' | '*' | '?' | '(' | ')' | '|' | '[' | ']' | '{' | '}' | '^' | '$'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Escapes `$` and `&`, which refer to the match in `RedirectMatch` targets.
+fn escape_apache_target(s: &str) -> String {
+    s.replace('$', "\\$").replace('&', "\\&")
+}
+
+fn render(pairs: &BTreeMap<String, String>, format: RedirectsExportFormat) -> String {
+    let mut out = String::new();
+    match format {
+        RedirectsExportFormat::Nginx => {
+            for (from, to) in pairs {
+                // nginx expands variables in the `return` target and has no
+                // way to escape a literal `$`.
+                if to.contains('$') {
+                    tracing::warn!("Skipping redirect {from} -> {to}: nginx cannot return a '$'");
+                    continue;
+                }
+                out.push_str(&format!(
+                    "location ~* {} {{ return 301 {}; }}\n",
+                    quote(&format!("^{}$", regex_escape(&decode_url_path(from)))),
+                    quote(&encode_target(to))
+                ));
+            }
+        }
+        RedirectsExportFormat::Apache => {
+            for (from, to) in pairs {
+                out.push_str(&format!(
+                    "RedirectMatch 301 {} {}\n",
+                    quote_apache(&format!("(?i)^{}$", regex_escape(&decode_url_path(from)))),
+                    quote_apache(&escape_apache_target(&encode_target(to)))
+                ));
+            }
+        }
+        RedirectsExportFormat::Netlify => {
+            for (from, to) in pairs {
+                out.push_str(&format!(
+                    "{}\t{}\t301\n",
+                    encode_url_path(from),
+                    encode_target(to)
+                ));
+            }
+        }
+        RedirectsExportFormat::CloudfrontJson => {
+            let data = pairs
+                .iter()
+                .map(|(from, to)| json!({ "key": encode_url_path(from), "value": encode_target(to) }))
+                .collect::<Vec<_>>();
+            out = serde_json::to_string_pretty(&json!({ "data": data }))
+                .expect("serializing strings cannot fail");
+            out.push('\n');
+        }
+        RedirectsExportFormat::Csv => {
+            out.push_str("from,to\n");
+            for (from, to) in pairs {
+                out.push_str(&format!(
+                    "{},{}\n",
+                    csv_field(&encode_url_path(from)),
+                    csv_field(&encode_target(to))
+                ));
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use clap::ValueEnum;
+
+    use super::*;
+
+    fn pairs() -> BTreeMap<String, String> {
+        BTreeMap::from([
+            (
+                "/en-US/docs/Old page".to_string(),
+                "/en-US/docs/New_page#a b".to_string(),
+            ),
+            (
+                "/en-US/docs/Gone,really".to_string(),
+                "https://example.com/".to_string(),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_format_names() {
+        let names = RedirectsExportFormat::value_variants()
+            .iter()
+            .filter_map(|format| Some(format.to_possible_value()?.get_name().to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec!["nginx", "apache", "netlify", "cloudfront-json", "csv"]
+        );
+        assert!(RedirectsExportFormat::from_str("iis", false).is_err());
+    }
+
+    #[test]
+    fn test_render_nginx() {
+        assert_eq!(
+            render(&pairs(), RedirectsExportFormat::Nginx),
+            "location ~* \"^/en-US/docs/Gone,really$\" { return 301 \"https://example.com/\"; }\n\
+             location ~* \"^/en-US/docs/Old page$\" { return 301 \"/en-US/docs/New_page#a%20b\"; }\n"
+        );
+    }
+
+    #[test]
+    fn test_render_apache() {
+        assert_eq!(
+            render(&pairs(), RedirectsExportFormat::Apache),
+            "RedirectMatch 301 \"(?i)^/en-US/docs/Gone,really$\" \"https://example.com/\"\n\
+             RedirectMatch 301 \"(?i)^/en-US/docs/Old page$\" \"/en-US/docs/New_page#a%20b\"\n"
+        );
+    }
+
+    #[test]
+    fn test_render_regex_matches_exactly() {
+        let pairs = BTreeMap::from([(
+            "/en-US/docs/Web/API/Foo.bar_(baz)".to_string(),
+            "/en-US/docs/Web/API/Bar&Baz$1".to_string(),
+        )]);
+        assert_eq!(
+            render(&pairs, RedirectsExportFormat::Apache),
+            "RedirectMatch 301 \"(?i)^/en-US/docs/Web/API/Foo\\.bar_\\(baz\\)$\" \
+             \"/en-US/docs/Web/API/Bar\\&Baz\\$1\"\n"
+        );
+        // Targets with a `$` cannot be expressed for nginx.
+        assert_eq!(render(&pairs, RedirectsExportFormat::Nginx), "");
+        let pairs = BTreeMap::from([(
+            "/en-US/docs/Web/API/Foo.bar_(baz)".to_string(),
+            "/en-US/docs/Web/API/Bar&Baz".to_string(),
+        )]);
+        assert_eq!(
+            render(&pairs, RedirectsExportFormat::Nginx),
+            "location ~* \"^/en-US/docs/Web/API/Foo\\\\.bar_\\\\(baz\\\\)$\" \
+             { return 301 \"/en-US/docs/Web/API/Bar&Baz\"; }\n"
+        );
+
+        let re = regex::Regex::new(&format!(
+            "(?i)^{}$",
+            regex_escape("/en-US/docs/Web/API/Foo.bar_(baz)")
+        ))
+        .unwrap();
+        assert!(re.is_match("/en-US/docs/Web/API/Foo.bar_(baz)"));
+        assert!(re.is_match("/en-us/docs/web/api/foo.BAR_(baz)"));
+        assert!(!re.is_match("/en-US/docs/Web/API/Foo.bar_(baz)/child"));
+        assert!(!re.is_match("/en-US/docs/Web/API/Fooxbar_(baz)"));
+    }
+
+    #[test]
+    fn test_render_netlify() {
+        assert_eq!(
+            render(&pairs(), RedirectsExportFormat::Netlify),
+            "/en-US/docs/Gone,really\thttps://example.com/\t301\n\
+             /en-US/docs/Old%20page\t/en-US/docs/New_page#a%20b\t301\n"
+        );
+    }
+
+    #[test]
+    fn test_render_cloudfront_json() {
+        let rendered = render(&pairs(), RedirectsExportFormat::CloudfrontJson);
+        let value: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+        assert_eq!(
+            value,
+            json!({ "data": [
+                { "key": "/en-US/docs/Gone,really", "value": "https://example.com/" },
+                { "key": "/en-US/docs/Old%20page", "value": "/en-US/docs/New_page#a%20b" },
+            ]})
+        );
+    }
+
+    #[test]
+    fn test_render_csv() {
+        assert_eq!(
+            render(&pairs(), RedirectsExportFormat::Csv),
+            "from,to\n\
+             \"/en-US/docs/Gone,really\",https://example.com/\n\
+             /en-US/docs/Old%20page,/en-US/docs/New_page#a%20b\n"
+        );
+    }
+}
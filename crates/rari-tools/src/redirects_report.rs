@@ -0,0 +1,295 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+use dialoguer::Confirm;
+use dialoguer::theme::ColorfulTheme;
+use rari_types::locale::Locale;
+use rari_utils::io::read_to_string;
+use serde::Serialize;
+
+use crate::error::ToolError;
+use crate::inbound_links::link_index;
+use crate::redirects::{read_redirects_raw, redirects_path, write_redirects};
+use crate::utils::read_all_doc_pages;
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RedirectEntry {
+    pub from: String,
+    pub to: String,
+}
+
+impl From<&(String, String)> for RedirectEntry {
+    fn from((from, to): &(String, String)) -> Self {
+        Self {
+            from: from.clone(),
+            to: to.clone(),
+        }
+    }
+}
+
+/// Redirects that are candidates for clean-up, grouped by reason.
+#[derive(Serialize, Debug, Default)]
+pub struct RedirectsReport {
+    /// Redirects whose source is not linked from any doc page, either as a
+    /// Markdown link or through a link macro with a fixed base path. This is
+    /// informational only: other macros, sidebars, non-doc pages and external
+    /// sites may still link to these URLs.
+    pub unlinked: Vec<RedirectEntry>,
+    /// Redirects pointing to external URLs.
+    pub external: Vec<RedirectEntry>,
+    /// Redirects whose source only differs in case from another source in the
+    /// same file. Redirects are resolved case-insensitively, so only the last
+    /// one of these is ever used; all others are listed here.
+    pub case_duplicates: Vec<RedirectEntry>,
+    /// Redirects pointing into another locale.
+    pub cross_locale: Vec<RedirectEntry>,
+}
+
+/// Prints a JSON report of redirects that are likely dead weight for the
+/// given locales (all locales if `None`).
+///
+/// See [`RedirectsReport`] for the categories.
+pub fn report_redirects(locale_filter: Option<&[Locale]>) -> Result<(), ToolError> {
+    let redirects = read_locale_redirects(locale_filter)?;
+    let docs = read_all_doc_pages()?;
+    let linked = link_index(docs.values().collect::<Vec<_>>());
+    let report = build_report(&redirects, &linked);
+    tracing::info!(
+        "{} unlinked, {} external, {} case duplicates, {} cross-locale redirects",
+        report.unlinked.len(),
+        report.external.len(),
+        report.case_duplicates.len(),
+        report.cross_locale.len(),
+    );
+    let mut out = std::io::stdout();
+    serde_json::to_writer_pretty(&mut out, &report)?;
+    Ok(())
+}
+
+/// Removes shadowed case duplicates from the redirect files of the given
+/// locales (all locales if `None`).
+///
+/// Unlinked redirects are never pruned, because the link index does not cover
+/// every source of inbound links.
+///
+/// Redirects whose source matches an entry of the allow-list file are kept.
+/// The allow-list contains one URL per line (matched case-insensitively),
+/// entries ending in `*` match as prefix, and lines starting with `#` are
+/// ignored.
+pub fn prune_redirects(
+    locale_filter: Option<&[Locale]>,
+    allow_list: Option<&Path>,
+    assume_yes: bool,
+) -> Result<(), ToolError> {
+    let allow_list = match allow_list {
+        Some(path) => read_to_string(path)?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_lowercase)
+            .collect(),
+        None => vec![],
+    };
+    let redirects = read_locale_redirects(locale_filter)?;
+    let report = build_report(&redirects, &HashSet::new());
+
+    let to_prune = prunable(&report, &allow_list);
+    if to_prune.is_empty() {
+        tracing::info!("No redirects to prune");
+        return Ok(());
+    }
+    tracing::info!("This will remove {} redirects:", to_prune.len());
+    for entry in &to_prune {
+        tracing::info!("{}\t{}", entry.from, entry.to);
+    }
+
+    if assume_yes
+        || Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt("Proceed?")
+            .default(true)
+            .interact()
+            .unwrap_or_default()
+    {
+        for (locale, pairs) in &redirects {
+            let before = pairs.len();
+            let map = pairs
+                .iter()
+                .filter(|pair| !to_prune.contains(&RedirectEntry::from(*pair)))
+                .cloned()
+                .collect::<HashMap<_, _>>();
+            if map.len() != before {
+                tracing::info!(
+                    "Writing pruned redirects for locale: {} ({} removed)",
+                    locale,
+                    before - map.len()
+                );
+                write_redirects(&redirects_path(*locale)?, &map)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_locale_redirects(
+    locale_filter: Option<&[Locale]>,
+) -> Result<BTreeMap<Locale, Vec<(String, String)>>, ToolError> {
+    locale_filter
+        .unwrap_or(Locale::for_generic_and_spas())
+        .iter()
+        .filter_map(|locale| {
+            let path = match redirects_path(*locale) {
+                Ok(path) if path.exists() => path,
+                _ => {
+                    tracing::warn!("No redirects for locale {locale}, skipping");
+                    return None;
+                }
+            };
+            Some(read_redirects_raw(&path).map(|pairs| (*locale, pairs.into_iter().collect())))
+        })
+        .collect()
+}
+
+fn build_report(
+    redirects: &BTreeMap<Locale, Vec<(String, String)>>,
+    linked: &HashSet<String>,
+) -> RedirectsReport {
+    let mut report = RedirectsReport::default();
+    for (locale, pairs) in redirects {
+        // Later entries win when redirects are resolved, so walk backwards
+        // and report every source we have already seen.
+        let mut seen = HashSet::new();
+        for pair in pairs.iter().rev() {
+            if !seen.insert(pair.0.to_lowercase()) {
+                report.case_duplicates.push(pair.into());
+            }
+        }
+        for pair in pairs {
+            let (from, to) = pair;
+            if !linked.contains(&from.to_lowercase()) {
+                report.unlinked.push(pair.into());
+            }
+            if to.contains("://") {
+                report.external.push(pair.into());
+            } else if let Some(to_locale) = to.split('/').nth(1)
+                && !to_locale.eq_ignore_ascii_case(locale.as_url_str())
+            {
+                report.cross_locale.push(pair.into());
+            }
+        }
+    }
+    report.case_duplicates.sort();
+    report
+}
+
+fn prunable(report: &RedirectsReport, allow_list: &[String]) -> HashSet<RedirectEntry> {
+    let allowed = |from: &str| {
+        let from = from.to_lowercase();
+        allow_list
+            .iter()
+            .any(|allowed| match allowed.strip_suffix('*') {
+                Some(prefix) => from.starts_with(prefix),
+                None => from == *allowed,
+            })
+    };
+    report
+        .case_duplicates
+        .iter()
+        .filter(|entry| !allowed(&entry.from))
+        .cloned()
+        .collect()
+}
+
+// These tests use file system fixtures for the redirect files.
+// The file system is a shared resource, so we force tests to be run serially,
+// to avoid concurrent fixture management issues.
+#[cfg(test)]
+use serial_test::file_serial;
+#[cfg(test)]
+#[file_serial(file_fixtures)]
+mod test {
+    use super::*;
+    use crate::tests::fixtures::redirects::RedirectFixtures;
+
+    fn pair(from: &str, to: &str) -> (String, String) {
+        (from.to_string(), to.to_string())
+    }
+
+    fn entry(from: &str, to: &str) -> RedirectEntry {
+        RedirectEntry::from(&pair(from, to))
+    }
+
+    fn redirects() -> BTreeMap<Locale, Vec<(String, String)>> {
+        BTreeMap::from([
+            (
+                Locale::EnUs,
+                vec![
+                    pair("/en-US/docs/Linked", "/en-US/docs/Target"),
+                    pair("/en-US/docs/Old", "/en-US/docs/Target"),
+                    pair("/en-US/docs/old", "/en-US/docs/Other"),
+                    pair("/en-US/docs/External", "https://example.com/"),
+                ],
+            ),
+            (
+                Locale::De,
+                vec![pair("/de/docs/Linked", "/en-US/docs/Target")],
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_build_report() {
+        let linked = HashSet::from([
+            "/en-us/docs/linked".to_string(),
+            "/de/docs/linked".to_string(),
+        ]);
+        let report = build_report(&redirects(), &linked);
+        assert_eq!(
+            report.unlinked,
+            vec![
+                entry("/en-US/docs/Old", "/en-US/docs/Target"),
+                entry("/en-US/docs/old", "/en-US/docs/Other"),
+                entry("/en-US/docs/External", "https://example.com/"),
+            ]
+        );
+        assert_eq!(
+            report.external,
+            vec![entry("/en-US/docs/External", "https://example.com/")]
+        );
+        assert_eq!(
+            report.case_duplicates,
+            vec![entry("/en-US/docs/Old", "/en-US/docs/Target")]
+        );
+        assert_eq!(
+            report.cross_locale,
+            vec![entry("/de/docs/Linked", "/en-US/docs/Target")]
+        );
+    }
+
+    #[test]
+    fn test_prunable() {
+        let report = build_report(&redirects(), &HashSet::new());
+        // Unlinked redirects are reported, but never pruned.
+        assert_eq!(report.unlinked.len(), 5);
+        assert_eq!(
+            prunable(&report, &[]),
+            HashSet::from([entry("/en-US/docs/Old", "/en-US/docs/Target")])
+        );
+        assert!(prunable(&report, &["/en-us/docs/*".to_string()]).is_empty());
+    }
+
+    #[test]
+    fn test_read_locale_redirects_skips_missing_files() {
+        let _redirects = RedirectFixtures::new(
+            &[("docs/Old".to_string(), "docs/New".to_string())],
+            Locale::EnUs,
+        );
+        let redirects = read_locale_redirects(Some(&[Locale::EnUs, Locale::De])).unwrap();
+        assert_eq!(
+            redirects,
+            BTreeMap::from([(
+                Locale::EnUs,
+                vec![pair("/en-US/docs/Old", "/en-US/docs/New")]
+            )])
+        );
+    }
+}
@@ -17,6 +17,7 @@ use rayon::iter::{IntoParallelIterator, ParallelIterator, once};
 
 use crate::error::ToolError;
 use crate::git::exec_git_with_test_fallback;
+use crate::inbound_links::rewrite_inbound_links;
 use crate::redirects::{add_redirects, remove_redirects_by_targets};
 use crate::sidebars::update_sidebars;
 use crate::wikihistory::delete_from_wiki_history;
@@ -27,6 +28,7 @@ pub fn remove(
     recursive: bool,
     redirect: Option<&str>,
     assume_yes: bool,
+    rewrite_links: bool,
 ) -> Result<(), ToolError> {
     validate_args(slug)?;
     let locale = locale.unwrap_or_default();
@@ -74,6 +76,17 @@ pub fn remove(
             tracing::info!("{}", &url);
         }
 
+        // Point inbound links at the redirect target instead of the
+        // deleted documents.
+        if rewrite_links && let Some(target) = redirect_target(redirect, locale)? {
+            let pairs = removed_urls
+                .iter()
+                .map(|url| (url.clone(), target.clone()))
+                .collect::<Vec<_>>();
+            let rewritten = rewrite_inbound_links(&pairs)?;
+            tracing::info!("Rewrote links in {} documents", rewritten.len());
+        }
+
         // Find references to deleted documents and
         // list them for manual review
         tracing::info!("Checking references to deleted documents...");
@@ -119,23 +132,7 @@ fn do_remove(
     let doc = Doc::page_from_slug(slug, locale, false)?;
     let real_slug = doc.slug();
 
-    // If we get a redirect value passed in, it is either a slug or a complete url.
-    // If it is a slug, check if it actually exists, otherwise bail.
-    let redirect_target = if let Some(redirect_str) = redirect {
-        if !redirect_str.starts_with("http") {
-            let redirect_url = build_url(redirect_str, locale, PageCategory::Doc)?;
-            if !page::Page::exists(&redirect_url) {
-                return Err(ToolError::InvalidRedirectToURL(format!(
-                    "redirect slug does not exist: {redirect_url}"
-                )));
-            }
-            Some(redirect_url)
-        } else {
-            Some(redirect_str.to_owned())
-        }
-    } else {
-        None
-    };
+    let redirect_target = redirect_target(redirect, locale)?;
 
     let subpages = get_sub_pages(doc.url(), None, Default::default())?;
     if !recursive && !subpages.is_empty() && redirect.is_some() {
@@ -226,6 +223,25 @@ fn do_remove(
     Ok(slugs_to_remove)
 }
 
+/// If we get a redirect value passed in, it is either a slug or a complete url.
+/// If it is a slug, check if it actually exists, otherwise bail.
+fn redirect_target(redirect: Option<&str>, locale: Locale) -> Result<Option<String>, ToolError> {
+    let Some(redirect_str) = redirect else {
+        return Ok(None);
+    };
+    if !redirect_str.starts_with("http") {
+        let redirect_url = build_url(redirect_str, locale, PageCategory::Doc)?;
+        if !page::Page::exists(&redirect_url) {
+            return Err(ToolError::InvalidRedirectToURL(format!(
+                "redirect slug does not exist: {redirect_url}"
+            )));
+        }
+        Ok(Some(redirect_url))
+    } else {
+        Ok(Some(redirect_str.to_owned()))
+    }
+}
+
 fn validate_args(slug: &str) -> Result<(), ToolError> {
     if slug.is_empty() {
         return Err(ToolError::InvalidSlug(Cow::Borrowed(
@@ -1,19 +1,23 @@
 use std::borrow::Cow;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use const_format::concatcp;
 use pretty_yaml::config::{FormatOptions, LanguageOptions};
 use rari_doc::html::sidebar::{
-    BasicEntry, CoreEntry, Sidebar, SidebarEntry, SubPageEntry, SubPageGroupedEntry, WebExtApiEntry,
+    BasicEntry, CoreEntry, MetaChildren, MetaSidebar, Sidebar, SidebarEntry, SidebarMetaEntry,
+    SidebarMetaEntryContent, SubPageEntry, SubPageGroupedEntry, WebExtApiEntry,
 };
+use rari_doc::issues::{DIssue, DisplayIssue, IssueType};
+use rari_doc::pages::page::Page;
 use rari_doc::redirects::resolve_redirect;
 use rari_types::globals::content_root;
 use rari_types::locale::{Locale, default_locale};
 use rari_utils::concat_strs;
 
 use crate::error::ToolError;
+use crate::fix::issues::find_non_prefix_match;
 use crate::redirects::{read_redirects_raw, redirects_path};
 
 const PREFIX: &str = "# Do not add comments to this file. They will be lost.\n\n";
@@ -167,6 +171,294 @@ pub fn fmt_sidebars() -> Result<(), ToolError> {
     Ok(())
 }
 
+/// Validates all sidebars and prints the issues found as JSON, keyed by
+/// sidebar file.
+///
+/// Reports links to missing pages, links that are redirects, l10n keys
+/// that are missing for a locale, unused l10n keys, and duplicate entries
+/// within the same list.
+pub fn validate_sidebars() -> Result<(), ToolError> {
+    let mut all = BTreeMap::new();
+    for (path, sidebar) in read_sidebars()? {
+        let raw = fs::read_to_string(&path)?;
+        let issues = validate_sidebar(&raw, sidebar, &path.to_string_lossy())?;
+        if !issues.is_empty() {
+            all.insert(path.to_string_lossy().into_owned(), issues);
+        }
+    }
+    let count = all.values().map(Vec::len).sum::<usize>();
+    if count == 0 {
+        tracing::info!("No issues found in sidebars");
+        return Ok(());
+    }
+    let mut out = std::io::stdout();
+    serde_json::to_writer_pretty(&mut out, &all)?;
+    Err(ToolError::InvalidSidebars(count))
+}
+
+/// Collects issues with their byte offset into the raw sidebar file, so they
+/// can be reported with line and column.
+struct SidebarIssues<'a> {
+    raw: &'a str,
+    filepath: &'a str,
+    /// Next search offset per needle, so repeated links map to their own
+    /// occurrence in the file.
+    cursors: HashMap<String, usize>,
+    issues: Vec<DIssue>,
+}
+
+impl<'a> SidebarIssues<'a> {
+    fn new(raw: &'a str, filepath: &'a str) -> Self {
+        Self {
+            raw,
+            filepath,
+            cursors: HashMap::new(),
+            issues: vec![],
+        }
+    }
+
+    /// Finds the next occurrence of `needle` at or after `min_offset`.
+    fn locate(&mut self, needle: &str, min_offset: usize) -> Option<usize> {
+        let cursor = self.cursors.entry(needle.to_string()).or_default();
+        let from = (*cursor).max(min_offset);
+        let offset = find_non_prefix_match(self.raw, from, needle)?;
+        *cursor = offset + needle.len();
+        Some(offset)
+    }
+
+    fn push(
+        &mut self,
+        offset: Option<usize>,
+        needle: &str,
+        name: IssueType,
+        explanation: String,
+        suggestion: Option<String>,
+        href: Option<String>,
+    ) {
+        let (line, column) = offset
+            .map(|offset| {
+                let line_start = self.raw[..offset].rfind('\n').map(|i| i + 1).unwrap_or(0);
+                let line = self.raw[..offset].matches('\n').count() as i64 + 1;
+                let column = self.raw[line_start..offset].chars().count() as i64 + 1;
+                (Some(line), Some(column))
+            })
+            .unwrap_or_default();
+        let source_context = offset.zip(column).map(|(offset, column)| {
+            let line_start = self.raw[..offset].rfind('\n').map(|i| i + 1).unwrap_or(0);
+            let line_end = self.raw[offset..]
+                .find('\n')
+                .map(|i| i + offset)
+                .unwrap_or(self.raw.len());
+            format!(
+                "{}\n{}^\n",
+                &self.raw[line_start..line_end],
+                "-".repeat(column as usize - 1)
+            )
+        });
+        let display_issue = DisplayIssue {
+            id: self.issues.len() as i64,
+            explanation: Some(explanation),
+            fixable: Some(suggestion.is_some()),
+            suggestion,
+            fixed: false,
+            line,
+            column,
+            end_line: line,
+            end_column: column.map(|column| column + needle.chars().count() as i64 - 1),
+            source_context,
+            filepath: Some(self.filepath.to_string()),
+            name,
+        };
+        self.issues.push(match href {
+            Some(href) => DIssue::BrokenLink {
+                display_issue,
+                href: Some(href),
+            },
+            None => DIssue::Unknown { display_issue },
+        });
+    }
+
+    fn check_link(&mut self, link: &str) {
+        let offset = self.locate(link, 0);
+        let Some(slug) = link.strip_prefix('/') else {
+            return;
+        };
+        let url = if link.starts_with(EN_US_DOCS_PREFIX) {
+            Cow::Borrowed(link)
+        } else {
+            Cow::Owned(concat_strs!(EN_US_DOCS_PREFIX, "/", slug))
+        };
+        let bare_url = url.split_once('#').map(|(url, _)| url).unwrap_or(&url);
+        // This also catches links that only differ in case from the page.
+        if let Some(redirect) = resolve_redirect(bare_url) {
+            let suggestion = if link.starts_with(EN_US_DOCS_PREFIX) {
+                redirect.to_string()
+            } else {
+                redirect
+                    .strip_prefix(EN_US_DOCS_PREFIX)
+                    .unwrap_or(&redirect)
+                    .to_string()
+            };
+            self.push(
+                offset,
+                link,
+                IssueType::RedirectedLink,
+                format!("Link {link} is a redirect"),
+                Some(suggestion),
+                Some(link.to_string()),
+            );
+        } else if !Page::exists(bare_url) {
+            self.push(
+                offset,
+                link,
+                IssueType::BrokenLink,
+                format!("Link {link} doesn't resolve"),
+                None,
+                Some(link.to_string()),
+            );
+        }
+    }
+
+    fn check_entries(&mut self, entries: &[SidebarMetaEntry]) {
+        let mut seen = HashSet::new();
+        for entry in entries {
+            let link = match &entry.content {
+                SidebarMetaEntryContent::Link {
+                    link: Some(link), ..
+                }
+                | SidebarMetaEntryContent::LinkWithHash { link, .. } => Some(link.as_str()),
+                _ => None,
+            };
+            if let Some(link) = link {
+                // Locate before checking, so the duplicate is reported at
+                // its own occurrence.
+                let is_duplicate = !seen.insert(link.to_lowercase());
+                if is_duplicate {
+                    let offset = self.locate(link, 0);
+                    self.push(
+                        offset,
+                        link,
+                        IssueType::SidebarDuplicateEntry,
+                        format!("Duplicate entry for {link}"),
+                        None,
+                        None,
+                    );
+                } else {
+                    self.check_link(link);
+                }
+            }
+            match &entry.children {
+                MetaChildren::Children(children) => self.check_entries(children),
+                MetaChildren::ListSubPages { path, .. }
+                | MetaChildren::ListSubPagesGrouped { path, .. } => self.check_link(path),
+                _ => {}
+            }
+        }
+    }
+
+    /// Reports unused l10n keys, used keys that en-US doesn't define (they
+    /// render as the raw key), and used keys missing for a translated locale.
+    ///
+    /// Translated locales are those listed in the `l10n` map and all locales
+    /// with translated content.
+    fn check_l10n(&mut self, sidebar: &Sidebar) {
+        let used = sidebar
+            .sidebar
+            .iter()
+            .flat_map(traverse_and_extract_l10nable)
+            .collect::<BTreeSet<_>>();
+        let l10n_offset = self.raw.find("\nl10n:").map(|i| i + 1);
+        let en_us = sidebar.l10n.l10n.get(&Locale::default());
+        for (locale, map) in &sidebar.l10n.l10n {
+            let locale_offset = self
+                .locate(
+                    &concat_strs!(locale.as_url_str(), ":"),
+                    l10n_offset.unwrap_or_default(),
+                )
+                .or(l10n_offset);
+            for key in map.keys() {
+                if !used.contains(key.as_str()) {
+                    let offset = self.locate(key, locale_offset.unwrap_or_default());
+                    self.push(
+                        offset,
+                        key,
+                        IssueType::SidebarUnusedL10n,
+                        format!("Unused l10n key {key} for {locale}"),
+                        None,
+                        None,
+                    );
+                }
+            }
+        }
+        for key in &used {
+            if !en_us.is_some_and(|map| map.contains_key(*key)) {
+                let offset = self.locate(key, 0);
+                self.push(
+                    offset,
+                    key,
+                    IssueType::SidebarMissingL10n,
+                    format!("Missing l10n key {key} for {}", Locale::default()),
+                    None,
+                    None,
+                );
+            }
+        }
+        let Some(en_us) = en_us else {
+            return;
+        };
+        let locales = sidebar
+            .l10n
+            .l10n
+            .keys()
+            .chain(Locale::for_generic_and_spas())
+            .filter(|locale| **locale != Locale::default())
+            .collect::<BTreeSet<_>>();
+        for locale in locales {
+            let map = sidebar.l10n.l10n.get(locale);
+            let locale_offset = l10n_offset
+                .and_then(|l10n_offset| {
+                    find_non_prefix_match(
+                        self.raw,
+                        l10n_offset,
+                        &concat_strs!(locale.as_url_str(), ":"),
+                    )
+                })
+                .or(l10n_offset);
+            for key in en_us.keys() {
+                if used.contains(key.as_str()) && !map.is_some_and(|map| map.contains_key(key)) {
+                    self.push(
+                        locale_offset,
+                        locale.as_url_str(),
+                        IssueType::SidebarMissingL10n,
+                        format!("Missing l10n key {key} for {locale}"),
+                        None,
+                        None,
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn validate_sidebar(raw: &str, sidebar: Sidebar, filepath: &str) -> Result<Vec<DIssue>, ToolError> {
+    let mut issues = SidebarIssues::new(raw, filepath);
+    issues.check_l10n(&sidebar);
+    // Links are located in document order, the l10n section comes last.
+    issues.cursors.clear();
+    let meta = MetaSidebar::try_from(sidebar)?;
+    issues.check_entries(&meta.entries);
+    let mut issues = issues.issues;
+    issues.sort_by_key(|issue| issue.display_issue().line);
+    for (id, issue) in issues.iter_mut().enumerate() {
+        match issue {
+            DIssue::BrokenLink { display_issue, .. }
+            | DIssue::Macros { display_issue, .. }
+            | DIssue::Unknown { display_issue } => display_issue.id = id as i64,
+        }
+    }
+    Ok(issues)
+}
+
 pub(crate) fn update_sidebars(pairs: Pairs<'_>) -> Result<(), ToolError> {
     let sidebars = read_sidebars()?;
 
@@ -212,6 +504,72 @@ pub(crate) fn update_sidebars(pairs: Pairs<'_>) -> Result<(), ToolError> {
     Ok(())
 }
 
+/// Inserts a link to `slug` into every sidebar that lists one of its
+/// siblings, keeping the siblings sorted. Returns the paths of the updated
+/// sidebars.
+pub(crate) fn insert_into_sidebars(slug: &str) -> Result<Vec<PathBuf>, ToolError> {
+    let link = concat_strs!("/", slug.trim_start_matches('/'));
+    let Some((parent, _)) = link.rsplit_once('/') else {
+        return Ok(vec![]);
+    };
+    let mut updated = vec![];
+    for (path, mut parsed_sidebar) in read_sidebars()? {
+        if insert_sibling(&mut parsed_sidebar.sidebar, parent, &link) {
+            write_sidebar(&parsed_sidebar, &path)?;
+            updated.push(path);
+        }
+    }
+    Ok(updated)
+}
+
+fn entry_link(entry: &SidebarEntry) -> Option<&str> {
+    let link = match entry {
+        SidebarEntry::Link(link) => Some(link.as_str()),
+        SidebarEntry::Section(basic_entry) | SidebarEntry::Default(basic_entry) => {
+            basic_entry.core.link.as_deref()
+        }
+        _ => None,
+    }?;
+    Some(link.strip_prefix(EN_US_DOCS_PREFIX).unwrap_or(link))
+}
+
+/// Inserts `link` into the first list that contains a link with the same
+/// `parent`, checking each level before recursing into its children. Does
+/// nothing if `link` is already listed there.
+fn insert_sibling(entries: &mut Vec<SidebarEntry>, parent: &str, link: &str) -> bool {
+    let is_sibling = |entry: &SidebarEntry| {
+        entry_link(entry)
+            .and_then(|other| other.rsplit_once('/'))
+            .is_some_and(|(other_parent, _)| other_parent.eq_ignore_ascii_case(parent))
+    };
+    if entries.iter().any(is_sibling) {
+        if entries
+            .iter()
+            .any(|entry| entry_link(entry).is_some_and(|other| other.eq_ignore_ascii_case(link)))
+        {
+            return false;
+        }
+        let position = entries
+            .iter()
+            .rposition(|entry| {
+                is_sibling(entry)
+                    && entry_link(entry)
+                        .is_some_and(|other| other.to_lowercase() < link.to_lowercase())
+            })
+            .map(|i| i + 1)
+            .or_else(|| entries.iter().position(is_sibling))
+            .unwrap_or_default();
+        entries.insert(position, SidebarEntry::Link(link.to_string()));
+        return true;
+    }
+    entries.iter_mut().any(|entry| match entry {
+        SidebarEntry::Section(basic_entry) | SidebarEntry::Default(basic_entry) => {
+            insert_sibling(&mut basic_entry.children, parent, link)
+        }
+        _ => false,
+    })
+}
+
 fn write_sidebar(sidebar: &Sidebar, path: &Path) -> Result<(), ToolError> {
     let y = serde_yaml_ng::to_string(sidebar)?;
     // Format yaml a bit prettier than serde does
@@ -231,7 +589,7 @@ fn write_sidebar(sidebar: &Sidebar, path: &Path) -> Result<(), ToolError> {
     Ok(())
 }
 
-fn read_sidebars() -> Result<Vec<(std::path::PathBuf, Sidebar)>, ToolError> {
+fn read_sidebars() -> Result<Vec<(PathBuf, Sidebar)>, ToolError> {
     // read all sidebars
     let mut path = content_root().to_path_buf();
     path.push("sidebars");
@@ -413,9 +771,12 @@ use serial_test::file_serial;
 #[file_serial(file_fixtures)]
 mod test {
 
+    use std::iter;
+
     use indoc::indoc;
 
     use super::*;
+    use crate::tests::fixtures::docs::DocFixtures;
     use crate::tests::fixtures::sidebars::SidebarFixtures;
 
     #[test]
@@ -560,4 +921,121 @@ mod test {
         let seventh_entry = &sb.sidebar.get(6);
         assert!(seventh_entry.is_none());
     }
+
+    #[test]
+    fn test_validate_sidebar() {
+        let slugs = vec![
+            "Web/CSS".to_string(),
+            "Web/CSS/Exists".to_string(),
+            "Web/CSS/Other".to_string(),
+        ];
+        let _docs = DocFixtures::new(&slugs, Locale::EnUs);
+        let sb = indoc!(
+            r#"
+            # Do not add comments to this file. They will be lost.
+
+            sidebar:
+              - type: section
+                link: /Web/CSS
+                title: CSS
+              - details: closed
+                title: Stuff
+                children:
+                  - /Web/CSS/Exists
+                  - /Web/CSS/other
+                  - /Web/CSS/Missing
+                  - /Web/CSS/Exists
+
+            l10n:
+              en-US:
+                Stuff: Stuff
+                Unused: Unused
+              de:
+                Unused: Unbenutzt
+            "#
+        );
+        let sidebar = serde_yaml_ng::from_str::<Sidebar>(sb).unwrap();
+        let issues = validate_sidebar(sb, sidebar, "sidebar_0.yaml").unwrap();
+        let found = issues
+            .iter()
+            .map(|issue| {
+                let display_issue = issue.display_issue();
+                (display_issue.name, display_issue.line.unwrap())
+            })
+            .collect::<Vec<_>>();
+        // Locales with translated content but no l10n section are reported
+        // at the `l10n:` line.
+        let translated = Locale::for_generic_and_spas().len() - 1;
+        let mut expected = vec![
+            (IssueType::SidebarMissingL10n, 5),
+            (IssueType::RedirectedLink, 11),
+            (IssueType::BrokenLink, 12),
+            (IssueType::SidebarDuplicateEntry, 13),
+        ];
+        expected.extend(iter::repeat_n(
+            (IssueType::SidebarMissingL10n, 15),
+            translated,
+        ));
+        expected.extend([
+            (IssueType::SidebarUnusedL10n, 18),
+            (IssueType::SidebarMissingL10n, 19),
+            (IssueType::SidebarUnusedL10n, 20),
+        ]);
+        assert_eq!(found, expected);
+        assert_eq!(
+            issues[0].display_issue().explanation.as_deref(),
+            Some("Missing l10n key CSS for en-US")
+        );
+        assert_eq!(
+            issues[1].display_issue().suggestion.as_deref(),
+            Some("/Web/CSS/Other")
+        );
+        assert_eq!(issues[1].display_issue().column, Some(9));
+    }
+
+    #[test]
+    fn test_insert_into_sidebars() {
+        let sb = indoc!(
+            r#"
+            # Do not add comments to this file. They will be lost.
+
+            sidebar:
+              - type: section
+                link: /Web/CSS
+                title: CSS
+              - title: Properties
+                details: closed
+                children:
+                  - /Web/CSS/Reference/Properties/align-content
+                  - link: /Web/CSS/Reference/Properties/color
+                    title: color
+                  - /Web/CSS/Reference/Properties/display
+            "#
+        );
+        let _sidebars = SidebarFixtures::new(vec![sb]);
+        let updated = insert_into_sidebars("Web/CSS/Reference/Properties/caret").unwrap();
+        assert_eq!(updated.len(), 1);
+        // Inserting twice is a no-op.
+        let updated = insert_into_sidebars("Web/CSS/Reference/Properties/caret").unwrap();
+        assert!(updated.is_empty());
+        let updated = insert_into_sidebars("Web/HTML/Reference/Elements/a").unwrap();
+        assert!(updated.is_empty());
+
+        let mut path = content_root().to_path_buf();
+        path.push("sidebars");
+        path.push("sidebar_0.yaml");
+        let sb = serde_yaml_ng::from_str::<Sidebar>(&fs::read_to_string(&path).unwrap()).unwrap();
+        let SidebarEntry::Default(BasicEntry { children, .. }) = &sb.sidebar[1] else {
+            panic!("Expected an entry with children");
+        };
+        assert_eq!(
+            children.iter().filter_map(entry_link).collect::<Vec<_>>(),
+            vec![
+                "/Web/CSS/Reference/Properties/align-content",
+                "/Web/CSS/Reference/Properties/caret",
+                "/Web/CSS/Reference/Properties/color",
+                "/Web/CSS/Reference/Properties/display",
+            ]
+        );
+    }
 }
@@ -148,7 +148,7 @@ impl DocFixtures {
         let folder_path = Self::path_from_slug(slug, locale);
         let abs_folder_path = locale_root.join(&folder_path);
 
-        let title = Self::capitalize(slug.split('/').last().unwrap());
+        let title = Self::capitalize(slug.split('/').next_back().unwrap());
         let full_content = formatdoc! {
             r#"---
             title: {}
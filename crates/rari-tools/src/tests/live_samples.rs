@@ -0,0 +1,68 @@
+use indoc::indoc;
+use rari_doc::issues::{IN_MEMORY, IssueType};
+use rari_doc::pages::page::Page;
+use rari_types::locale::Locale;
+use serial_test::file_serial;
+use tracing_subscriber::layer::SubscriberExt;
+
+use super::fixtures::docs::DocFixtures;
+use crate::fix::issues::get_issues;
+
+/// Tests that missing, unused and duplicate live samples are reported with
+/// the position of the macro or code block.
+#[test]
+#[file_serial(file_fixtures)]
+fn test_live_sample_issues() {
+    let subscriber = tracing_subscriber::registry().with(IN_MEMORY.clone());
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let slug = "Test/Live-Samples";
+    let _docs = DocFixtures::new(&[slug.to_string()], Locale::EnUs);
+    DocFixtures::create_doc_with_content(
+        slug,
+        Locale::EnUs,
+        indoc!(
+            r#"
+            ## Working
+
+            ```html
+            <p>Hello</p>
+            ```
+
+            ```css live-sample___unused
+            p { color: red; }
+            ```
+
+            {{EmbedLiveSample("Working")}}
+
+            {{EmbedLiveSample("Working")}}
+
+            ## Empty
+
+            {{EmbedLiveSample("Nothing here")}}
+            "#
+        ),
+    );
+
+    let page = Page::from_url(&format!("/en-US/docs/{slug}")).unwrap();
+    let issues = get_issues(&page).unwrap();
+    let found = issues
+        .iter()
+        .map(|issue| {
+            let display_issue = issue.display_issue();
+            (
+                display_issue.name,
+                display_issue.line.unwrap(),
+                display_issue.column.unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        vec![
+            (IssueType::LiveSampleUnusedCode, 12, 8),
+            (IssueType::LiveSampleDuplicateId, 18, 1),
+            (IssueType::LiveSampleMissingCode, 22, 1),
+        ]
+    );
+}
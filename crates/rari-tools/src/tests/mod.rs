@@ -1,3 +1,5 @@
 mod blog_fallback;
 pub mod fixtures;
 mod image_fallback;
+mod live_samples;
+mod templ_policies;
@@ -0,0 +1,50 @@
+use indoc::indoc;
+use rari_doc::issues::{DIssue, IN_MEMORY, IssueType};
+use rari_doc::pages::page::{Page, PageLike};
+use rari_types::locale::Locale;
+use serial_test::file_serial;
+use tracing_subscriber::layer::SubscriberExt;
+
+use super::fixtures::docs::DocFixtures;
+use crate::fix::issues::{apply_suggestions, collect_suggestions, get_fixable_issues};
+
+/// Tests that a deprecated templ is reported as fixable and that the fix
+/// removes it.
+#[test]
+#[file_serial(file_fixtures)]
+fn test_deprecated_templ_issue() {
+    let subscriber = tracing_subscriber::registry().with(IN_MEMORY.clone());
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let slug = "Test/Deprecated-Templ";
+    let _docs = DocFixtures::new(&[slug.to_string()], Locale::EnUs);
+    DocFixtures::create_doc_with_content(
+        slug,
+        Locale::EnUs,
+        indoc!(
+            r#"
+            See the {{XULElem("button")}} element.
+            "#
+        ),
+    );
+
+    let page = Page::from_url(&format!("/en-US/docs/{slug}")).unwrap();
+    let issues = get_fixable_issues(&page).unwrap();
+    assert_eq!(issues.len(), 1);
+    let DIssue::Macros {
+        display_issue,
+        macro_name,
+        href,
+    } = &issues[0]
+    else {
+        panic!("expected a macro issue");
+    };
+    assert_eq!(display_issue.name, IssueType::TemplDeprecated);
+    assert_eq!(macro_name.as_deref(), Some("xulelem"));
+    assert_eq!(href.as_deref(), Some(r#"{{XULElem("button")}}"#));
+    assert_eq!(display_issue.suggestion.as_deref(), Some(""));
+
+    let raw = page.raw_content();
+    let fixed = apply_suggestions(raw, &collect_suggestions(raw, &issues)).unwrap();
+    assert!(fixed.contains("See the  element."));
+}
@@ -12,6 +12,15 @@ use tracing::warn;
 use crate::error::ToolError;
 use crate::redirects::{self, redirects_path};
 
+/// Quotes `s` as a CSV field if it contains a separator, quote or newline.
+pub(crate) fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
 pub(crate) fn parent_slug(slug: &str) -> Result<&str, ToolError> {
     let slug = slug.trim_end_matches('/');
     if let Some(i) = slug.rfind('/') {
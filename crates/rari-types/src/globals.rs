@@ -7,7 +7,7 @@ use serde::Deserialize;
 
 use crate::error::EnvError;
 use crate::locale::Locale;
-use crate::settings::{Deps, Settings};
+use crate::settings::{Deps, DepsSources, Settings};
 use crate::{HistoryEntry, Popularities, globals};
 
 #[inline(always)]
@@ -77,6 +77,18 @@ pub fn settings() -> &'static Settings {
     SETTINGS.get_or_init(|| Settings::new().expect("error generating settings"))
 }
 
+static DEPS_SOURCES: OnceLock<DepsSources> = OnceLock::new();
+
+/// The dependency sources of the [`settings`], or read from the config if
+/// the settings are not loaded, as the dependencies are updated before that.
+pub fn deps_sources() -> &'static DepsSources {
+    match SETTINGS.get() {
+        Some(settings) => &settings.deps_sources,
+        None => DEPS_SOURCES
+            .get_or_init(|| DepsSources::new().expect("error reading dependency sources")),
+    }
+}
+
 pub static DEPS: OnceLock<Deps> = OnceLock::new();
 pub fn deps() -> &'static Deps {
     DEPS.get_or_init(Deps::new)
@@ -163,6 +175,32 @@ pub fn git_history() -> &'static HashMap<PathBuf, HistoryEntry> {
     &GIT_HISTORY
 }
 
+/// Git history of the blog, curriculum, generic content and contributor
+/// spotlight roots, keyed by full path.
+pub static OTHER_GIT_HISTORY: LazyLock<HashMap<PathBuf, HistoryEntry>> = LazyLock::new(|| {
+    [
+        blog_root(),
+        curriculum_root(),
+        generic_content_root(),
+        contributor_spotlight_root(),
+    ]
+    .into_iter()
+    .flatten()
+    .flat_map(|root| {
+        let history: HashMap<PathBuf, HistoryEntry> =
+            fs::read_to_string(root.join("_git_history.json"))
+                .map(|json_str| serde_json::from_str(&json_str).expect("unable to parse l10n json"))
+                .unwrap_or_default();
+        history
+            .into_iter()
+            .map(|(path, entry)| (root.join(path), entry))
+    })
+    .collect()
+});
+pub fn other_git_history() -> &'static HashMap<PathBuf, HistoryEntry> {
+    &OTHER_GIT_HISTORY
+}
+
 pub static POPULARITIES: LazyLock<Popularities> = LazyLock::new(|| {
     let f = globals::data_dir()
         .join("popularities")
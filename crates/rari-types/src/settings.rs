@@ -1,10 +1,13 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
 
-use config::{Config, ConfigError, Environment, File};
+use config::builder::DefaultState;
+use config::{Config, ConfigBuilder, ConfigError, Environment, File};
 use semver::VersionReq;
 use serde::{Deserialize, Serialize};
 
 use crate::locale::Locale;
+use crate::templ::TemplPolicy;
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
@@ -21,6 +24,8 @@ pub struct Deps {
     pub web_specs: VersionReq,
     #[serde(rename = "@webref/css")]
     pub webref_css: VersionReq,
+    #[serde(rename = "@webref/idl")]
+    pub webref_idl: VersionReq,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -39,6 +44,19 @@ impl Deps {
             .expect("embedded deps pins (deps/package.json) must be valid")
             .dependencies
     }
+
+    /// The npm package names and pinned version ranges.
+    pub fn packages(&self) -> [(&'static str, &VersionReq); 7] {
+        [
+            ("@mdn/browser-compat-data", &self.bcd),
+            ("browser-specs", &self.browser_specs),
+            ("mdn-data", &self.mdn_data),
+            ("web-features", &self.web_features),
+            ("web-specs", &self.web_specs),
+            ("@webref/css", &self.webref_css),
+            ("@webref/idl", &self.webref_idl),
+        ]
+    }
 }
 
 impl Default for Deps {
@@ -47,6 +65,70 @@ impl Default for Deps {
     }
 }
 
+/// Where dependency data is downloaded from.
+///
+/// Each location may be a `file://` URL, so a mirror or a local directory of
+/// fixtures can stand in for the public services. Besides the config files,
+/// they are read from `RARI_`-prefixed environment variables, e.g.
+/// `RARI_NPM_REGISTRY_URL`.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct DepsSources {
+    /// Base URL of the npm registry.
+    pub npm_registry_url: String,
+    /// Base URL for raw files in GitHub repositories.
+    pub github_raw_url: String,
+    /// Location of the popularities CSV.
+    pub popularities_url: String,
+    /// Location of the web-features developer signals JSON.
+    pub developer_signals_url: String,
+}
+
+impl Default for DepsSources {
+    fn default() -> Self {
+        Self {
+            npm_registry_url: "https://registry.npmjs.org".to_string(),
+            github_raw_url: "https://raw.githubusercontent.com".to_string(),
+            popularities_url: "https://popularities.mdn.mozilla.net/current.csv".to_string(),
+            developer_signals_url:
+                "https://web-platform-dx.github.io/developer-signals/web-features-signals.json"
+                    .to_string(),
+        }
+    }
+}
+
+impl DepsSources {
+    /// Reads the sources from the same config files as [`Settings`] and
+    /// `RARI_`-prefixed environment variables, without requiring a content
+    /// root.
+    pub fn new() -> Result<Self, ConfigError> {
+        config_files()
+            .add_source(Environment::with_prefix("RARI").try_parsing(true))
+            .build()?
+            .try_deserialize()
+    }
+}
+
+fn config_files() -> ConfigBuilder<DefaultState> {
+    let config_dir = dirs::config_local_dir().map(|dir| dir.join("rari").join("config.toml"));
+    let mut s = Config::builder();
+    if let Some(config_dir) = config_dir {
+        s = s.add_source(File::from(config_dir).required(false));
+    }
+    s.add_source(File::with_name(".config.toml").required(false))
+}
+
+fn config() -> Result<Config, ConfigError> {
+    config_files()
+        .add_source(
+            Environment::default()
+                .list_separator(",")
+                .with_list_parse_key("additional_locales_for_generics_and_spas")
+                .try_parsing(true),
+        )
+        .build()
+}
+
 #[derive(Serialize, Deserialize, Default, Debug)]
 #[serde(default)]
 pub struct Settings {
@@ -67,8 +149,15 @@ pub struct Settings {
     pub data_issues: bool,
     pub json_issues: bool,
     pub json_live_samples: bool,
+    pub live_sample_pages: bool,
+    pub syntax_highlighting: bool,
+    pub check_code_syntax: bool,
+    pub check_css_values: bool,
     pub blog_unpublished: bool,
     pub blog_pagination: bool,
+    pub templ_policies: HashMap<String, TemplPolicy>,
+    #[serde(skip)]
+    pub deps_sources: DepsSources,
 }
 
 impl Settings {
@@ -119,22 +208,8 @@ impl Settings {
     }
 
     fn new_internal() -> Result<Self, ConfigError> {
-        let config_dir = dirs::config_local_dir().map(|dir| dir.join("rari").join("config.toml"));
-        let mut s = Config::builder();
-        if let Some(config_dir) = config_dir {
-            s = s.add_source(File::from(config_dir).required(false));
-        }
-        let s = s
-            .add_source(File::with_name(".config.toml").required(false))
-            .add_source(
-                Environment::default()
-                    .list_separator(",")
-                    .with_list_parse_key("additional_locales_for_generics_and_spas")
-                    .try_parsing(true),
-            )
-            .build()?;
-
-        let mut settings: Self = s.try_deserialize::<Self>()?.validate();
+        let mut settings: Self = config()?.try_deserialize::<Self>()?.validate();
+        settings.deps_sources = DepsSources::new()?;
         settings.blog_root = settings
             .blog_root
             .and_then(|br| br.parent().map(|p| p.to_path_buf()));
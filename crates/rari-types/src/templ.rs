@@ -1,6 +1,7 @@
 use darling::FromMeta;
 use proc_macro2::TokenStream;
 use quote::{ToTokens, quote};
+use serde::{Deserialize, Serialize};
 
 use crate::{Arg, RariEnv};
 
@@ -26,3 +27,26 @@ impl ToTokens for TemplType {
         tokens.extend(variant);
     }
 }
+
+/// What to do with a deprecated or removed templ instead of invoking it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum TemplPolicy {
+    /// Render nothing.
+    Drop,
+    /// Render `text`, or the first argument without `text`, as plain text.
+    Text {
+        #[serde(default)]
+        text: Option<String>,
+    },
+    /// Invoke the templ `with` instead.
+    ///
+    /// `args` are the arguments to pass: `$1` refers to the first original
+    /// argument and so on, anything else is passed as a string. Without
+    /// `args` the original arguments are passed through.
+    Replace {
+        with: String,
+        #[serde(default)]
+        args: Option<Vec<String>>,
+    },
+}